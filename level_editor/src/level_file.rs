@@ -0,0 +1,96 @@
+use std::path::PathBuf;
+
+use steks_common::prelude::*;
+
+const DEFAULT_PATH: &str = "level.yaml";
+
+/// A level within a yaml file of levels, in the same format as `levels.yaml`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelFile {
+    pub path: PathBuf,
+    pub index: usize,
+}
+
+impl LevelFile {
+    /// Usage: `level_editor [path] [index]`
+    pub fn from_args() -> Self {
+        let mut args = std::env::args().skip(1);
+        let path = args.next().unwrap_or_else(|| DEFAULT_PATH.to_string());
+        let index = args.next().and_then(|x| x.parse().ok()).unwrap_or_default();
+
+        Self {
+            path: path.into(),
+            index,
+        }
+    }
+
+    /// Load the level. Levels past the end of the file are new and empty.
+    pub fn load(&self) -> anyhow::Result<DesignedLevel> {
+        let levels = self.read_all()?;
+        Ok(levels.into_iter().nth(self.index).unwrap_or_default())
+    }
+
+    /// Save the level, keeping every other level in the file.
+    /// Note that comments in the file are not kept.
+    pub fn save(&self, level: &DesignedLevel) -> anyhow::Result<()> {
+        let mut levels = self.read_all()?;
+
+        match levels.get_mut(self.index) {
+            Some(existing) => *existing = level.clone(),
+            None => {
+                let index = levels.len();
+                if index != self.index {
+                    anyhow::bail!(
+                        "Cannot save level {} as the file only has {index} levels",
+                        self.index
+                    );
+                }
+                levels.push(level.clone());
+            }
+        }
+
+        std::fs::write(&self.path, levels_to_yaml(&levels)?)?;
+        Ok(())
+    }
+
+    pub fn read_all(&self) -> anyhow::Result<Vec<DesignedLevel>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        let data = std::fs::read_to_string(&self.path)?;
+        levels_from_yaml(&data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join("level_editor_test_save_and_load.yaml");
+        let _ = std::fs::remove_file(&path);
+
+        let mut level = DesignedLevel::default();
+        level.title = Some("Test".to_string());
+        level.initial_stage.shapes.push(ShapeCreation {
+            shape: LevelShapeForm::O4,
+            x: Some(10.0),
+            ..Default::default()
+        });
+
+        let file = LevelFile { path, index: 0 };
+        file.save(&level).unwrap();
+
+        assert_eq!(file.load().unwrap(), level);
+
+        let too_far = LevelFile {
+            index: 2,
+            ..file.clone()
+        };
+        assert!(too_far.save(&level).is_err());
+        assert_eq!(too_far.load().unwrap(), DesignedLevel::default());
+
+        std::fs::remove_file(&file.path).unwrap();
+    }
+}
@@ -1,3 +1,4 @@
+use bevy::input::keyboard::KeyboardInput;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy_prototype_lyon::prelude::*;
@@ -7,23 +8,32 @@ use steks_base::prelude::*;
 
 use strum::IntoEnumIterator;
 
+mod level_file;
+mod play_test;
+mod stages;
+use level_file::*;
+use play_test::*;
+use stages::*;
+
 fn main() {
     let mut app = App::new();
 
     app.add_plugins(DefaultPlugins)
-        .init_resource::<LevelState>()
+        .insert_resource(LevelState::load(LevelFile::from_args()))
         .init_resource::<UiState>()
         .add_systems(Startup, setup)
-        .add_systems(Update, button_system);
-
-    app.add_systems(Update, mousebutton_listener);
-    app.add_systems(Update, handle_drag_start.after(mousebutton_listener));
-
-    app.add_event::<DragStartEvent>();
-    app.add_event::<DragMoveEvent>();
-    app.add_event::<DragEndingEvent>();
+        .add_systems(Update, button_system)
+        .add_systems(Update, keyboard_shortcuts);
 
     app.insert_resource(Msaa::Sample4).add_plugins(ShapePlugin);
+    app.add_plugins(PlayTestPlugin);
+
+    app.add_systems(
+        Update,
+        handle_drag_start
+            .after(mousebutton_listener)
+            .run_if(is_editing),
+    );
 
     // app.register_transition::<StyleLeftLens>();
     // app.register_transition::<TransformScaleLens>();
@@ -43,12 +53,87 @@ pub struct UiState {
     pub spawn_shape: ShapeIndex,
     pub spawn_state: ShapeState,
     pub wand_type: Option<WandType>,
+    /// The stage being edited
+    pub stage: usize,
+    /// Show the world exactly as it will be at this stage
+    pub preview: bool,
+    /// The level is being played rather than edited
+    pub playing: bool,
 }
 
-#[derive(Debug, Resource, Default, MavericContext)]
+#[derive(Debug, Resource, MavericContext)]
 pub struct LevelState {
-    pub placed_shapes: Vec<EncodableShape>,
+    pub file: LevelFile,
+    pub level: DesignedLevel,
     //pub dragged_shape: Option<ShapeIndex>,
+    pub play_test: Option<PlayTestResult>,
+}
+
+impl LevelState {
+    pub fn load(file: LevelFile) -> Self {
+        let level = match file.load() {
+            Ok(level) => level,
+            Err(err) => {
+                error!("Could not load {}: {err}", file.path.display());
+                DesignedLevel::default()
+            }
+        };
+        info!("Editing level {} of {}", file.index, file.path.display());
+
+        Self {
+            file,
+            level,
+            play_test: None,
+        }
+    }
+
+    pub fn save(&self) {
+        match self.file.save(&self.level) {
+            Ok(()) => info!("Saved level {} to {}", self.file.index, self.file.path.display()),
+            Err(err) => error!("Could not save {}: {err}", self.file.path.display()),
+        }
+    }
+
+    pub fn shapes_mut(&mut self, stage: usize) -> Option<&mut Vec<ShapeCreation>> {
+        get_stage_mut(&mut self.level, stage).map(|x| &mut x.shapes)
+    }
+
+    /// Change a shape. Shapes from earlier stages are changed with a `ShapeUpdate`.
+    pub fn edit_shape(
+        &mut self,
+        stage: usize,
+        shape: &ResolvedShape,
+        edit_creation: impl FnOnce(&mut ShapeCreation),
+        edit_update: impl FnOnce(&mut ShapeUpdate),
+    ) {
+        if shape.created_stage == stage {
+            if let Some(creation) = self
+                .shapes_mut(stage)
+                .and_then(|shapes| shapes.get_mut(shape.index))
+            {
+                edit_creation(creation);
+            }
+        } else if let Some(id) = ensure_id(&mut self.level, shape.created_stage, shape.index) {
+            if let Some(update) =
+                get_update_mut(&mut self.level, stage, id, shape.creation.modifiers)
+            {
+                edit_update(update);
+            }
+        }
+    }
+}
+
+/// Where to draw a shape. Shapes without a position in the level are placed randomly in game,
+/// so they are lined up along the bottom.
+pub fn display_location(shape: &ShapeCreation, index: usize) -> Location {
+    let data = ShapeCreationData::from_shape_creation(*shape, ShapeStage(0));
+    data.location.unwrap_or_else(|| {
+        Location::new(
+            (index as f32 * SHAPE_SIZE * 2.0) - (MAX_WINDOW_WIDTH * 0.4),
+            MAX_WINDOW_HEIGHT * -0.4,
+            0.0,
+        )
+    })
 }
 
 impl Default for UiState {
@@ -57,6 +142,9 @@ impl Default for UiState {
             spawn_shape: ShapeIndex(0),
             spawn_state: ShapeState::Normal,
             wand_type: None,
+            stage: 0,
+            preview: false,
+            playing: false,
         }
     }
 }
@@ -65,7 +153,27 @@ impl Default for UiState {
 pub enum WandType {
     Delete,
     SetState(ShapeState),
+    SetForm(ShapeIndex),
+    SetColor(Option<(u8, u8, u8)>),
     PlaceShape(ShapeIndex),
+    /// Move a shape that has been picked up
+    MoveShape(ResolvedShape),
+}
+
+const COLORS: [(&str, Option<(u8, u8, u8)>); 4] = [
+    ("no color", None),
+    ("red", Some((200, 50, 50))),
+    ("green", Some((50, 200, 50))),
+    ("blue", Some((50, 50, 200))),
+];
+
+const STAGE_NAMES: [&str; 10] = [
+    "stage 1", "stage 2", "stage 3", "stage 4", "stage 5", "stage 6", "stage 7", "stage 8",
+    "stage 9", "stage 10",
+];
+
+fn is_editing(ui_state: Res<UiState>) -> bool {
+    !ui_state.playing
 }
 
 pub fn handle_drag_start(
@@ -97,45 +205,106 @@ pub fn handle_drag_start(
             }
         }
 
-        if let Some((index, shape)) = level_state
-            .placed_shapes
-            .iter()
-            .enumerate()
-            .filter(|(_, shape)| shape.contains_point(event.position))
-            .next()
-        {
-            if let Some(wand_type) = ui_state.wand_type {
-                match wand_type {
-                    WandType::Delete => {
-                        level_state.placed_shapes.remove(index);
-                    }
-                    WandType::SetState(state) => {
-                        level_state
-                            .placed_shapes
-                            .get_mut(index)
-                            .map(|x| x.state = state);
+        let stage = ui_state.stage;
+        let resolved = resolve_stage(&level_state.level, stage);
+
+        if let Some((display_index, shape)) = resolved.iter().enumerate().find(|(index, shape)| {
+            EncodableShape {
+                shape: shape.creation.shape.into(),
+                location: display_location(&shape.creation, *index),
+                state: shape.creation.state,
+                modifiers: shape.creation.modifiers,
+            }
+            .contains_point(event.position)
+        }) {
+            match ui_state.wand_type {
+                Some(WandType::Delete) => {
+                    if shape.created_stage == stage {
+                        if let Some(shapes) = level_state.shapes_mut(stage) {
+                            shapes.remove(shape.index);
+                        }
+                    } else {
+                        warn!("Shapes can only be deleted in the stage they are created");
                     }
-                    WandType::PlaceShape(_) => {}
                 }
-            } else {
-                ui_state.wand_type = Some(WandType::PlaceShape(shape.shape));
-                //level_state.dragged_shape = Some(shape.shape);
-                level_state.placed_shapes.remove(index);
+                Some(WandType::SetState(state)) => level_state.edit_shape(
+                    stage,
+                    shape,
+                    |x| x.state = state,
+                    |x| x.state = Some(state),
+                ),
+                Some(WandType::SetForm(form)) => level_state.edit_shape(
+                    stage,
+                    shape,
+                    |x| x.shape = form.into(),
+                    |x| x.shape = Some(form.into()),
+                ),
+                Some(WandType::SetColor(color)) => level_state.edit_shape(
+                    stage,
+                    shape,
+                    |x| x.color = color,
+                    |x| x.color = color,
+                ),
+                Some(WandType::PlaceShape(_)) | Some(WandType::MoveShape(_)) => {}
+                None => {
+                    // Pick the shape up so it can be put down somewhere else
+                    let mut shape = *shape;
+                    let location = display_location(&shape.creation, display_index);
+                    shape.creation.x = Some(location.position.x);
+                    shape.creation.y = Some(location.position.y);
+                    ui_state.wand_type = Some(WandType::MoveShape(shape));
+                }
             }
 
             continue 'events;
         }
 
-        if let Some(WandType::PlaceShape(shape)) = ui_state.wand_type {
-            level_state.placed_shapes.push(EncodableShape {
-                shape,
-                location: Location {
-                    position: event.position,
-                    angle: 0.0,
-                },
-                state: ShapeState::Normal,
-                modifiers: ShapeModifiers::Normal,
-            })
+        match ui_state.wand_type {
+            Some(WandType::PlaceShape(shape)) => {
+                if let Some(shapes) = level_state.shapes_mut(stage) {
+                    shapes.push(ShapeCreation {
+                        shape: shape.into(),
+                        x: Some(event.position.x),
+                        y: Some(event.position.y),
+                        r: Some(0.0),
+                        state: ShapeState::Normal,
+                        modifiers: ShapeModifiers::Normal,
+                        ..Default::default()
+                    })
+                }
+            }
+            Some(WandType::MoveShape(shape)) => {
+                let position = event.position;
+                level_state.edit_shape(
+                    stage,
+                    &shape,
+                    |x| {
+                        x.x = Some(position.x);
+                        x.y = Some(position.y);
+                    },
+                    |x| move_in_update(x, &shape, position.x, position.y),
+                );
+                ui_state.wand_type = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn keyboard_shortcuts(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    keys: Res<Input<KeyCode>>,
+    mut level_state: ResMut<LevelState>,
+) {
+    let control = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    for ev in keyboard_events.read() {
+        if !control || ev.state != bevy::input::ButtonState::Pressed {
+            continue;
+        }
+        match ev.key_code {
+            Some(KeyCode::S) => level_state.save(),
+            Some(KeyCode::O) => *level_state = LevelState::load(level_state.file.clone()),
+            _ => {}
         }
     }
 }
@@ -143,6 +312,8 @@ pub fn handle_drag_start(
 fn button_system(
     interaction_query: Query<(&Interaction, &ButtonMarker), (Changed<Interaction>, With<Button>)>,
     mut ui_state: ResMut<UiState>,
+    mut level_state: ResMut<LevelState>,
+    mut current_level: ResMut<CurrentLevel>,
 ) {
     for (interaction, marker) in interaction_query.iter() {
         if *interaction != Interaction::Pressed {
@@ -151,10 +322,15 @@ fn button_system(
 
         match marker {
             ButtonMarker::PreviousShape => {
-                ui_state.spawn_shape.0 = ui_state.spawn_shape.0.checked_sub(1).unwrap_or(20)
+                ui_state.spawn_shape.0 = ui_state
+                    .spawn_shape
+                    .0
+                    .checked_sub(1)
+                    .unwrap_or(ALL_SHAPES.len() as u8 - 1)
             }
             ButtonMarker::NextShape => {
-                ui_state.spawn_shape.0 = ui_state.spawn_shape.0.wrapping_add(1) % 21
+                ui_state.spawn_shape.0 =
+                    ui_state.spawn_shape.0.wrapping_add(1) % ALL_SHAPES.len() as u8
             }
             ButtonMarker::SpawnShape(shape) => {
                 ui_state.wand_type = Some(WandType::PlaceShape(*shape))
@@ -162,7 +338,54 @@ fn button_system(
             ButtonMarker::SetState(new_state) => {
                 ui_state.wand_type = Some(WandType::SetState(*new_state))
             }
+            ButtonMarker::SetForm => {
+                ui_state.wand_type = Some(WandType::SetForm(ui_state.spawn_shape))
+            }
+            ButtonMarker::SetColor(index) => {
+                ui_state.wand_type = Some(WandType::SetColor(COLORS[*index].1))
+            }
+            ButtonMarker::SelectStage(stage) => {
+                ui_state.stage = *stage;
+                ui_state.wand_type = None;
+            }
+            ButtonMarker::AddStage => {
+                level_state.level.stages.push(LevelStage::default());
+                ui_state.stage = level_state.level.stages.len();
+                ui_state.wand_type = None;
+            }
+            ButtonMarker::TogglePreview(_) => ui_state.preview = !ui_state.preview,
+            ButtonMarker::Play => {
+                ui_state.playing = true;
+                ui_state.wand_type = None;
+                *current_level = play_test_level(&level_state.level);
+            }
+            ButtonMarker::Stop => {
+                ui_state.playing = false;
+                *current_level = editing_level();
+            }
+            ButtonMarker::UsePlayTestStars => {
+                if let Some(play_test) = &level_state.play_test {
+                    level_state.level.stars = Some(play_test.candidate_stars());
+                }
+            }
             ButtonMarker::Delete => ui_state.wand_type = Some(WandType::Delete),
+            ButtonMarker::Save => level_state.save(),
+            ButtonMarker::Load => {
+                *level_state = LevelState::load(level_state.file.clone());
+                ui_state.stage = 0;
+            }
+            ButtonMarker::PreviousLevel => {
+                let mut file = level_state.file.clone();
+                file.index = file.index.saturating_sub(1);
+                *level_state = LevelState::load(file);
+                ui_state.stage = 0;
+            }
+            ButtonMarker::NextLevel => {
+                let mut file = level_state.file.clone();
+                file.index += 1;
+                *level_state = LevelState::load(file);
+                ui_state.stage = 0;
+            }
         }
     }
 }
@@ -177,8 +400,8 @@ impl MavericRootChildren for UIRoot {
         context: &<Self::Context as NodeContext>::Wrapper<'_>,
         commands: &mut impl ChildCommands,
     ) {
-        commands.add_child(0, UiPreview, &context.0);
-        commands.add_child(1, UiMenu, &context.1);
+        commands.add_child(0, UiPreview, context);
+        commands.add_child(1, UiMenu, context);
     }
 }
 
@@ -186,26 +409,37 @@ impl MavericRootChildren for UIRoot {
 struct UiPreview;
 
 impl MavericNode for UiPreview {
-    type Context = LevelState;
+    type Context = (LevelState, UiState);
 
     fn set_components(commands: SetComponentCommands<Self, Self::Context>) {
         commands
             .ignore_context()
             .ignore_node()
-            .insert(SpatialBundle::default())
-           ;
+            .insert(SpatialBundle::default());
     }
 
     fn set_children<R: MavericRoot>(commands: SetChildrenCommands<Self, Self::Context, R>) {
         commands
             .ignore_node()
             .ordered_children_with_context(|context, commands| {
-                for (index, shape) in context.placed_shapes.iter().enumerate() {
+                if context.1.playing {
+                    return;
+                }
+                let stage = context.1.stage;
+                for (index, shape) in resolve_stage(&context.0.level, stage).iter().enumerate() {
+                    let location = display_location(&shape.creation, index);
+                    // Outside of preview, shapes from earlier stages are faded
+                    let faded = !context.1.preview && shape.created_stage != stage;
                     commands.add_child(
                         index as u32,
                         PlacedShape {
-                            shape: *shape,
+                            shape: ShapeCreationData::from_shape_creation(
+                                shape.creation,
+                                ShapeStage(shape.created_stage),
+                            )
+                            .with_location(location.position, location.angle),
                             index,
+                            faded,
                         },
                         &(),
                     )
@@ -218,7 +452,7 @@ impl MavericNode for UiPreview {
 struct UiMenu;
 
 impl MavericNode for UiMenu {
-    type Context = UiState;
+    type Context = (LevelState, UiState);
 
     fn set_components(commands: SetComponentCommands<Self, Self::Context>) {
         commands.ignore_node().ignore_context().insert(NodeBundle {
@@ -241,9 +475,29 @@ impl MavericNode for UiMenu {
         commands
             .ignore_node()
             .ordered_children_with_context(|context, commands| {
+                let (level_state, ui_state) = (&context.0, &context.1);
+
+                if ui_state.playing {
+                    commands.add_child("stop", button_node(ButtonMarker::Stop), &());
+                    if let Some(play_test) = &level_state.play_test {
+                        commands.add_child("play_test", play_test_node(play_test), &());
+                    }
+                    return;
+                }
+
+                commands.add_child("play", button_node(ButtonMarker::Play), &());
+                if let Some(play_test) = &level_state.play_test {
+                    commands.add_child("play_test", play_test_node(play_test), &());
+                    commands.add_child(
+                        "use_play_test_stars",
+                        button_node(ButtonMarker::UsePlayTestStars),
+                        &(),
+                    );
+                }
+
                 commands.add_child(
                     "spawn",
-                    button_node(ButtonMarker::SpawnShape(context.spawn_shape)),
+                    button_node(ButtonMarker::SpawnShape(ui_state.spawn_shape)),
                     &(),
                 );
 
@@ -256,6 +510,38 @@ impl MavericNode for UiMenu {
                 }
 
                 commands.add_child("delete", button_node(ButtonMarker::Delete), &());
+                commands.add_child("set_form", button_node(ButtonMarker::SetForm), &());
+
+                for (index, (name, _)) in COLORS.iter().enumerate() {
+                    commands.add_child(*name, button_node(ButtonMarker::SetColor(index)), &());
+                }
+
+                let stages = (level_state.level.stages.len() + 1).min(STAGE_NAMES.len());
+                for stage in 0..stages {
+                    commands.add_child(
+                        STAGE_NAMES[stage],
+                        button_node(ButtonMarker::SelectStage(stage)),
+                        &(),
+                    );
+                }
+                if stages < STAGE_NAMES.len() {
+                    commands.add_child("add_stage", button_node(ButtonMarker::AddStage), &());
+                }
+
+                commands.add_child(
+                    "preview",
+                    button_node(ButtonMarker::TogglePreview(ui_state.preview)),
+                    &(),
+                );
+
+                commands.add_child("save", button_node(ButtonMarker::Save), &());
+                commands.add_child("load", button_node(ButtonMarker::Load), &());
+                commands.add_child(
+                    "prev_level",
+                    button_node(ButtonMarker::PreviousLevel),
+                    &(),
+                );
+                commands.add_child("next_level", button_node(ButtonMarker::NextLevel), &());
             });
     }
 }
@@ -267,6 +553,18 @@ pub enum ButtonMarker {
     SpawnShape(ShapeIndex),
     SetState(ShapeState),
     Delete,
+    SetForm,
+    SetColor(usize),
+    SelectStage(usize),
+    AddStage,
+    TogglePreview(bool),
+    Play,
+    Stop,
+    UsePlayTestStars,
+    Save,
+    Load,
+    PreviousLevel,
+    NextLevel,
 }
 
 pub fn button_node(marker: ButtonMarker) -> impl MavericNode<Context = ()> {
@@ -286,6 +584,19 @@ pub fn button_node(marker: ButtonMarker) -> impl MavericNode<Context = ()> {
             ShapeState::Void => "void",
         },
         ButtonMarker::Delete => "delete",
+        ButtonMarker::SetForm => "set form",
+        ButtonMarker::SetColor(index) => COLORS[index].0,
+        ButtonMarker::SelectStage(stage) => STAGE_NAMES[stage],
+        ButtonMarker::AddStage => "add stage",
+        ButtonMarker::TogglePreview(false) => "preview",
+        ButtonMarker::TogglePreview(true) => "stop preview",
+        ButtonMarker::Play => "play",
+        ButtonMarker::Stop => "stop",
+        ButtonMarker::UsePlayTestStars => "use for stars",
+        ButtonMarker::Save => "save",
+        ButtonMarker::Load => "load",
+        ButtonMarker::PreviousLevel => "previous level",
+        ButtonMarker::NextLevel => "next level",
     };
 
     ButtonNode {
@@ -305,10 +616,23 @@ pub fn button_node(marker: ButtonMarker) -> impl MavericNode<Context = ()> {
     }
 }
 
+/// The height of the best play test tower
+fn play_test_node(play_test: &PlayTestResult) -> impl MavericNode<Context = ()> {
+    TextNode {
+        text: format!("best {:.2}m", play_test.height),
+        font_size: BUTTON_FONT_SIZE,
+        color: BUTTON_TEXT_COLOR,
+        font: MENU_TEXT_FONT_PATH,
+        alignment: TextAlignment::Center,
+        linebreak_behavior: bevy::text::BreakLineOn::NoWrap,
+    }
+}
+
 #[derive(Debug, PartialEq)]
 struct PlacedShape {
-    pub shape: EncodableShape,
+    pub shape: ShapeCreationData,
     pub index: usize,
+    pub faded: bool,
 }
 
 #[derive(Debug, PartialEq, Component)]
@@ -319,10 +643,13 @@ impl MavericNode for PlacedShape {
 
     fn set_components(commands: SetComponentCommands<Self, Self::Context>) {
         commands.insert_with_node(|node| {
-            let scd = ShapeCreationData::from_encodable(node.shape, ShapeStage(0));
+            let scd = node.shape;
             //let shape = node.0.shape.game_shape();
-            let fill = scd.fill(false);
+            let mut fill = scd.fill(false);
             let stroke = scd.stroke(false);
+            if node.faded {
+                fill.color.set_a(0.3);
+            }
             let mut bundle = scd.shape.body.get_shape_bundle(SHAPE_SIZE);
             let Location { position, angle } = scd.location.unwrap_or_default();
 
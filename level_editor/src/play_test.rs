@@ -0,0 +1,184 @@
+use steks_base::prelude::window_size::WindowSizePlugin;
+use steks_base::prelude::*;
+
+use crate::{LevelState, UiState};
+
+/// Runs the level being edited with the game's own plugins
+pub struct PlayTestPlugin;
+
+impl Plugin for PlayTestPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(WallsPlugin)
+            .add_plugins(WindowSizePlugin::<SteksBreakpoints>::default())
+            .add_plugins(WindowSizeTrackingPlugin)
+            .add_plugins(SettingsPlugin)
+            .add_plugins(InputPlugin)
+            .add_plugins(HasActedPlugin)
+            .add_plugins(PadlockPlugin)
+            .add_plugins(CollisionPlugin)
+            // Records are not tracked so that play tests do not leak into the game
+            .init_resource::<WorldRecords>()
+            .init_resource::<PersonalBests>()
+            .insert_resource(Insets::default())
+            .insert_resource(Time::<Fixed>::from_seconds(SECONDS_PER_FRAME))
+            .insert_resource(RapierConfiguration {
+                gravity: GRAVITY,
+                timestep_mode: TimestepMode::Fixed {
+                    dt: SECONDS_PER_FRAME as f32,
+                    substeps: 1,
+                },
+                ..RapierConfiguration::default()
+            })
+            .add_plugins(
+                RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PHYSICS_SCALE)
+                    .in_fixed_schedule(),
+            )
+            .add_plugins(DragPlugin::<UiState>::default())
+            .add_plugins(UndoPlugin::<UiState>::default())
+            .add_plugins(WinPlugin::<UiState>::default())
+            // The current level is not tracked so the editor always starts out editing
+            .insert_resource(editing_level())
+            .add_systems(Update, adjust_gravity)
+            .add_systems(Update, record_play_test);
+    }
+}
+
+impl UITrait for UiState {
+    fn is_minimized(&self) -> bool {
+        true
+    }
+
+    fn minimize(&mut self) {}
+
+    fn on_level_complete(_m: &mut ResMut<Self>) {}
+}
+
+/// The best tower built while play testing this level
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayTestResult {
+    pub height: f32,
+    pub shapes: ShapesVec,
+}
+
+impl PlayTestResult {
+    /// Stars based on this tower. Three stars needs this height and two stars needs a little less.
+    pub fn candidate_stars(&self) -> LevelStars {
+        let three = self.height.floor();
+        LevelStars {
+            two: (three - 10.0).max(0.0),
+            three,
+            measure: StarMeasure::Height,
+        }
+    }
+}
+
+/// An empty level with no shapes, used while editing
+pub fn editing_level() -> CurrentLevel {
+    CurrentLevel::new(
+        GameLevel::Designed {
+            meta: DesignedLevelMeta::Custom {
+                level: DesignedLevel::default().into(),
+            },
+        },
+        LevelCompletion::Incomplete { stage: 0 },
+        None,
+    )
+}
+
+/// The level being edited, ready to play
+pub fn play_test_level(level: &DesignedLevel) -> CurrentLevel {
+    CurrentLevel::new(
+        GameLevel::Designed {
+            meta: DesignedLevelMeta::Custom {
+                level: level.clone().into(),
+            },
+        },
+        LevelCompletion::Incomplete { stage: 0 },
+        None,
+    )
+}
+
+fn adjust_gravity(level: Res<CurrentLevel>, mut rapier_config: ResMut<RapierConfiguration>) {
+    if level.is_changed() {
+        rapier_config.gravity = level.level.get_gravity(level.completion).unwrap_or(GRAVITY);
+    }
+}
+
+fn record_play_test(
+    current_level: Res<CurrentLevel>,
+    ui_state: Res<UiState>,
+    mut level_state: ResMut<LevelState>,
+) {
+    if !ui_state.playing || !current_level.is_changed() {
+        return;
+    }
+
+    let LevelCompletion::Complete { score_info } = current_level.completion else {
+        return;
+    };
+    let Some(shapes) = current_level.saved_data() else {
+        return;
+    };
+
+    if level_state
+        .play_test
+        .as_ref()
+        .is_some_and(|x| x.height >= score_info.height)
+    {
+        return;
+    }
+
+    info!("New best play test height {:.2}", score_info.height);
+    level_state.play_test = Some(PlayTestResult {
+        height: score_info.height,
+        shapes: shapes.clone(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_stars() {
+        let result = PlayTestResult {
+            height: 152.7,
+            shapes: ShapesVec::default(),
+        };
+
+        assert_eq!(
+            result.candidate_stars(),
+            LevelStars {
+                two: 142.0,
+                three: 152.0,
+                measure: StarMeasure::Height,
+            }
+        );
+    }
+
+    /// The editor does not add the plugins which only the game uses,
+    /// so the play test plugins must not depend on their resources or events
+    #[test]
+    fn test_play_test_plugin_runs() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::input::InputPlugin)
+            .add_plugins(bevy::window::WindowPlugin::default())
+            .add_plugins(TransformPlugin)
+            .add_plugins(HierarchyPlugin)
+            .init_asset::<Mesh>()
+            // Added by the render and ui plugins, which the editor has but this test does not
+            .init_resource::<ClearColor>()
+            .init_resource::<UiScale>()
+            .init_resource::<UiState>()
+            .insert_resource(LevelState::load(crate::LevelFile {
+                path: std::env::temp_dir().join("level_editor_test_play_test_plugin_runs.yaml"),
+                index: 0,
+            }))
+            .add_plugins(PlayTestPlugin);
+
+        app.update();
+        app.update();
+    }
+}
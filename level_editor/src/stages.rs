@@ -0,0 +1,172 @@
+use steks_common::prelude::*;
+
+/// A shape as it is at a particular stage, after all updates up to that stage
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResolvedShape {
+    /// The stage in which the shape was created
+    pub created_stage: usize,
+    /// The index of the shape in that stage's shapes
+    pub index: usize,
+    pub creation: ShapeCreation,
+}
+
+pub fn get_stage_mut(level: &mut DesignedLevel, stage: usize) -> Option<&mut LevelStage> {
+    match stage.checked_sub(1) {
+        Some(index) => level.stages.get_mut(index),
+        None => Some(&mut level.initial_stage),
+    }
+}
+
+/// All the shapes that exist at a stage, with updates applied
+pub fn resolve_stage(level: &DesignedLevel, stage: usize) -> Vec<ResolvedShape> {
+    let mut shapes: Vec<ResolvedShape> = vec![];
+
+    for (stage_index, level_stage) in level.all_stages().enumerate().take(stage + 1) {
+        for update in level_stage.updates.iter() {
+            for shape in shapes
+                .iter_mut()
+                .filter(|x| x.creation.id == Some(update.id))
+            {
+                shape.creation.apply_update(update);
+            }
+        }
+
+        shapes.extend(
+            level_stage
+                .shapes
+                .iter()
+                .enumerate()
+                .map(|(index, creation)| ResolvedShape {
+                    created_stage: stage_index,
+                    index,
+                    creation: *creation,
+                }),
+        );
+    }
+
+    shapes
+}
+
+pub fn next_free_id(level: &DesignedLevel) -> u32 {
+    level
+        .all_stages()
+        .flat_map(|stage| stage.shapes.iter())
+        .filter_map(|shape| shape.id)
+        .max()
+        .map(|x| x + 1)
+        .unwrap_or_default()
+}
+
+/// Get the id of a shape, giving it a new one if it does not have one
+pub fn ensure_id(level: &mut DesignedLevel, created_stage: usize, index: usize) -> Option<u32> {
+    let next_id = next_free_id(level);
+    let shape = get_stage_mut(level, created_stage)?.shapes.get_mut(index)?;
+
+    Some(*shape.id.get_or_insert(next_id))
+}
+
+/// Get the update for a shape in a stage, adding one if there isn't one already
+pub fn get_update_mut(
+    level: &mut DesignedLevel,
+    stage: usize,
+    id: u32,
+    current_modifiers: ShapeModifiers,
+) -> Option<&mut ShapeUpdate> {
+    let level_stage = get_stage_mut(level, stage)?;
+
+    let index = match level_stage.updates.iter().position(|x| x.id == id) {
+        Some(index) => index,
+        None => {
+            level_stage.updates.push(ShapeUpdate {
+                id,
+                // updates always set the modifiers so keep the current ones
+                modifiers: current_modifiers,
+                ..Default::default()
+            });
+            level_stage.updates.len() - 1
+        }
+    };
+
+    level_stage.updates.get_mut(index)
+}
+
+/// Move a shape in a later stage than it was created.
+/// Updates replace the whole location so the shape's rotation is set too.
+pub fn move_in_update(update: &mut ShapeUpdate, shape: &ResolvedShape, x: f32, y: f32) {
+    update.x = Some(x);
+    update.y = Some(y);
+    update.r = shape.creation.r;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_level() -> DesignedLevel {
+        let mut level = DesignedLevel::default();
+        level.initial_stage.shapes = vec![
+            ShapeCreation {
+                shape: LevelShapeForm::O4,
+                x: Some(10.0),
+                y: Some(20.0),
+                ..Default::default()
+            },
+            ShapeCreation {
+                shape: LevelShapeForm::L4,
+                ..Default::default()
+            },
+        ];
+        level.stages = vec![LevelStage::default(), LevelStage::default()];
+        level
+    }
+
+    #[test]
+    fn test_ensure_id() {
+        let mut level = test_level();
+
+        assert_eq!(ensure_id(&mut level, 0, 1), Some(0));
+        assert_eq!(ensure_id(&mut level, 0, 0), Some(1));
+        assert_eq!(ensure_id(&mut level, 0, 1), Some(0));
+        assert_eq!(ensure_id(&mut level, 0, 2), None);
+    }
+
+    #[test]
+    fn test_resolve_updates() {
+        let mut level = test_level();
+        let id = ensure_id(&mut level, 0, 0).unwrap();
+        let update = get_update_mut(&mut level, 2, id, ShapeModifiers::Normal).unwrap();
+        update.state = Some(ShapeState::Fixed);
+        update.x = Some(100.0);
+
+        level.stages[0].shapes.push(ShapeCreation {
+            shape: LevelShapeForm::I4,
+            ..Default::default()
+        });
+
+        let stage_zero = resolve_stage(&level, 0);
+        assert_eq!(stage_zero.len(), 2);
+        assert_eq!(stage_zero[0].creation.state, ShapeState::Normal);
+
+        let stage_one = resolve_stage(&level, 1);
+        assert_eq!(stage_one.len(), 3);
+        assert_eq!(stage_one[2].created_stage, 1);
+
+        let stage_two = resolve_stage(&level, 2);
+        assert_eq!(stage_two[0].creation.state, ShapeState::Fixed);
+        assert_eq!(stage_two[0].creation.x, Some(100.0));
+        // The game replaces the whole location when any part of it is updated
+        assert_eq!(stage_two[0].creation.y, Some(0.0));
+
+        level.initial_stage.shapes[1].r = Some(0.25);
+        let rotated = resolve_stage(&level, 1)[1];
+        let id = ensure_id(&mut level, 0, 1).unwrap();
+        let update = get_update_mut(&mut level, 1, id, ShapeModifiers::Normal).unwrap();
+        move_in_update(update, &rotated, 30.0, 40.0);
+
+        let moved = resolve_stage(&level, 1)[1].creation;
+        assert_eq!(
+            (moved.x, moved.y, moved.r),
+            (Some(30.0), Some(40.0), Some(0.25))
+        );
+    }
+}
@@ -21,8 +21,9 @@ fn main(){
         if let Some(stars) = level.stars{
             let two = stars.two;
             let three = stars.three;
+            let measure = stars.measure;
 
-            contents.push_str(format!("\n        {hash}=> Some(LevelStars{{two: {two:.2}, three: {three:.2}}}),").as_str());
+            contents.push_str(format!("\n        {hash}=> Some(LevelStars{{two: {two:.2}, three: {three:.2}, measure: StarMeasure::{measure:?}}}),").as_str());
         }
     }
 
@@ -37,19 +37,33 @@ pub(crate) async fn my_handler(
 
     let result_type: Command = Command::from_query_map(&e.payload.query_string_parameters);
 
-    let mut headers = HeaderMap::new();
-    headers.insert("Content-Type", result_type.content_type_header_value());
-
     let dimensions = dimensions_from_query_map(&e.payload.query_string_parameters);
 
-    let body = result_type.get_response_body(game, dimensions);
-
-    let resp = ApiGatewayProxyResponse {
-        status_code: 200,
-        headers,
-        multi_value_headers: HeaderMap::new(),
-        body: Some(body),
-        is_base64_encoded: result_type.is_base64_encoded(),
+    let resp = match result_type.get_response_body(game, dimensions) {
+        Ok(body) => {
+            let mut headers = HeaderMap::new();
+            headers.insert("Content-Type", result_type.content_type_header_value());
+
+            ApiGatewayProxyResponse {
+                status_code: 200,
+                headers,
+                multi_value_headers: HeaderMap::new(),
+                body: Some(body),
+                is_base64_encoded: result_type.is_base64_encoded(),
+            }
+        }
+        Err(err) => {
+            let mut headers = HeaderMap::new();
+            headers.insert("Content-Type", HeaderValue::from_static("text/plain"));
+
+            ApiGatewayProxyResponse {
+                status_code: 400,
+                headers,
+                multi_value_headers: HeaderMap::new(),
+                body: Some(Body::Text(err.to_string())),
+                is_base64_encoded: false,
+            }
+        }
     };
 
     Ok(resp)
@@ -61,7 +75,12 @@ pub enum Command {
     Default,
     NoOverlay,
     SVG,
-    //TODO level yaml
+    /// An animated gif of the tower being built
+    GIF,
+    /// An animated png of the tower being built
+    APNG,
+    /// A level for rebuilding the tower, which can be imported into the game
+    YAML,
 }
 
 impl Display for Command {
@@ -70,6 +89,9 @@ impl Display for Command {
             Command::Default => f.write_str("Default"),
             Command::NoOverlay => f.write_str("No_Overlay"),
             Command::SVG => f.write_str("SVG"),
+            Command::GIF => f.write_str("GIF"),
+            Command::APNG => f.write_str("APNG"),
+            Command::YAML => f.write_str("YAML"),
         }
     }
 }
@@ -91,6 +113,9 @@ impl Command {
             //spellchecker:disable-next-line
             "nooverlay" | "no_overlay" | "no-overlay" => Self::NoOverlay,
             "svg" => Self::SVG,
+            "gif" => Self::GIF,
+            "apng" => Self::APNG,
+            "yaml" | "yml" => Self::YAML,
             _ => Self::Default,
         }
     }
@@ -100,6 +125,9 @@ impl Command {
             Command::Default => true,
             Command::NoOverlay => true,
             Command::SVG => false,
+            Command::GIF => true,
+            Command::APNG => true,
+            Command::YAML => false,
         }
     }
 
@@ -108,18 +136,24 @@ impl Command {
             Command::Default => HeaderValue::from_static("image/png"),
             Command::NoOverlay => HeaderValue::from_static("image/png"),
             Command::SVG => HeaderValue::from_static("image/svg+xml"),
+            Command::GIF => HeaderValue::from_static("image/gif"),
+            Command::APNG => HeaderValue::from_static("image/apng"),
+            Command::YAML => HeaderValue::from_static("application/yaml"),
         }
     }
 
-    pub fn get_response_body(&self, game: &str, dimensions: Dimensions) -> Body {
-        let bytes = convert_game_to_bytes(game);
-        let height_and_stars = HeightAndStars::from_bytes(bytes.as_slice());
+    pub fn get_response_body(
+        &self,
+        game: &str,
+        dimensions: Dimensions,
+    ) -> Result<Body, anyhow::Error> {
+        let bytes = convert_game_to_bytes(game)?;
+        let height_and_stars = HeightAndStars::from_bytes(bytes.as_slice())?;
 
         match self {
             Command::Default => {
-                let data =
-                    try_draw_image(&bytes, &text_overlay(), dimensions, height_and_stars).unwrap();
-                Body::Binary(data)
+                let data = try_draw_image(&bytes, &text_overlay(), dimensions, height_and_stars)?;
+                Ok(Body::Binary(data))
             }
             Command::NoOverlay => {
                 let data = try_draw_image(
@@ -127,14 +161,28 @@ impl Command {
                     &OverlayChooser::no_overlay(),
                     dimensions,
                     height_and_stars,
-                )
-                .unwrap();
-                Body::Binary(data)
+                )?;
+                Ok(Body::Binary(data))
             }
             Command::SVG => {
-                let data = make_svg_from_bytes(bytes.as_slice(), dimensions);
+                let data = make_svg_from_bytes(bytes.as_slice(), dimensions)?;
+
+                Ok(Body::Text(data))
+            }
+            Command::GIF => {
+                let data = try_draw_gif(&bytes, &text_overlay(), dimensions, height_and_stars)?;
+                Ok(Body::Binary(data))
+            }
+            Command::APNG => {
+                let data = try_draw_apng(&bytes, &text_overlay(), dimensions, height_and_stars)?;
+                Ok(Body::Binary(data))
+            }
+            Command::YAML => {
+                let shapes = ShapesVec::from_bytes(bytes.as_slice())?;
+                let level = DesignedLevel::from_shared_shapes(&shapes);
+                let data = levels_to_yaml(&[level])?;
 
-                Body::Text(data)
+                Ok(Body::Text(data))
             }
         }
     }
@@ -174,18 +222,21 @@ struct HeightAndStars {
 }
 
 impl HeightAndStars {
-    pub fn from_bytes(bytes: &[u8]) -> Self {
-        let shapes_vec = ShapesVec::from_bytes(&bytes);
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ShareCodeError> {
+        let shapes_vec = ShapesVec::from_bytes(&bytes)?;
         let height = shapes_vec.calculate_tower_height();
         let hash = shapes_vec.hash();
 
-        let stars = get_level_stars(hash).map(|x|x.get_star(height));
+        // Only the height can be worked out from the shapes
+        let stars = get_level_stars(hash)
+            .filter(|x| x.measure.is_height())
+            .map(|x| x.get_star(&LevelScore { height, ..Default::default() }));
 
 
-        HeightAndStars {
+        Ok(HeightAndStars {
             height,
             stars,
-        }
+        })
     }
 }
 
@@ -246,15 +297,15 @@ fn modify_svg(mut tree: Tree, h: HeightAndStars) -> Tree {
     tree
 }
 
-pub fn convert_game_to_bytes(data: &str) -> Vec<u8> {
-    base64::engine::general_purpose::URL_SAFE
-        .decode(data)
-        .unwrap_or_default()
+pub fn convert_game_to_bytes(data: &str) -> Result<Vec<u8>, base64::DecodeError> {
+    base64::engine::general_purpose::URL_SAFE.decode(data)
 }
 
 #[cfg(test)]
 mod tests {
-    use steks_common::prelude::{choose_color, Location, ALL_SHAPES, SHAPE_SIZE};
+    use steks_common::prelude::{
+        choose_color, Location, ALL_SHAPES, CLASSIC_SHAPE_COUNT, SHAPE_SIZE,
+    };
 
     use crate::*;
     use std::hash::{Hash, Hasher};
@@ -280,12 +331,12 @@ mod tests {
             height = dimensions.height
         );
 
-        let bytes = convert_game_to_bytes(data);
-        let height_and_stars = HeightAndStars::from_bytes(bytes.as_slice());
+        let bytes = convert_game_to_bytes(data).unwrap();
+        let height_and_stars = HeightAndStars::from_bytes(bytes.as_slice()).unwrap();
 
         let hash: u64 = match command {
             Command::SVG => {
-                let svg: String = make_svg_from_bytes(&bytes, dimensions);
+                let svg: String = make_svg_from_bytes(&bytes, dimensions).unwrap();
                 let hash = calculate_hash(&svg);
                 std::fs::write(name.clone(), svg).unwrap();
                 hash
@@ -305,6 +356,10 @@ mod tests {
                 let hash = calculate_hash(&data);
                 hash
             }
+            Command::GIF | Command::APNG => {
+                unreachable!("Animations are checked by animation_test")
+            }
+            Command::YAML => unreachable!("Levels are checked by yaml_test"),
         };
 
         insta::assert_debug_snapshot!(name, hash);
@@ -329,6 +384,126 @@ mod tests {
         test_image(data, command, Dimensions { width, height });
     }
 
+    #[test_case("gif", 512, 512)]
+    #[test_case("apng", 512, 512)]
+    #[test_case("apng", 256, 512)]
+    fn animation_test(command: &'static str, width: u32, height: u32) {
+        let command = Command::from_str_ignore_case(command);
+        let dimensions = Dimensions { width, height };
+        let bytes = convert_game_to_bytes(TEST_DATA).unwrap();
+        let height_and_stars = HeightAndStars::from_bytes(bytes.as_slice()).unwrap();
+
+        let frames =
+            try_draw_frames(&bytes, &text_overlay(), dimensions, height_and_stars).unwrap();
+        let shape_count = ShapesVec::from_bytes(&bytes).unwrap().0.len();
+        assert_eq!(frames.len(), shape_count);
+
+        let body = command.get_response_body(TEST_DATA, dimensions).unwrap();
+        let Body::Binary(data) = body else {
+            panic!("{command} should be binary");
+        };
+
+        let signature: &[u8] = if command == Command::GIF {
+            b"GIF89a"
+        } else {
+            b"\x89PNG"
+        };
+        assert!(data.starts_with(signature), "{command} has the wrong signature");
+        if command == Command::APNG {
+            assert!(
+                data.windows(4).any(|x| x == b"acTL"),
+                "APNG should have an animation control chunk"
+            );
+        }
+
+        let len = data.len();
+        assert!(len < 3_000_000, "{command} is too big - {len} bytes");
+    }
+
+    #[test_case("gif")]
+    #[test_case("apng")]
+    fn large_animation_test(command: &'static str) {
+        let command = Command::from_str_ignore_case(command);
+        let dimensions = Dimensions {
+            width: 100_000,
+            height: 50_000,
+        };
+
+        let Body::Binary(data) = command.get_response_body(TEST_DATA, dimensions).unwrap() else {
+            panic!("{command} should be binary");
+        };
+
+        let (width, height) = if command == Command::GIF {
+            // The logical screen size follows the signature, as little endian u16s
+            (
+                u16::from_le_bytes([data[6], data[7]]) as u32,
+                u16::from_le_bytes([data[8], data[9]]) as u32,
+            )
+        } else {
+            // The IHDR chunk follows the signature, with big endian u32s
+            (
+                u32::from_be_bytes(data[16..20].try_into().unwrap()),
+                u32::from_be_bytes(data[20..24].try_into().unwrap()),
+            )
+        };
+        assert_eq!((width, height), (MAX_ANIMATION_SIZE, MAX_ANIMATION_SIZE / 2));
+    }
+
+    #[test]
+    fn many_shapes_animation_test() {
+        let shapes = ShapesVec(
+            (0..(MAX_ANIMATION_FRAMES * 2 + 1))
+                .map(|index| EncodableShape {
+                    shape: ShapeIndex(0),
+                    location: Location::new(0.0, index as f32 * SHAPE_SIZE, 0.0),
+                    state: ShapeState::Normal,
+                    modifiers: ShapeModifiers::Normal,
+                })
+                .collect(),
+        );
+        let bytes = shapes.make_bytes();
+        let height_and_stars = HeightAndStars::from_bytes(bytes.as_slice()).unwrap();
+        let dimensions = Dimensions {
+            width: 64,
+            height: 64,
+        };
+
+        let frames =
+            try_draw_frames(&bytes, &text_overlay(), dimensions, height_and_stars).unwrap();
+        assert!(frames.len() <= MAX_ANIMATION_FRAMES);
+        assert_eq!(
+            frame_shape_counts(shapes.0.len()).last(),
+            Some(shapes.0.len())
+        );
+    }
+
+    #[test_case("default")]
+    #[test_case("svg")]
+    #[test_case("gif")]
+    #[test_case("yaml")]
+    fn invalid_data_test(command: &'static str) {
+        let command = Command::from_str_ignore_case(command);
+
+        // Not base64
+        assert!(command.get_response_body("!!!", Dimensions::default()).is_err());
+        // A shape is cut off
+        assert!(command.get_response_body(&TEST_DATA[..20], Dimensions::default()).is_err());
+    }
+
+    #[test]
+    fn yaml_test() {
+        let Body::Text(yaml) = Command::YAML
+            .get_response_body(TEST_DATA, Dimensions::default())
+            .unwrap()
+        else {
+            panic!("YAML should be text");
+        };
+
+        let levels = levels_from_yaml(&yaml).unwrap();
+        let shapes = ShapesVec::from_bytes(&convert_game_to_bytes(TEST_DATA).unwrap()).unwrap();
+        assert_eq!(levels, vec![DesignedLevel::from_shared_shapes(&shapes)]);
+    }
+
     fn calculate_hash<T: Hash>(t: &T) -> u64 {
         let mut s = std::collections::hash_map::DefaultHasher::new();
         t.hash(&mut s);
@@ -341,7 +516,8 @@ mod tests {
 
         svg.push_str(r#"<svg width="500" height="500" xmlns="http://www.w3.org/2000/svg">"#);
 
-        for (index, shape) in ALL_SHAPES.iter().enumerate() {
+        // The grid only has room for the classic shapes
+        for (index, shape) in ALL_SHAPES.iter().take(*CLASSIC_SHAPE_COUNT).enumerate() {
             svg.push('\n');
 
             let x = ((index % 5) as f32 * 100.) + 50.;
@@ -381,7 +557,7 @@ mod tests {
         svg.push_str(r#"<svg width="500" height="1000" xmlns="http://www.w3.org/2000/svg">"#);
 
         for alt in [false, true] {
-            for (index, shape) in ALL_SHAPES.iter().enumerate() {
+            for (index, shape) in ALL_SHAPES.iter().take(*CLASSIC_SHAPE_COUNT).enumerate() {
                 svg.push('\n');
 
                 let x = ((index % 5) as f32 * 100.) + 50.;
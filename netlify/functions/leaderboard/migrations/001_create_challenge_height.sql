@@ -0,0 +1,10 @@
+-- Daily challenge records, keyed by date because different days can have the same shapes.
+-- Records made before this table existed stay in tower_height under the hash of the day's shapes
+-- and are returned for a date until that date has a record of its own.
+CREATE TABLE IF NOT EXISTS challenge_height (
+    challenge_date DATE NOT NULL,
+    shapes_hash BIGINT UNSIGNED NOT NULL,
+    max_height FLOAT NOT NULL,
+    image_blob TEXT NOT NULL,
+    PRIMARY KEY (challenge_date)
+);
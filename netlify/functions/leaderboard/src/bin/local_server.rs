@@ -0,0 +1,96 @@
+//! Serves the leaderboard API from memory so the game can be pointed at it during development.
+//! Build the game with `STEKS_LEADERBOARD_URL=http://localhost:8888` to use it.
+//! Run it with `cargo run --bin local_server --features local-server`.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use leaderboard::storage::MemoryStorage;
+use leaderboard::{handle_command, CORS_HEADERS};
+
+const DEFAULT_PORT: u16 = 8888;
+
+#[tokio::main]
+async fn main() -> Result<(), leaderboard::Error> {
+    let port = std::env::args()
+        .nth(1)
+        .map(|port| port.parse())
+        .transpose()?
+        .unwrap_or(DEFAULT_PORT);
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    let storage = Arc::new(MemoryStorage::default());
+
+    let make_service = make_service_fn(move |_| {
+        let storage = storage.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let storage = storage.clone();
+                async move { Ok::<_, Infallible>(handle(storage.as_ref(), request).await) }
+            }))
+        }
+    });
+
+    println!("Serving leaderboard on http://{address}");
+    Server::bind(&address).serve(make_service).await?;
+    Ok(())
+}
+
+async fn handle(storage: &MemoryStorage, request: Request<Body>) -> Response<Body> {
+    let parameters = parse_query(request.uri().query().unwrap_or_default());
+
+    let get_parameter = |name: &'static str| {
+        parameters
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    };
+
+    let mut builder = Response::builder();
+    for (key, value) in CORS_HEADERS {
+        builder = builder.header(key, value);
+    }
+
+    let response = match handle_command(storage, get_parameter).await {
+        Ok(response) => builder
+            .status(response.status_code)
+            .body(response.body.map(Body::from).unwrap_or_else(Body::empty)),
+        Err(err) => {
+            println!("Error: {err}");
+            builder
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::from(err.to_string()))
+        }
+    };
+
+    response.expect("Response should be valid")
+}
+
+/// The lambda receives its query parameters already percent-decoded, so decode them here too
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_parse_query_decodes_values() {
+        let parameters = parse_query("command=set&hash=123&blob=a%2Fb%3D&height=1.5");
+
+        assert_eq!(
+            parameters,
+            vec![
+                ("command".to_string(), "set".to_string()),
+                ("hash".to_string(), "123".to_string()),
+                ("blob".to_string(), "a/b=".to_string()),
+                ("height".to_string(), "1.5".to_string()),
+            ]
+        );
+    }
+}
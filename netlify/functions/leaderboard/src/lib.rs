@@ -0,0 +1,321 @@
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate};
+use itertools::Itertools;
+use steks_common::prelude::ShapesVec;
+
+pub mod storage;
+pub mod verification;
+
+pub use lambda_runtime::Error;
+use storage::*;
+
+/// A response which does not depend on how the request arrived
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeaderboardResponse {
+    pub status_code: u16,
+    pub body: Option<String>,
+}
+
+impl LeaderboardResponse {
+    fn ok(status_code: u16, body: Option<String>) -> Self {
+        Self { status_code, body }
+    }
+}
+
+pub const CORS_HEADERS: [(&str, &str); 3] = [
+    ("Access-Control-Allow-Origin", "*"),
+    ("Access-Control-Allow-Headers", "*"),
+    ("Access-Control-Allow-Methods", "GET, POST"),
+];
+
+pub async fn handle_command<'a>(
+    storage: &impl LeaderboardStorage,
+    get_parameter: impl Fn(&'static str) -> Option<&'a str>,
+) -> Result<LeaderboardResponse, Error> {
+    let command = get_parameter("command").ok_or_else(|| "Could not get command")?;
+    let command: Command = command.parse()?;
+
+    match command {
+        Command::Get => {
+            let rows = storage.get_all().await?;
+            let data = Itertools::join(&mut rows.into_iter(), " ");
+
+            Ok(LeaderboardResponse::ok(200, Some(data)))
+        }
+        Command::GetRow => {
+            if let Some(date) = get_date(&get_parameter)? {
+                let row = match storage.get_challenge_row(date).await? {
+                    Some(row) => Some(row),
+                    // Challenge records used to be kept by hash with everything else
+                    None => storage.get_row(legacy_challenge_hash(date)).await?,
+                };
+                let row = row.unwrap_or(FullRow {
+                    shapes_hash: 0,
+                    max_height: 0.0,
+                    image_blob: "0".to_string(),
+                });
+
+                return Ok(LeaderboardResponse::ok(200, Some(row.to_string())));
+            }
+
+            let shapes_hash = get_parameter("hash").ok_or_else(|| "Could not get hash")?;
+            let shapes_hash: u64 = shapes_hash.parse()?;
+
+            let row = storage.get_row(shapes_hash).await?.unwrap_or(FullRow {
+                shapes_hash,
+                max_height: 0.0,
+                image_blob: "0".to_string(),
+            });
+
+            Ok(LeaderboardResponse::ok(200, Some(row.to_string())))
+        }
+        Command::TrySet => {
+            let hash = get_parameter("hash").ok_or_else(|| "Could not get hash")?;
+            let hash: u64 = hash.parse()?;
+            let height = get_parameter("height").ok_or_else(|| "Could not get height")?;
+            let height: f32 = height.parse()?;
+            let blob = get_parameter("blob").ok_or_else(|| "Could not get blob")?;
+
+            let date = get_date(&get_parameter)?;
+
+            let verified =
+                verification::verify_submission(hash, height, blob).and_then(|shapes| match date {
+                    Some(date) => verification::verify_challenge(&shapes, date),
+                    None => Ok(()),
+                });
+
+            if let Err(rejection) = verified {
+                println!("Rejected tower {hash}: {rejection}");
+                return Ok(LeaderboardResponse::ok(422, Some(rejection.to_string())));
+            }
+
+            match date {
+                Some(date) => storage.try_set_challenge(date, hash, height, blob).await?,
+                None => storage.try_set(hash, height, blob).await?,
+            }
+            Ok(LeaderboardResponse::ok(202, None))
+        }
+    }
+}
+
+/// Daily challenge records are keyed by date rather than by hash
+fn get_date<'a>(
+    get_parameter: &impl Fn(&'static str) -> Option<&'a str>,
+) -> Result<Option<NaiveDate>, Error> {
+    match get_parameter("date") {
+        Some(date) => Ok(Some(date.parse()?)),
+        None => Ok(None),
+    }
+}
+
+fn legacy_challenge_hash(date: NaiveDate) -> u64 {
+    ShapesVec::challenge_hash(date.year().unsigned_abs(), date.month(), date.day())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Get,
+    GetRow,
+    TrySet,
+}
+
+impl FromStr for Command {
+    type Err = &'static str;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("get") {
+            Ok(Self::Get)
+        } else if value.eq_ignore_ascii_case("getrow") {
+            Ok(Self::GetRow)
+        } else if value.eq_ignore_ascii_case("set") {
+            Ok(Self::TrySet)
+        } else {
+            Err("Could not parse command")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use steks_common::prelude::*;
+
+    fn run(storage: &MemoryStorage, query: &[(&'static str, String)]) -> LeaderboardResponse {
+        let get_parameter = |name: &'static str| {
+            query
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        };
+
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(handle_command(storage, get_parameter))
+            .unwrap()
+    }
+
+    #[test]
+    pub fn test_set_and_get_row() {
+        let storage = MemoryStorage::default();
+        let shapes = ShapesVec(vec![EncodableShape {
+            shape: GameShape::by_name("O4").unwrap().index,
+            location: Location::new(100.0, 100.0, 0.0),
+            state: ShapeState::Normal,
+            modifiers: ShapeModifiers::Normal,
+        }]);
+        let hash = shapes.hash();
+        let height: f32 = format!("{:.2}", shapes.calculate_tower_height())
+            .parse()
+            .unwrap();
+        let blob = shapes.make_base64_data();
+
+        let missing = run(
+            &storage,
+            &[("command", "getrow".to_string()), ("hash", hash.to_string())],
+        );
+        assert_eq!(missing.body, Some(format!("{hash} 0 0")));
+
+        let set = run(
+            &storage,
+            &[
+                ("command", "set".to_string()),
+                ("hash", hash.to_string()),
+                ("height", height.to_string()),
+                ("blob", blob.clone()),
+            ],
+        );
+        assert_eq!(set.status_code, 202);
+
+        let found = run(
+            &storage,
+            &[("command", "getrow".to_string()), ("hash", hash.to_string())],
+        );
+        assert_eq!(found.body, Some(format!("{hash} {height} {blob}")));
+    }
+
+    /// The shapes of the 2023-12-25 challenge, side by side
+    fn challenge_shapes() -> ShapesVec {
+        ShapesVec(
+            ShapeIndex::challenge_shapes(2023, 12, 25)
+                .into_iter()
+                .enumerate()
+                .map(|(index, shape)| EncodableShape {
+                    shape,
+                    location: Location::new(index as f32 * 250.0, 0.0, 0.0),
+                    state: ShapeState::Normal,
+                    modifiers: ShapeModifiers::Normal,
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    pub fn test_challenges_are_keyed_by_date() {
+        let storage = MemoryStorage::default();
+        let shapes = challenge_shapes();
+        let hash = shapes.hash();
+        let height: f32 = format!("{:.2}", shapes.calculate_tower_height())
+            .parse()
+            .unwrap();
+        let blob = shapes.make_base64_data();
+
+        let set = run(
+            &storage,
+            &[
+                ("command", "set".to_string()),
+                ("hash", hash.to_string()),
+                ("height", height.to_string()),
+                ("blob", blob.clone()),
+                ("date", "2023-12-25".to_string()),
+            ],
+        );
+        assert_eq!(set.status_code, 202);
+
+        let found = run(
+            &storage,
+            &[
+                ("command", "getrow".to_string()),
+                ("date", "2023-12-25".to_string()),
+            ],
+        );
+        assert_eq!(found.body, Some(format!("{hash} {height} {blob}")));
+
+        let other_day = run(
+            &storage,
+            &[
+                ("command", "getrow".to_string()),
+                ("date", "2023-12-26".to_string()),
+            ],
+        );
+        assert_eq!(other_day.body, Some("0 0 0".to_string()));
+
+        let by_hash = run(
+            &storage,
+            &[("command", "getrow".to_string()), ("hash", hash.to_string())],
+        );
+        assert_eq!(by_hash.body, Some(format!("{hash} 0 0")));
+    }
+
+    #[test]
+    pub fn test_challenge_must_use_the_days_shapes() {
+        let storage = MemoryStorage::default();
+        let shapes = challenge_shapes();
+        let height: f32 = format!("{:.2}", shapes.calculate_tower_height())
+            .parse()
+            .unwrap();
+
+        let set = run(
+            &storage,
+            &[
+                ("command", "set".to_string()),
+                ("hash", shapes.hash().to_string()),
+                ("height", height.to_string()),
+                ("blob", shapes.make_base64_data()),
+                ("date", "2023-12-26".to_string()),
+            ],
+        );
+        assert_eq!(set.status_code, 422);
+
+        let found = run(
+            &storage,
+            &[
+                ("command", "getrow".to_string()),
+                ("date", "2023-12-26".to_string()),
+            ],
+        );
+        assert_eq!(found.body, Some("0 0 0".to_string()));
+    }
+
+    #[test]
+    pub fn test_challenge_falls_back_to_legacy_hash() {
+        let storage = MemoryStorage::default();
+        let shapes = challenge_shapes();
+        let hash = shapes.hash();
+        let height: f32 = format!("{:.2}", shapes.calculate_tower_height())
+            .parse()
+            .unwrap();
+        let blob = shapes.make_base64_data();
+
+        let set = run(
+            &storage,
+            &[
+                ("command", "set".to_string()),
+                ("hash", hash.to_string()),
+                ("height", height.to_string()),
+                ("blob", blob.clone()),
+            ],
+        );
+        assert_eq!(set.status_code, 202);
+
+        let found = run(
+            &storage,
+            &[
+                ("command", "getrow".to_string()),
+                ("date", "2023-12-25".to_string()),
+            ],
+        );
+        assert_eq!(found.body, Some(format!("{hash} {height} {blob}")));
+    }
+}
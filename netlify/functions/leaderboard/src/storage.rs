@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use planetscale_driver::PSConnection;
+use planetscale_driver::{query, Database};
+
+/// Somewhere to keep the best tower for each level
+#[async_trait]
+pub trait LeaderboardStorage: Send + Sync {
+    async fn get_all(&self) -> anyhow::Result<Vec<MiniRow>>;
+
+    async fn get_row(&self, shapes_hash: u64) -> anyhow::Result<Option<FullRow>>;
+
+    /// Store the tower if it is higher than the current best
+    async fn try_set(&self, shapes_hash: u64, max_height: f32, image_blob: &str)
+        -> anyhow::Result<()>;
+
+    async fn get_challenge_row(&self, date: NaiveDate) -> anyhow::Result<Option<FullRow>>;
+
+    /// Store the tower if it is higher than the current best for that day's challenge
+    async fn try_set_challenge(
+        &self,
+        date: NaiveDate,
+        shapes_hash: u64,
+        max_height: f32,
+        image_blob: &str,
+    ) -> anyhow::Result<()>;
+}
+
+#[derive(Debug, Clone, Copy, Database, PartialEq)]
+pub struct MiniRow {
+    pub shapes_hash: u64,
+    pub max_height: f32,
+}
+
+impl Display for MiniRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{} {}", self.shapes_hash, self.max_height))
+    }
+}
+
+#[derive(Debug, Clone, Database, PartialEq)]
+pub struct FullRow {
+    pub shapes_hash: u64,
+    pub max_height: f32,
+    pub image_blob: String,
+}
+
+impl Display for FullRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{} {} {}",
+            self.shapes_hash, self.max_height, self.image_blob
+        ))
+    }
+}
+
+pub struct PlanetScaleStorage {
+    host: String,
+    username: String,
+    password: String,
+}
+
+impl PlanetScaleStorage {
+    pub fn from_env() -> Self {
+        let host = env::var("DATABASE_HOST").expect("DATABASE_HOST not found");
+        let username = env::var("DATABASE_USERNAME").expect("DATABASE_USERNAME not found");
+        let password = env::var("DATABASE_PASSWORD").expect("DATABASE_PASSWORD not found");
+
+        Self {
+            host,
+            username,
+            password,
+        }
+    }
+
+    fn connect(&self) -> PSConnection {
+        PSConnection::new(
+            self.host.as_str(),
+            self.username.as_str(),
+            self.password.as_str(),
+        )
+    }
+}
+
+#[async_trait]
+impl LeaderboardStorage for PlanetScaleStorage {
+    async fn get_all(&self) -> anyhow::Result<Vec<MiniRow>> {
+        let mut connection = self.connect();
+
+        query("select shapes_hash, max_height FROM tower_height;")
+            .fetch_all(&mut connection)
+            .await
+    }
+
+    async fn get_row(&self, shapes_hash: u64) -> anyhow::Result<Option<FullRow>> {
+        let connection = self.connect();
+
+        let row_result: anyhow::Result<FullRow> = query("select shapes_hash, max_height, image_blob FROM tower_height where shapes_hash = $0;")
+            .bind(shapes_hash)
+            .fetch_one(&connection)
+            .await;
+
+        match row_result {
+            Ok(row) => Ok(Some(row)),
+            Err(err) => {
+                if err.to_string().contains("No results found") {
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    async fn try_set(
+        &self,
+        shapes_hash: u64,
+        max_height: f32,
+        image_blob: &str,
+    ) -> anyhow::Result<()> {
+        let mut connection = self.connect();
+
+        query(
+            "
+            Insert into tower_height (shapes_hash, max_height, image_blob) Values($0, $1, \"$2\")
+            ON DUPLICATE KEY UPDATE
+            max_height = IF (max_height > $1, max_height, $1),
+            image_blob = IF (max_height > $1, image_blob, \"$2\");
+            ",
+        )
+        .bind(shapes_hash)
+        .bind(max_height)
+        .bind(image_blob)
+        .execute(&mut connection)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_challenge_row(&self, date: NaiveDate) -> anyhow::Result<Option<FullRow>> {
+        let connection = self.connect();
+
+        let row_result: anyhow::Result<FullRow> = query("select shapes_hash, max_height, image_blob FROM challenge_height where challenge_date = \"$0\";")
+            .bind(date)
+            .fetch_one(&connection)
+            .await;
+
+        match row_result {
+            Ok(row) => Ok(Some(row)),
+            Err(err) => {
+                if err.to_string().contains("No results found") {
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    async fn try_set_challenge(
+        &self,
+        date: NaiveDate,
+        shapes_hash: u64,
+        max_height: f32,
+        image_blob: &str,
+    ) -> anyhow::Result<()> {
+        let mut connection = self.connect();
+
+        query(
+            "
+            Insert into challenge_height (challenge_date, shapes_hash, max_height, image_blob) Values(\"$0\", $1, $2, \"$3\")
+            ON DUPLICATE KEY UPDATE
+            shapes_hash = IF (max_height > $2, shapes_hash, $1),
+            image_blob = IF (max_height > $2, image_blob, \"$3\"),
+            max_height = IF (max_height > $2, max_height, $2);
+            ",
+        )
+        .bind(date)
+        .bind(shapes_hash)
+        .bind(max_height)
+        .bind(image_blob)
+        .execute(&mut connection)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Keeps everything in memory. Useful for local development and tests.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    rows: Mutex<HashMap<u64, FullRow>>,
+    challenge_rows: Mutex<HashMap<NaiveDate, FullRow>>,
+}
+
+#[async_trait]
+impl LeaderboardStorage for MemoryStorage {
+    async fn get_all(&self) -> anyhow::Result<Vec<MiniRow>> {
+        let rows = self.rows.lock().map_err(|err| anyhow::anyhow!("{err}"))?;
+
+        Ok(rows
+            .values()
+            .map(|row| MiniRow {
+                shapes_hash: row.shapes_hash,
+                max_height: row.max_height,
+            })
+            .collect())
+    }
+
+    async fn get_row(&self, shapes_hash: u64) -> anyhow::Result<Option<FullRow>> {
+        let rows = self.rows.lock().map_err(|err| anyhow::anyhow!("{err}"))?;
+        Ok(rows.get(&shapes_hash).cloned())
+    }
+
+    async fn try_set(
+        &self,
+        shapes_hash: u64,
+        max_height: f32,
+        image_blob: &str,
+    ) -> anyhow::Result<()> {
+        let mut rows = self.rows.lock().map_err(|err| anyhow::anyhow!("{err}"))?;
+
+        match rows.get(&shapes_hash) {
+            Some(existing) if existing.max_height > max_height => {}
+            _ => {
+                rows.insert(
+                    shapes_hash,
+                    FullRow {
+                        shapes_hash,
+                        max_height,
+                        image_blob: image_blob.to_string(),
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_challenge_row(&self, date: NaiveDate) -> anyhow::Result<Option<FullRow>> {
+        let rows = self
+            .challenge_rows
+            .lock()
+            .map_err(|err| anyhow::anyhow!("{err}"))?;
+        Ok(rows.get(&date).cloned())
+    }
+
+    async fn try_set_challenge(
+        &self,
+        date: NaiveDate,
+        shapes_hash: u64,
+        max_height: f32,
+        image_blob: &str,
+    ) -> anyhow::Result<()> {
+        let mut rows = self
+            .challenge_rows
+            .lock()
+            .map_err(|err| anyhow::anyhow!("{err}"))?;
+
+        match rows.get(&date) {
+            Some(existing) if existing.max_height > max_height => {}
+            _ => {
+                rows.insert(
+                    date,
+                    FullRow {
+                        shapes_hash,
+                        max_height,
+                        image_blob: image_blob.to_string(),
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt::Display;
+
+use base64::Engine;
+use chrono::{Datelike, NaiveDate};
+use steks_common::prelude::*;
+
+/// Heights are sent with two decimal places
+const HEIGHT_TOLERANCE: f32 = 0.01;
+/// Shapes resting on each other can appear to overlap slightly because positions and angles are rounded when encoded
+const OVERLAP_TOLERANCE: f32 = 5.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rejection {
+    InvalidBlob { reason: String },
+    HashMismatch { claimed: u64, actual: u64 },
+    HeightMismatch { claimed: f32, actual: f32 },
+    OverlappingShapes { first: usize, second: usize, depth: f32 },
+    NotChallengeShapes { date: NaiveDate },
+}
+
+impl Rejection {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Rejection::InvalidBlob { .. } => "invalid_blob",
+            Rejection::HashMismatch { .. } => "hash_mismatch",
+            Rejection::HeightMismatch { .. } => "height_mismatch",
+            Rejection::OverlappingShapes { .. } => "overlapping_shapes",
+            Rejection::NotChallengeShapes { .. } => "not_challenge_shapes",
+        }
+    }
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let code = self.code();
+        match self {
+            Rejection::InvalidBlob { reason } => write!(f, "{code}: {reason}"),
+            Rejection::HashMismatch { claimed, actual } => {
+                write!(f, "{code}: claimed {claimed} but blob has {actual}")
+            }
+            Rejection::HeightMismatch { claimed, actual } => {
+                write!(f, "{code}: claimed {claimed:.2} but blob has {actual:.2}")
+            }
+            Rejection::OverlappingShapes {
+                first,
+                second,
+                depth,
+            } => write!(f, "{code}: shapes {first} and {second} overlap by {depth:.2}"),
+            Rejection::NotChallengeShapes { date } => {
+                write!(f, "{code}: shapes are not the challenge for {date}")
+            }
+        }
+    }
+}
+
+/// Check that a submitted tower is what the client says it is
+pub fn verify_submission(hash: u64, height: f32, blob: &str) -> Result<ShapesVec, Rejection> {
+    let bytes = base64::engine::general_purpose::URL_SAFE
+        .decode(blob)
+        .map_err(|err| Rejection::InvalidBlob {
+            reason: err.to_string(),
+        })?;
+
+    let shapes = ShapesVec::from_bytes(&bytes).map_err(|err| Rejection::InvalidBlob {
+        reason: err.to_string(),
+    })?;
+
+    let actual_hash = shapes.hash();
+    if actual_hash != hash {
+        return Err(Rejection::HashMismatch {
+            claimed: hash,
+            actual: actual_hash,
+        });
+    }
+
+    let actual_height = shapes.calculate_tower_height();
+    if (actual_height - height).abs() > HEIGHT_TOLERANCE {
+        return Err(Rejection::HeightMismatch {
+            claimed: height,
+            actual: actual_height,
+        });
+    }
+
+    if let Some((first, second, depth)) = shapes.find_overlapping_shapes(OVERLAP_TOLERANCE) {
+        return Err(Rejection::OverlappingShapes {
+            first,
+            second,
+            depth,
+        });
+    }
+
+    Ok(shapes)
+}
+
+/// Check that a submitted tower uses the shapes of the daily challenge it claims to be for
+pub fn verify_challenge(shapes: &ShapesVec, date: NaiveDate) -> Result<(), Rejection> {
+    if shapes.is_challenge(date.year().unsigned_abs(), date.month(), date.day()) {
+        Ok(())
+    } else {
+        Err(Rejection::NotChallengeShapes { date })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // spell-checker: disable-next-line
+    const TEST_DATA: &str = "CQCBGHvzog4AfvB2ZysEAILnjfABCACDxpPAAhMAfnVw1uAFAHw-bLbnAgCCyZ2xPgwAhV6IVCoJAIOygSikCzCOqo__PAMgeVRpVOkCIIv_i1Q8";
+
+    fn test_shapes() -> ShapesVec {
+        let bytes = base64::engine::general_purpose::URL_SAFE
+            .decode(TEST_DATA)
+            .unwrap();
+        ShapesVec::from_bytes(&bytes).unwrap()
+    }
+
+    #[test]
+    pub fn test_valid_submission() {
+        let shapes = test_shapes();
+        let height = (shapes.calculate_tower_height() * 100.0).round() / 100.0;
+
+        let result = verify_submission(shapes.hash(), height, TEST_DATA);
+        assert_eq!(result, Ok(shapes));
+    }
+
+    #[test]
+    pub fn test_wrong_hash() {
+        let shapes = test_shapes();
+        let result = verify_submission(
+            shapes.hash() + 1,
+            shapes.calculate_tower_height(),
+            TEST_DATA,
+        );
+
+        assert!(matches!(result, Err(Rejection::HashMismatch { .. })));
+    }
+
+    #[test]
+    pub fn test_wrong_height() {
+        let shapes = test_shapes();
+        let result = verify_submission(
+            shapes.hash(),
+            shapes.calculate_tower_height() + 10.0,
+            TEST_DATA,
+        );
+
+        assert!(matches!(result, Err(Rejection::HeightMismatch { .. })));
+    }
+
+    #[test]
+    pub fn test_wrong_challenge() {
+        let shapes = test_shapes();
+        let date = NaiveDate::from_ymd_opt(2023, 12, 25).unwrap();
+
+        let result = verify_challenge(&shapes, date);
+
+        assert_eq!(result, Err(Rejection::NotChallengeShapes { date }));
+    }
+
+    #[test]
+    pub fn test_invalid_blob() {
+        let result = verify_submission(0, 0.0, "not a blob!");
+
+        assert!(matches!(result, Err(Rejection::InvalidBlob { .. })));
+    }
+}
@@ -268,7 +268,7 @@ impl Achievement {
 fn track_level_completion_achievements(
     current_level: Res<CurrentLevel>,
     mut achievements: ResMut<Achievements>,
-    shapes_query: Query<(&ShapeIndex, &Transform, &ShapeComponent, &Friction)>,
+    shapes_query: Query<(&ShapeIndex, &Transform, &ShapeComponent, &ShapeModifiers)>,
 ) {
     use Achievement::*;
     use DesignedLevelMeta::*;
@@ -18,6 +18,7 @@ fn icon_button_system(
     >,
     mut change_level_events: EventWriter<ChangeLevelEvent>,
     mut share_events: EventWriter<ShareEvent>,
+    mut undo_events: EventWriter<UndoEvent>,
     mut global_ui_state: ResMut<GlobalUiState>,
     mut settings: ResMut<GameSettings>,
     current_level: Res<CurrentLevel>,
@@ -46,6 +47,8 @@ fn icon_button_system(
                 Share => share_events.send(ShareEvent::CurrentShapes),
                 SharePB => share_events.send(ShareEvent::PersonalBest),
                 NextLevel => change_level_events.send(ChangeLevelEvent::Next),
+                RetryLevel => change_level_events.send(ChangeLevelEvent::ResetLevel),
+                Undo => undo_events.send(UndoEvent),
                 OpenNews => {
                     news.is_read = true;
                     *global_ui_state = GlobalUiState::News;
@@ -85,7 +88,7 @@ fn icon_button_system(
                 RefreshWR => {
                     if let LevelCompletion::Complete { score_info } = current_level.completion {
                         crate::leaderboard::refresh_wr_data(
-                            score_info.hash,
+                            RecordKey::new(&current_level.level, score_info.hash),
                             leaderboard_data_event_writer.clone(),
                         )
                     }
@@ -223,6 +226,12 @@ fn text_button_system(
                 TextButton::Tutorial => change_level_events
                     .send(ChangeLevelEvent::ChooseTutorialLevel { index: 0, stage: 0 }),
                 TextButton::Infinite => change_level_events.send(ChangeLevelEvent::StartInfinite),
+                TextButton::ChooseSeed => global_ui_state.as_mut().open_seed_entry(),
+                TextButton::SeedDigit(digit) => global_ui_state.as_mut().push_seed_digit(digit),
+                TextButton::DeleteSeedDigit => global_ui_state.as_mut().delete_seed_digit(),
+                TextButton::PlaySeed { seed } => {
+                    change_level_events.send(ChangeLevelEvent::ChooseInfinite { seed, target: 0 })
+                }
                 TextButton::Begging => change_level_events.send(ChangeLevelEvent::Begging),
                 TextButton::Video =>{
                     toggle_selfie_mode(&settings, video_events.clone());
@@ -230,6 +239,10 @@ fn text_button_system(
                 TextButton::DailyChallenge => {
                     change_level_events.send(ChangeLevelEvent::StartChallenge)
                 }
+                TextButton::PastChallenges => global_ui_state.as_mut().open_past_challenges(),
+                TextButton::GotoChallenge { date } => {
+                    change_level_events.send(ChangeLevelEvent::ChooseChallenge { date })
+                }
 
                 TextButton::Share => share_events.send(ShareEvent::CurrentShapes),
                 TextButton::GotoLevel { level } => {
@@ -160,6 +160,10 @@ fn track_level_completion(
         }
         GameLevel::Infinite { .. } | GameLevel::Loaded { .. } | GameLevel::Begging => {}
         GameLevel::Challenge { date, streak } => {
+            // Past challenges have no streak
+            if *streak == 0 {
+                return;
+            }
             streak_resource.count = *streak;
             streak_resource.most_recent = *date;
 
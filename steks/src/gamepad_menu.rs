@@ -0,0 +1,131 @@
+use crate::prelude::*;
+
+pub struct GamepadMenuPlugin;
+
+impl Plugin for GamepadMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GamepadMenuFocus>()
+            // The listener must see the menu as it was before this frame's presses opened or closed it
+            .add_systems(
+                Update,
+                gamepad_menu_navigation.after(gamepad_listener::<GlobalUiState>),
+            )
+            .add_systems(Update, show_gamepad_focus.after(gamepad_menu_navigation));
+    }
+}
+
+/// The text button which the d-pad has moved to.
+/// The south face button presses it, the east face button closes the menu and start opens it.
+#[derive(Debug, Default, Resource, PartialEq)]
+pub struct GamepadMenuFocus(pub Option<Entity>);
+
+fn gamepad_menu_navigation(
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mut global_ui_state: ResMut<GlobalUiState>,
+    mut focus: ResMut<GamepadMenuFocus>,
+    mut text_buttons: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &ViewVisibility,
+            &TextButtonComponent,
+            &mut Interaction,
+        ),
+        With<Button>,
+    >,
+    mut pressed: Local<Option<Entity>>,
+) {
+    // Release the button pressed last frame so it can be pressed again
+    if let Some(Ok((.., mut interaction))) =
+        pressed.take().map(|entity| text_buttons.get_mut(entity))
+    {
+        if *interaction == Interaction::Pressed {
+            *interaction = Interaction::None;
+        }
+    }
+
+    let Some(gamepad) = first_gamepad(&gamepads) else {
+        return;
+    };
+    let just_pressed = |button_type| buttons.just_pressed(GamepadButton::new(gamepad, button_type));
+
+    if just_pressed(GamepadButtonType::Start) {
+        if global_ui_state.is_minimized() {
+            global_ui_state.open_menu();
+        } else {
+            global_ui_state.minimize();
+        }
+        focus.set_if_neq(GamepadMenuFocus(None));
+        return;
+    }
+
+    if global_ui_state.is_minimized() {
+        focus.set_if_neq(GamepadMenuFocus(None));
+        return;
+    }
+
+    if just_pressed(GamepadButtonType::East) {
+        global_ui_state.minimize();
+        focus.set_if_neq(GamepadMenuFocus(None));
+        return;
+    }
+    if just_pressed(GamepadButtonType::DPadLeft) {
+        global_ui_state.previous_levels_page();
+    }
+    if just_pressed(GamepadButtonType::DPadRight) {
+        global_ui_state.next_levels_page();
+    }
+
+    // Top to bottom, then left to right
+    let mut visible: Vec<(Entity, Vec2)> = text_buttons
+        .iter()
+        .filter(|(_, _, view_visibility, button, _)| view_visibility.get() && !button.disabled)
+        .map(|(entity, transform, ..)| (entity, transform.translation().truncate()))
+        .collect();
+    visible.sort_by(|a, b| a.1.y.total_cmp(&b.1.y).then(a.1.x.total_cmp(&b.1.x)));
+
+    let index = focus
+        .0
+        .and_then(|entity| visible.iter().position(|x| x.0 == entity));
+
+    let new_index = if just_pressed(GamepadButtonType::DPadDown) {
+        index.map(|i| (i + 1) % visible.len()).or(Some(0))
+    } else if just_pressed(GamepadButtonType::DPadUp) {
+        index
+            .map(|i| (i + visible.len() - 1) % visible.len())
+            .or(visible.len().checked_sub(1))
+    } else {
+        index
+    };
+
+    let new_focus = new_index.and_then(|i| visible.get(i)).map(|x| x.0);
+    focus.set_if_neq(GamepadMenuFocus(new_focus));
+
+    if just_pressed(GamepadButtonType::South) {
+        if let Some(Ok((entity, .., mut interaction))) =
+            new_focus.map(|entity| text_buttons.get_mut(entity))
+        {
+            *interaction = Interaction::Pressed;
+            *pressed = Some(entity);
+        }
+    }
+}
+
+/// Highlights the border of the focused button
+fn show_gamepad_focus(
+    focus: Res<GamepadMenuFocus>,
+    mut text_buttons: Query<(Entity, &mut BorderColor), (With<TextButtonComponent>, With<Button>)>,
+) {
+    for (entity, mut border_color) in text_buttons.iter_mut() {
+        let color = if focus.0 == Some(entity) {
+            FOCUS_RING_STROKE
+        } else {
+            BUTTON_BORDER
+        };
+
+        if border_color.0 != color {
+            border_color.0 = color;
+        }
+    }
+}
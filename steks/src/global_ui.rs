@@ -103,6 +103,31 @@ impl GlobalUiState {
         }
     }
 
+    pub fn open_past_challenges(&mut self) {
+        *self = GlobalUiState::MenuOpen(MenuPage::Challenges { page: 0 })
+    }
+
+    pub fn open_seed_entry(&mut self) {
+        *self = GlobalUiState::MenuOpen(MenuPage::InfiniteSeed { seed: 0 })
+    }
+
+    pub fn push_seed_digit(&mut self, digit: u8) {
+        if let GlobalUiState::MenuOpen(MenuPage::InfiniteSeed { seed }) = self {
+            if let Some(new_seed) = seed
+                .checked_mul(10)
+                .and_then(|x| x.checked_add(digit as u64))
+            {
+                *seed = new_seed;
+            }
+        }
+    }
+
+    pub fn delete_seed_digit(&mut self) {
+        if let GlobalUiState::MenuOpen(MenuPage::InfiniteSeed { seed }) = self {
+            *seed /= 10;
+        }
+    }
+
     pub fn open_menu(&mut self) {
         *self = GlobalUiState::MenuOpen(MenuPage::Main)
     }
@@ -128,6 +153,10 @@ impl GlobalUiState {
             let new_level = level.saturating_add(1) % (CAMPAIGN_LEVELS.len() as u8 - 1);
 
             *self = GlobalUiState::MenuOpen(MenuPage::PBs { level: new_level })
+        } else if let GlobalUiState::MenuOpen(MenuPage::Challenges { page }) = self {
+            let new_page = page.saturating_add(1).min(MAX_CHALLENGES_PAGE);
+
+            *self = GlobalUiState::MenuOpen(MenuPage::Challenges { page: new_page })
         }
     }
 
@@ -144,6 +173,12 @@ impl GlobalUiState {
             } else {
                 *self = GlobalUiState::MenuOpen(MenuPage::Main);
             }
+        } else if let GlobalUiState::MenuOpen(MenuPage::Challenges { page }) = self {
+            if let Some(new_page) = page.checked_sub(1) {
+                *self = GlobalUiState::MenuOpen(MenuPage::Challenges { page: new_page });
+            } else {
+                *self = GlobalUiState::MenuOpen(MenuPage::Main);
+            }
         }
     }
 }
@@ -161,8 +196,11 @@ impl MavericRootChildren for GlobalUiRoot {
             NewsResource,
             UserSignedIn,
             PersonalBests,
+            WorldRecords,
         ),
         InputSettings,
+        ObjectiveStatus,
+        UndoHistory,
     );
 
     fn set_children(
@@ -187,9 +225,12 @@ impl MavericRootChildren for GlobalUiRoot {
                         3..=99 => MenuPage::Level {
                             page: ((page - 3) as u8),
                         },
-                        100.. => MenuPage::PBs {
+                        100..=999 => MenuPage::PBs {
                             level: (page - 100) as u8,
                         },
+                        1000.. => MenuPage::Challenges {
+                            page: (page - 1000) as u8,
+                        },
                     })
                 }
 
@@ -208,6 +249,16 @@ impl MavericRootChildren for GlobalUiRoot {
                         get_carousel_child,
                         transition_duration,
                     ),
+                    MenuPage::Challenges { page } => Carousel::new(
+                        *page as u32 + 1000,
+                        get_carousel_child,
+                        transition_duration,
+                    ),
+                    MenuPage::InfiniteSeed { .. } => {
+                        // A carousel page number can't hold the seed being typed
+                        commands.add_child("seed", *menu_state, &context.2);
+                        return;
+                    }
                 };
 
                 commands.add_child("carousel", carousel, &context.2);
@@ -223,8 +274,11 @@ impl MavericRootChildren for GlobalUiRoot {
                         let show_snow_icon = !context.2 .0.snow_enabled
                             && current_level.snowdrop_settings().is_some();
 
+                        let can_undo = context.5.can_undo();
+
                         let icons = [
                             Some((IconButton::OpenMenu, IconButtonStyle::Menu)),
+                            can_undo.then_some((IconButton::Undo, IconButtonStyle::Undo)),
                             show_news_icon.then_some((IconButton::OpenNews, IconButtonStyle::News)),
                             show_snow_icon
                                 .then_some((IconButton::EnableSnow, IconButtonStyle::Snow)),
@@ -252,6 +306,18 @@ impl MavericRootChildren for GlobalUiRoot {
                                 },
                                 &(),
                             );
+
+                            let status = context.4.as_ref();
+                            if status != &ObjectiveStatus::default() {
+                                commands.add_child(
+                                    "objectives",
+                                    ObjectivesPanel {
+                                        status: status.clone(),
+                                        top: insets.real_top(),
+                                    },
+                                    &(),
+                                );
+                            }
                         }
                     }
                     LevelCompletion::Complete { score_info } => {
@@ -1,6 +1,7 @@
 use base64::Engine;
 use bevy::{log, prelude::*};
 
+use chrono::NaiveDate;
 use itertools::Itertools;
 
 use crate::prelude::*;
@@ -24,8 +25,8 @@ impl Plugin for LeaderboardPlugin {
 
 #[derive(Debug, Event, Clone)]
 pub enum LeaderboardDataEvent {
-    Success { text: String },
-    Failure { hash: u64, error: String },
+    Success { key: RecordKey, text: String },
+    Failure { key: RecordKey, error: String },
 }
 
 #[derive(Debug, Event)]
@@ -62,10 +63,10 @@ fn is_cheat_in_path() -> Option<()> {
     None
 }
 
-pub fn refresh_wr_data(hash: u64, writer: AsyncEventWriter<LeaderboardDataEvent>) {
+pub fn refresh_wr_data(key: RecordKey, writer: AsyncEventWriter<LeaderboardDataEvent>) {
     debug!("Refreshing Leaderboard");
     spawn_and_run(async move {
-        let data_event = get_leaderboard_data(hash).await;
+        let data_event = get_leaderboard_data(key).await;
         writer
             .send_async(data_event)
             .await
@@ -82,14 +83,14 @@ fn hydrate_leaderboard(
         return;
     };
 
-    let text = match &ev {
-        LeaderboardDataEvent::Success { text } => text,
-        LeaderboardDataEvent::Failure { hash, error } => {
+    let (key, text) = match &ev {
+        LeaderboardDataEvent::Success { key, text } => (*key, text),
+        LeaderboardDataEvent::Failure { key, error } => {
             crate::logging::try_log_error_message(error.clone());
             match current_level.completion {
                 LevelCompletion::Incomplete { .. } => {}
                 LevelCompletion::Complete { score_info } => {
-                    if score_info.hash == *hash {
+                    if RecordKey::new(&current_level.level, score_info.hash) == *key {
                         current_level.completion = LevelCompletion::Complete {
                             score_info: ScoreInfo {
                                 wr: WRData::ConnectionError,
@@ -117,6 +118,12 @@ fn hydrate_leaderboard(
             return;
         }
     };
+    if let RecordKey::Shapes { hash: expected } = key {
+        if hash != expected {
+            warn!("Received wr for hash {hash} but expected {expected}");
+            return;
+        }
+    }
     let written_height: f32 = match written_height.parse() {
         Ok(height) => height,
         Err(_err) => {
@@ -141,17 +148,32 @@ fn hydrate_leaderboard(
             }
         }
     };
-    let shapes = ShapesVec::from_bytes(&image_blob);
-    let wr_height = shapes.calculate_tower_height();
-    let internal_wr_height: Option<f32> = match wrs.map.entry(hash) {
-        std::collections::btree_map::Entry::Vacant(ve) => {
-            ve.insert(LevelWR::new(image_blob, Some(updated)));
+    let shapes = match ShapesVec::from_bytes(&image_blob) {
+        Ok(shapes) => shapes,
+        Err(err) => {
+            error!("{err}");
+            return;
+        }
+    };
+    // Towers are measured from the water line in game.
+    // Records for other levels are not shown so their plain height is fine.
+    let water_line = match current_level.completion {
+        LevelCompletion::Complete { score_info }
+            if RecordKey::new(&current_level.level, score_info.hash) == key =>
+        {
+            current_level.level.water_line()
+        }
+        _ => None,
+    };
+    let wr_height = shapes.calculate_tower_height_above(water_line);
+    let internal_wr_height: Option<f32> = match wrs.get_mut(&key) {
+        None => {
+            wrs.insert(key, LevelWR::new(image_blob, Some(updated)));
             None
         }
-        std::collections::btree_map::Entry::Occupied(mut oe) => {
-            let saved_wr = oe.get_mut();
-            let saved_shapes = ShapesVec::from_bytes(&saved_wr.image_blob);
-            let saved_height = saved_shapes.calculate_tower_height();
+        Some(saved_wr) => {
+            let saved_shapes = ShapesVec::from_bytes(&saved_wr.image_blob).unwrap_or_default();
+            let saved_height = saved_shapes.calculate_tower_height_above(water_line);
 
             match saved_height.total_cmp(&wr_height) {
                 std::cmp::Ordering::Less => {
@@ -164,7 +186,7 @@ fn hydrate_leaderboard(
                 }
                 std::cmp::Ordering::Greater => {
                     info!("Existing record {saved_height} is better than record from server {wr_height}");
-                    update_wr(&saved_shapes);
+                    update_wr(&saved_shapes, key.challenge_date());
                     saved_wr.updated = Some(updated);
 
                     Some(saved_height)
@@ -179,16 +201,18 @@ fn hydrate_leaderboard(
         return;
     };
 
-    if score_info.hash != hash {
-        warn!("Current level hash does not match");
+    if RecordKey::new(&current_level.level, score_info.hash) != key {
+        warn!("Current level record key does not match");
         return;
     }
 
-    let wr_data: WRData = if internal_wr_height == Some(score_info.height) {
-        WRData::InternalConfirmed
-    } else {
-        WRData::External(wr_height)
-    };
+    // The saved record was encoded so its height is not exact
+    let wr_data: WRData =
+        if internal_wr_height.is_some_and(|x| (x - score_info.height).abs() < 0.01) {
+            WRData::InternalConfirmed
+        } else {
+            WRData::External(wr_height)
+        };
 
     if score_info.wr != wr_data {
         current_level.completion = LevelCompletion::Complete {
@@ -200,34 +224,44 @@ fn hydrate_leaderboard(
     }
 }
 
-async fn get_leaderboard_data(hash: u64) -> LeaderboardDataEvent {
+/// Set `STEKS_LEADERBOARD_URL` at build time to use a local leaderboard server
+const LEADERBOARD_URL: &str = match option_env!("STEKS_LEADERBOARD_URL") {
+    Some(url) => url,
+    None => "https://steks.net/.netlify/functions/leaderboard",
+};
+
+async fn get_leaderboard_data(key: RecordKey) -> LeaderboardDataEvent {
     let client = reqwest::Client::new();
-    let url =
-        format!("https://steks.net/.netlify/functions/leaderboard?command=getrow&hash={hash}");
+    let url = match key {
+        RecordKey::Shapes { hash } => format!("{LEADERBOARD_URL}?command=getrow&hash={hash}"),
+        RecordKey::Challenge { date } => format!("{LEADERBOARD_URL}?command=getrow&date={date}"),
+    };
     let res = client.get(url).send().await;
 
     match res {
         Ok(response) => match response.text().await {
-            Ok(text) => LeaderboardDataEvent::Success { text },
+            Ok(text) => LeaderboardDataEvent::Success { key, text },
             Err(err) => LeaderboardDataEvent::Failure {
                 error: err.to_string(),
-                hash,
+                key,
             },
         },
         Err(err) => LeaderboardDataEvent::Failure {
             error: err.to_string(),
-            hash,
+            key,
         },
     }
 }
 
-fn update_wr(shapes_vec: &ShapesVec) {
+/// Challenge records are kept separately for each date.
+/// The leaderboard stores the plain height, ignoring any water line, because that is what it can verify.
+fn update_wr(shapes_vec: &ShapesVec, date: Option<NaiveDate>) {
     let hash = shapes_vec.hash();
     let height = shapes_vec.calculate_tower_height();
     let blob = shapes_vec.make_base64_data();
     log::debug!("Updating wrs {hash} {height}");
     spawn_and_run(async move {
-        match update_wrs_async(hash, height, blob).await {
+        match update_wrs_async(hash, height, blob, date).await {
             Ok(_) => log::debug!("Updated leaderboard {hash} {height}"),
             Err(err) => {
                 crate::logging::try_log_error_message(format!(
@@ -238,16 +272,23 @@ fn update_wr(shapes_vec: &ShapesVec) {
     });
 }
 
-async fn update_wrs_async(hash: u64, height: f32, blob: String) -> Result<(), reqwest::Error> {
-    if cfg!(debug_assertions) {
+async fn update_wrs_async(
+    hash: u64,
+    height: f32,
+    blob: String,
+    date: Option<NaiveDate>,
+) -> Result<(), reqwest::Error> {
+    if cfg!(debug_assertions) && option_env!("STEKS_LEADERBOARD_URL").is_none() {
         return Ok(());
     }
 
+    let mut url = format!("{LEADERBOARD_URL}?command=set&hash={hash}&height={height:.2}&blob={blob}");
+    if let Some(date) = date {
+        url.push_str(&format!("&date={date}"));
+    }
+
     let client = reqwest::Client::new();
-    let res = client
-            .post(format!("https://steks.net/.netlify/functions/leaderboard?command=set&hash={hash}&height={height:.2}&blob={blob}"))
-            .send()
-            .await?;
+    let res = client.post(url).send().await?;
 
     res.error_for_status().map(|_| ())
 }
@@ -313,6 +354,7 @@ fn check_pbs_on_completion(
     current_level: Res<CurrentLevel>,
     mut pbs: ResMut<PersonalBests>,
     mut max_infinite: ResMut<MaxInfiniteStage>,
+    mut infinite_bests: ResMut<InfiniteBests>,
 ) {
     if !current_level.is_changed() {
         return;
@@ -322,8 +364,14 @@ fn check_pbs_on_completion(
         return;
     }
 
-    let (height, hash) = match current_level.completion {
+    let (score, hash) = match current_level.completion {
         LevelCompletion::Incomplete { stage } => {
+            if let GameLevel::Infinite { seed, .. } = current_level.level {
+                // Only take the resource mutably if it changes, so it isn't saved needlessly
+                if stage > infinite_bests.get(seed) {
+                    infinite_bests.record(seed, stage);
+                }
+            }
             if stage > max_infinite.0 {
                 max_infinite.0 = stage;
                 if let Some(options) = submit_score_options(current_level.as_ref()) {
@@ -332,8 +380,9 @@ fn check_pbs_on_completion(
             }
             return;
         }
-        LevelCompletion::Complete { score_info } => (score_info.height, score_info.hash),
+        LevelCompletion::Complete { score_info } => (score_info.score(), score_info.hash),
     };
+    let height = score.height;
 
     let Some(shapes) = &current_level.saved_data() else {
         return;
@@ -343,7 +392,7 @@ fn check_pbs_on_completion(
         let star = current_level
             .level
             .get_level_stars()
-            .map(|s| s.get_star(height))
+            .map(|s| s.get_star(&score))
             .unwrap_or(StarType::Incomplete);
         #[allow(deprecated)]
         LevelPB {
@@ -353,25 +402,13 @@ fn check_pbs_on_completion(
         }
     };
 
-    let pb_changed = match DetectChangesMut::bypass_change_detection(&mut pbs)
-        .map
-        .entry(hash)
-    {
-        std::collections::btree_map::Entry::Vacant(v) => {
-            v.insert(level_pb());
-            true
-        }
-        std::collections::btree_map::Entry::Occupied(mut o) => {
-            if o.get().height + 0.01 < height {
-                o.insert(level_pb());
-                true
-            } else {
-                false
-            }
-        }
+    let key = RecordKey::new(&current_level.level, hash);
+    let pb_changed = match pbs.get(&key) {
+        None => true,
+        Some(existing) => existing.height + 0.01 < height,
     };
     if pb_changed {
-        pbs.set_changed();
+        pbs.insert(key, level_pb());
 
         if let Some(options) = submit_score_options(current_level.as_ref()) {
             submit_score(options.into())
@@ -418,19 +455,20 @@ fn check_wrs_on_completion(
             return;
         };
 
+    let key = RecordKey::new(&current_level.level, hash);
     let level_wr = || LevelWR::new(shapes.make_bytes(), None);
 
-    let refresh = match world_records.map.entry(hash) {
-        std::collections::btree_map::Entry::Vacant(v) => {
-            v.insert(level_wr());
+    let refresh = match world_records.get(&key) {
+        None => {
+            world_records.insert(key, level_wr());
             true
         }
-        std::collections::btree_map::Entry::Occupied(mut o) => {
-            let previous = o.get();
+        Some(previous) => {
             let now = chrono::offset::Utc::now();
 
-            if previous.calculate_height() + 0.01 < height {
-                o.insert(level_wr());
+            let previous_height = previous.calculate_height_above(current_level.level.water_line());
+            if previous_height + 0.01 < height {
+                world_records.insert(key, level_wr());
                 true
             } else {
                 match previous.updated {
@@ -442,7 +480,7 @@ fn check_wrs_on_completion(
     };
 
     if refresh {
-        refresh_wr_data(hash, writer);
+        refresh_wr_data(key, writer);
     }
 }
 
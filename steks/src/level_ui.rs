@@ -194,7 +194,10 @@ impl MavericNode for MainPanel {
                         );
 
                         if let Some(level_stars) = args.level.get_level_stars() {
-                            let star_type = level_stars.get_star(pb_height);
+                            let star_type = level_stars.get_star(&LevelScore {
+                                height: pb_height,
+                                ..args.score_info.score()
+                            });
                             commands.add_child(
                                 "pb_stars",
                                 ImageNode {
@@ -290,6 +293,24 @@ impl MavericNode for MainPanel {
                         &(),
                     );
 
+                    let objectives = args.level.get_objectives();
+                    let measure = args
+                        .level
+                        .get_level_stars()
+                        .map(|x| x.measure)
+                        .unwrap_or_default();
+                    if objectives.time_limit.is_some()
+                        || objectives.move_limit.is_some()
+                        || !measure.is_height()
+                    {
+                        let ScoreInfo { seconds, moves, .. } = args.score_info;
+                        commands.add_child(
+                            "objective_data",
+                            panel_text_node(format!("{seconds:.1}s    {moves} moves")),
+                            &(),
+                        );
+                    }
+
                     if let Some(star_type) = args.score_info.star {
                         if let Some(level_stars) = args.level.get_level_stars() {
                             commands.add_child(
@@ -362,7 +383,7 @@ impl MavericNode for MainPanel {
                         &(),
                     );
 
-                    if let GameLevel::Challenge { streak, .. } = args.level {
+                    if let GameLevel::Challenge { streak: streak @ 1.., .. } = args.level {
                         commands.add_child(
                             "streak",
                             panel_text_node(format!("Streak    {streak:.2}",)),
@@ -440,14 +461,20 @@ impl MavericNode for StarHeights {
 
             let second_star: String = match args.star_type {
                 StarType::Incomplete | StarType::OneStar => {
-                    format!("{filler}{height:3.0}m", height = args.level_stars.two)
+                    format!(
+                        "{filler}{}",
+                        args.level_stars.format_threshold(args.level_stars.two)
+                    )
                 }
                 StarType::ThreeStar | StarType::TwoStar => empty.to_string(),
             };
 
             let third_star: String = match args.star_type {
                 StarType::Incomplete | StarType::OneStar | StarType::TwoStar => {
-                    format!("{filler}{height:3.0}m", height = args.level_stars.three)
+                    format!(
+                        "{filler}{}",
+                        args.level_stars.format_threshold(args.level_stars.three)
+                    )
                 }
                 StarType::ThreeStar => empty.to_string(),
             };
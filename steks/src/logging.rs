@@ -37,11 +37,16 @@ fn watch_level_changes(
             }
             ChangeLevelEvent::ResetLevel => "Reset Level".to_string(),
             ChangeLevelEvent::StartInfinite => "Start Infinite".to_string(),
+            ChangeLevelEvent::ChooseInfinite { seed, .. } => {
+                format!("Start Infinite with seed {seed}")
+            }
             ChangeLevelEvent::StartChallenge => "Start Challenge".to_string(),
+            ChangeLevelEvent::ChooseChallenge { date } => format!("Go to challenge {date}"),
             ChangeLevelEvent::Load(_) => "Load Game".to_string(),
             ChangeLevelEvent::Credits => "Start Credits".to_string(),
             ChangeLevelEvent::Begging => "Go to begging".to_string(),
             ChangeLevelEvent::Custom { .. } => "Go to custom level".to_string(),
+            ChangeLevelEvent::PlayReplay { .. } => "Play replay".to_string(),
         };
         let loggable_event = LoggableEvent::ChangeLevel {
             level_from: current_level.level.clone().into(),
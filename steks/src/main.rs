@@ -9,6 +9,7 @@ pub mod asynchronous;
 pub mod button;
 pub mod demo;
 pub mod game_level;
+pub mod gamepad_menu;
 pub mod global_ui;
 pub mod import;
 pub mod leaderboard;
@@ -17,6 +18,7 @@ pub mod level_ui;
 pub mod logging;
 pub mod menu;
 pub mod news;
+pub mod objectives_panel;
 pub mod preview_images;
 pub mod share;
 pub mod startup;
@@ -56,6 +58,7 @@ pub mod prelude {
     pub use crate::compatibility::*;
     pub use crate::demo::*;
     pub use crate::game_level::*;
+    pub use crate::gamepad_menu::*;
     pub use crate::global_ui::*;
     pub use crate::import::*;
     pub use crate::leaderboard::*;
@@ -64,6 +67,7 @@ pub mod prelude {
     pub use crate::logging::*;
     pub use crate::menu::*;
     pub use crate::news::*;
+    pub use crate::objectives_panel::*;
     pub use crate::preview_images::*;
     pub use crate::share::*;
     pub use crate::video::*;
@@ -3,6 +3,9 @@ use maveric::prelude::*;
 use strum::EnumIs;
 
 const LEVELS_PER_PAGE: u8 = 8;
+const CHALLENGES_PER_PAGE: u8 = 8;
+/// How far back past challenges go
+pub const MAX_CHALLENGES_PAGE: u8 = 11;
 
 pub fn max_page_exclusive() -> u8 {
     let t = CAMPAIGN_LEVELS.len() as u8;
@@ -16,6 +19,9 @@ pub enum MenuPage {
     Accessibility,
     Level { page: u8 },
     PBs { level: u8 },
+    Challenges { page: u8 },
+    /// Typing in a seed for infinite mode
+    InfiniteSeed { seed: u64 },
 }
 
 fn filter_button(button: TextButton, context: &NewsResource) -> bool {
@@ -33,6 +39,7 @@ impl MavericNode for MenuPage {
         NewsResource,
         UserSignedIn,
         PersonalBests,
+        WorldRecords,
     );
 
     fn set_components(commands: SetComponentCommands<Self, Self::Context>) {
@@ -68,7 +75,9 @@ impl MavericNode for MenuPage {
                         #[cfg(feature = "web")]
                         Begging,
                         DailyChallenge,
+                        PastChallenges,
                         Infinite,
+                        ChooseSeed,
                         Tutorial,
                         Share,
                         OpenSettings,
@@ -89,6 +98,7 @@ impl MavericNode for MenuPage {
                         #[cfg(feature = "web")]
                         Begging,
                         DailyChallenge,
+                        PastChallenges,
                         Tutorial,
                         Share,
                         OpenSettings,
@@ -267,13 +277,69 @@ impl MavericNode for MenuPage {
                     )
                 }
 
-                commands.add_child("buttons", LevelMenuArrows(*page), &());
+                commands.add_child("buttons", LevelMenuArrows(*page, 4), &());
             }
 
             MenuPage::PBs { level } => {
                 //let campaign_completion = &context.1;
                 commands.add_child("preview", PBPreview { level: *level }, context);
             }
+
+            MenuPage::Challenges { page } => {
+                let today = get_today_date();
+                let start = *page as u64 * CHALLENGES_PER_PAGE as u64;
+                let pbs = &context.5;
+                let wrs = &context.6;
+
+                for (key, days) in (start..start + CHALLENGES_PER_PAGE as u64).enumerate() {
+                    let Some(date) = today.checked_sub_days(chrono::Days::new(days)) else {
+                        continue;
+                    };
+                    let record_key = RecordKey::Challenge { date };
+
+                    let pb = pbs.get(&record_key).map(|x| x.height);
+                    let wr = wrs.get(&record_key).map(|x| x.calculate_height());
+                    let format_height = |height: Option<f32>| match height {
+                        Some(height) => format!("{height:6.2}m"),
+                        None => "   -   ".to_string(),
+                    };
+                    let text = format!("{date} {} {}", format_height(pb), format_height(wr));
+
+                    commands.add_child(
+                        key as u32,
+                        text_button_node_with_text(
+                            TextButton::GotoChallenge { date },
+                            text,
+                            false,
+                            false,
+                            false,
+                        ),
+                        &(),
+                    )
+                }
+
+                commands.add_child("buttons", LevelMenuArrows(*page, MAX_CHALLENGES_PAGE), &());
+            }
+
+            MenuPage::InfiniteSeed { seed } => {
+                commands.add_child(
+                    "play",
+                    text_button_node(TextButton::PlaySeed { seed: *seed }, true, false, false),
+                    &(),
+                );
+                commands.add_child("top_digits", SeedKeypadRow([1, 2, 3, 4, 5]), &());
+                commands.add_child("bottom_digits", SeedKeypadRow([6, 7, 8, 9, 0]), &());
+                commands.add_child(
+                    "delete",
+                    text_button_node(TextButton::DeleteSeedDigit, true, false, false),
+                    &(),
+                );
+                commands.add_child(
+                    "back",
+                    text_button_node(TextButton::BackToMenu, true, false, false),
+                    &(),
+                );
+            }
         });
     }
 }
@@ -291,6 +357,7 @@ impl MavericNode for PBPreview {
         NewsResource,
         UserSignedIn,
         PersonalBests,
+        WorldRecords,
     );
 
     fn set_components(commands: SetComponentCommands<Self, Self::Context>) {
@@ -343,7 +410,20 @@ impl MavericNode for PBPreview {
                     .get(node.level as usize)
                     .and_then(|x| x.stars)
                 {
-                    let stars = level_stars.get_star(height);
+                    let stars = if level_stars.measure.is_height() {
+                        level_stars.get_star(&LevelScore {
+                            height,
+                            ..Default::default()
+                        })
+                    } else {
+                        // Personal bests only record the height
+                        context
+                            .1
+                            .stars
+                            .get(node.level as usize)
+                            .cloned()
+                            .unwrap_or_default()
+                    };
                     commands.add_child(
                         "pb_stars",
                         ImageNode {
@@ -374,6 +454,7 @@ impl MavericNode for PBButtons {
         NewsResource,
         UserSignedIn,
         PersonalBests,
+        WorldRecords,
     );
 
     fn set_components(commands: SetComponentCommands<Self, Self::Context>) {
@@ -459,8 +540,9 @@ impl MavericNode for PBButtons {
     }
 }
 
+/// Arrows for a paged menu. The fields are the current page and the last page.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
-pub struct LevelMenuArrows(u8);
+pub struct LevelMenuArrows(u8, u8);
 
 impl MavericNode for LevelMenuArrows {
     type Context = ();
@@ -514,7 +596,7 @@ impl MavericNode for LevelMenuArrows {
                 )
             }
 
-            if args.0 < 4 {
+            if args.0 < args.1 {
                 commands.add_child(
                     "right",
                     icon_button_node(IconButton::NextLevelsPage, IconButtonStyle::HeightPadded),
@@ -530,3 +612,50 @@ impl MavericNode for LevelMenuArrows {
         });
     }
 }
+
+/// A row of digit keys for typing in a seed
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SeedKeypadRow([u8; 5]);
+
+impl MavericNode for SeedKeypadRow {
+    type Context = ();
+
+    fn set_components(commands: SetComponentCommands<Self, Self::Context>) {
+        commands.ignore_node().ignore_context().insert(NodeBundle {
+            style: Style {
+                position_type: PositionType::Relative,
+                left: Val::Percent(0.0),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+
+                width: Val::Px(TEXT_BUTTON_WIDTH),
+                height: Val::Px(TEXT_BUTTON_HEIGHT),
+                margin: UiRect {
+                    left: Val::Auto,
+                    right: Val::Auto,
+                    top: Val::Px(MENU_TOP_BOTTOM_MARGIN),
+                    bottom: Val::Px(MENU_TOP_BOTTOM_MARGIN),
+                },
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_grow: 0.0,
+                flex_shrink: 0.0,
+
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+    }
+
+    fn set_children<R: MavericRoot>(commands: SetChildrenCommands<Self, Self::Context, R>) {
+        commands.unordered_children_with_node(|args, commands| {
+            for digit in args.0 {
+                commands.add_child(
+                    digit as u32,
+                    key_button_node(TextButton::SeedDigit(digit)),
+                    &(),
+                );
+            }
+        });
+    }
+}
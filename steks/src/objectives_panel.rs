@@ -0,0 +1,73 @@
+use crate::prelude::*;
+
+use maveric::prelude::*;
+
+/// Shows the time and moves left, and offers a retry when an objective is failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectivesPanel {
+    pub status: ObjectiveStatus,
+    pub top: f32,
+}
+
+impl MavericNode for ObjectivesPanel {
+    type Context = ();
+
+    fn set_components(commands: SetComponentCommands<Self, Self::Context>) {
+        commands
+            .ignore_context()
+            .insert_with_node(|node| NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(node.top),
+                    right: Val::Px(10.0),
+                    display: Display::Flex,
+                    align_items: AlignItems::End,
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+    }
+
+    fn set_children<R: MavericRoot>(commands: SetChildrenCommands<Self, Self::Context, R>) {
+        commands.ordered_children_with_node_and_context(|node, context, commands| {
+            let status = &node.status;
+
+            if let Some(seconds) = status.seconds_remaining {
+                commands.add_child("time", panel_text_node(format!("{seconds}s left")), context);
+            }
+
+            if let Some(moves) = status.moves_remaining {
+                let text = match moves {
+                    1 => "1 move left".to_string(),
+                    moves => format!("{moves} moves left"),
+                };
+                commands.add_child("moves", panel_text_node(text), context);
+            }
+
+            if status.has_target {
+                commands.add_child("target", panel_text_node("Reach the target"), context);
+            }
+
+            if let Some(failure) = status.failure {
+                commands.add_child(
+                    "failure",
+                    TextNode {
+                        text: failure.message(),
+                        font_size: LEVEL_TITLE_FONT_SIZE,
+                        color: WARN_COLOR,
+                        font: LEVEL_TITLE_FONT_PATH,
+                        alignment: TextAlignment::Right,
+                        linebreak_behavior: bevy::text::BreakLineOn::NoWrap,
+                    },
+                    context,
+                );
+                commands.add_child(
+                    "retry",
+                    icon_button_node(IconButton::RetryLevel, IconButtonStyle::Big),
+                    context,
+                );
+            }
+        });
+    }
+}
@@ -41,13 +41,13 @@ fn update_preview_images(
         return;
     }
 
-    let (preview, hash) = match ui_state.as_ref() {
+    let (preview, key) = match ui_state.as_ref() {
         GlobalUiState::MenuClosed(GameUIState::Preview(preview)) => {
             let LevelCompletion::Complete { score_info } = current_level.completion else {
                 return;
             };
 
-            (*preview, score_info.hash)
+            (*preview, RecordKey::new(&current_level.level, score_info.hash))
         }
         GlobalUiState::MenuOpen(MenuPage::PBs { level }) => {
             let Some(level) = CAMPAIGN_LEVELS.get(*level as usize) else {
@@ -55,7 +55,7 @@ fn update_preview_images(
             };
             let sv = ShapesVec::from(level);
             let hash = sv.hash();
-            (PreviewImage::PB, hash)
+            (PreviewImage::PB, RecordKey::Shapes { hash })
         }
         _ => return,
     };
@@ -72,7 +72,7 @@ fn update_preview_images(
 
     match preview {
         PreviewImage::PB => {
-            if let Some(pb) = pbs.map.get(&hash) {
+            if let Some(pb) = pbs.get(&key) {
                 match game_to_image(pb.image_blob.as_slice()) {
                     Ok(image) => {
                         *im = image;
@@ -84,7 +84,7 @@ fn update_preview_images(
             }
         }
         PreviewImage::WR => {
-            if let Some(wr) = wrs.map.get(&hash) {
+            if let Some(wr) = wrs.get(&key) {
                 match game_to_image(wr.image_blob.as_slice()) {
                     Ok(image) => {
                         *im = image;
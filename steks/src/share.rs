@@ -20,18 +20,28 @@ impl Plugin for SharePlugin {
 
 fn handle_shares(
     mut events: EventReader<ShareEvent>,
-    shapes_query: Query<(&ShapeIndex, &Transform, &ShapeComponent, &Friction)>,
+    shapes_query: Query<(&ShapeIndex, &Transform, &ShapeComponent, &ShapeModifiers)>,
     pbs: Res<PersonalBests>,
     ui_state: Res<GlobalUiState>,
+    current_level: Res<CurrentLevel>,
+    infinite_bests: Res<InfiniteBests>,
 ) {
     let Some(ev) = events.read().next() else {
         return;
     };
 
-    let data: String = match ev {
+    let path: String = match ev {
         ShareEvent::CurrentShapes => {
-            let shapes = shapes_vec_from_query(shapes_query);
-            shapes.make_base64_data()
+            if let GameLevel::Infinite { seed, .. } = current_level.level {
+                let stage = match current_level.completion {
+                    LevelCompletion::Incomplete { stage } => stage,
+                    LevelCompletion::Complete { .. } => infinite_bests.get(seed),
+                };
+                InfiniteShareCode { seed, stage }.to_path()
+            } else {
+                let shapes = shapes_vec_from_query(shapes_query);
+                format!("game/{}", shapes.make_base64_data())
+            }
         }
         ShareEvent::PersonalBest => {
             let shapes = shapes_vec_from_query(shapes_query);
@@ -41,7 +51,7 @@ fn handle_shares(
                 }
                 _ => {
                     let hash = shapes.hash();
-                    pbs.map.get(&hash)
+                    pbs.get(&RecordKey::new(&current_level.level, hash))
                 }
             };
 
@@ -49,13 +59,16 @@ fn handle_shares(
                 return;
             };
 
-            base64::engine::general_purpose::URL_SAFE.encode(pb.image_blob.clone())
+            format!(
+                "game/{}",
+                base64::engine::general_purpose::URL_SAFE.encode(pb.image_blob.clone())
+            )
         }
     };
-    bevy::log::debug!("Sharing game {data:?}");
+    bevy::log::debug!("Sharing game {path:?}");
 
     #[cfg(target_arch = "wasm32")]
     {
-        crate::wasm::share_game(data);
+        crate::wasm::share_game(path);
     }
 }
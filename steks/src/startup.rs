@@ -107,6 +107,7 @@ pub fn setup_app(app: &mut App) {
         .add_plugins(ImportPlugin)
         .add_plugins(NewsPlugin)
         .add_plugins(StreakPlugin)
+        .add_plugins(AutosavePlugin)
         .insert_resource(Time::<Fixed>::from_seconds(SECONDS_PER_FRAME))
         .add_systems(FixedUpdate, limit_fixed_time)
         .insert_resource(RapierConfiguration {
@@ -121,6 +122,10 @@ pub fn setup_app(app: &mut App) {
             RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PHYSICS_SCALE),
         ))
         .add_plugins(DragPlugin::<GlobalUiState>::default())
+        .add_plugins(GamepadPlugin::<GlobalUiState>::default())
+        .add_plugins(GamepadMenuPlugin)
+        .add_plugins(UndoPlugin::<GlobalUiState>::default())
+        .add_plugins(ReplayPlugin)
         .add_plugins(WinPlugin::<GlobalUiState>::default())
         .add_plugins(GameLevelPlugin)
         .add_plugins(LevelPlugin::new(CurrentLevel::new(
@@ -33,14 +33,28 @@ type StrokeWidthLens = Prism2<StrokeOptionsLens, StrokeOptionsLineWidthLens>;
 struct LevelOutlinesRoot;
 
 impl MavericRootChildren for LevelOutlinesRoot {
-    type Context = CurrentLevel;
+    type Context = (CurrentLevel, ReplayPlayback);
 
     fn set_children(
         context: &<Self::Context as NodeContext>::Wrapper<'_>,
         commands: &mut impl ChildCommands,
     ) {
-        if let GameLevel::Designed { meta } = &context.level {
-            let stage = meta.get_level().get_current_stage(context.completion);
+        if let Some(ghost_shapes) = context.1.ghost_shapes() {
+            for (index, shape) in ghost_shapes.iter().enumerate() {
+                let outline = ShapeOutline {
+                    shape: shape.shape.into(),
+                    x: Some(shape.location.position.x),
+                    y: Some(shape.location.position.y),
+                    revs: Some(shape.location.angle / std::f32::consts::TAU),
+                    scale: None,
+                    shape_id: None,
+                };
+                commands.add_child((index as u32) + 200, ShapeOutlineNode(outline), &());
+            }
+        }
+
+        if let GameLevel::Designed { meta } = &context.0.level {
+            let stage = meta.get_level().get_current_stage(context.0.completion);
 
             for (index, shape_outline) in stage.outlines.iter().enumerate() {
                 commands.add_child(index as u32, ShapeOutlineNode(*shape_outline), &());
@@ -41,9 +41,10 @@ pub fn request_fullscreen() {
     }
 }
 
-pub fn share_game(game: String) {
+/// Share a path on the website, e.g. `game/<data>`
+pub fn share_game(path: String) {
     IoTaskPool::get()
-        .spawn(async move { share_game_async(game).await })
+        .spawn(async move { share_game_async(path).await })
         .detach();
 }
 
@@ -137,7 +138,7 @@ pub fn open_link(url: &str) {
     }
 }
 
-async fn share_game_async(game: String) {
+async fn share_game_async(path: String) {
     let device_id = capacitor_bindings::device::Device::get_id()
         .await
         .unwrap_or_else(|_| capacitor_bindings::device::DeviceId {
@@ -148,7 +149,7 @@ async fn share_game_async(game: String) {
         .try_log_async1(device_id.clone().into())
         .await;
 
-    let url = "https://steks.net/game/".to_string() + game.as_str();
+    let url = "https://steks.net/".to_string() + path.as_str();
     let result = capacitor_bindings::share::Share::share(
         ShareOptions::builder()
             .title("steks")
@@ -74,6 +74,7 @@ pub fn setup_app(app: &mut App) {
             RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PHYSICS_SCALE).in_fixed_schedule(),
         )
         .add_plugins(DragPlugin::<GlobalUiState>::default())
+        .add_plugins(UndoPlugin::<GlobalUiState>::default())
         .add_plugins(WinPlugin::<GlobalUiState>::default())
         .add_plugins(LevelPlugin::new(CurrentLevel::new(
             GameLevel::Designed {
@@ -0,0 +1,278 @@
+use bevy::time::common_conditions::on_timer;
+use bevy::window::{ApplicationLifetime, WindowFocused};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+/// Shapes moving slower than this (in pixels per second) have settled
+const SETTLED_LINEAR_SPEED: f32 = 1.0;
+/// Shapes rotating slower than this (in radians per second) have settled
+const SETTLED_ANGULAR_SPEED: f32 = 0.05;
+/// Shapes which have moved less than this since the last autosave don't need saving again
+const AUTOSAVE_POSITION_TOLERANCE: f32 = 0.5;
+const AUTOSAVE_ANGLE_TOLERANCE: f32 = 0.01;
+
+#[derive(Debug, Default)]
+pub struct AutosavePlugin;
+
+impl Plugin for AutosavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_tracked_resource::<Autosave>()
+            .add_systems(
+                Update,
+                autosave_when_settled.run_if(on_timer(Duration::from_secs(1))),
+            )
+            .add_systems(Update, autosave_when_backgrounded);
+    }
+}
+
+/// The live world of an unfinished stage, so it can be restored after a restart
+#[derive(Debug, Clone, Default, PartialEq, Resource, Serialize, Deserialize)]
+pub struct Autosave(pub Option<WorldSave>);
+
+impl TrackableResource for Autosave {
+    const KEY: &'static str = "Autosave";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldSave {
+    pub level: GameLevel,
+    pub stage: usize,
+    pub shapes: Vec<SavedShape>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedShape {
+    pub shape: EncodableShape,
+    pub linvel: [f32; 2],
+    pub angvel: f32,
+    pub stage: usize,
+    pub id: Option<u32>,
+    /// How far a moving shape is along its path
+    #[serde(default)]
+    pub frame: Option<u32>,
+}
+
+type SavedShapeQueryItem = (
+    &'static ShapeIndex,
+    &'static Transform,
+    &'static ShapeComponent,
+    &'static ShapeModifiers,
+    &'static Velocity,
+    &'static ShapeStage,
+    Option<&'static ShapeWithId>,
+    Option<&'static MovingShape>,
+);
+
+impl WorldSave {
+    /// Only unfinished stages are saved. Finished levels are kept in the current level's saved data.
+    pub fn new(current_level: &CurrentLevel, shapes: &Query<SavedShapeQueryItem>) -> Option<Self> {
+        let LevelCompletion::Incomplete { stage } = current_level.completion else {
+            return None;
+        };
+
+        let shapes = shapes
+            .iter()
+            .map(
+                |(
+                    index,
+                    transform,
+                    shape_component,
+                    modifiers,
+                    velocity,
+                    shape_stage,
+                    id,
+                    moving,
+                )| {
+                    SavedShape {
+                        shape: EncodableShape {
+                            shape: *index,
+                            location: transform.into(),
+                            state: shape_component.into(),
+                            modifiers: *modifiers,
+                        },
+                        linvel: velocity.linvel.to_array(),
+                        angvel: velocity.angvel,
+                        stage: shape_stage.0,
+                        id: id.map(|x| x.id),
+                        frame: moving.map(|x| x.frame),
+                    }
+                },
+            )
+            .collect();
+
+        Some(Self {
+            level: current_level.level.clone(),
+            stage,
+            shapes,
+        })
+    }
+
+    /// Whether this save is for the stage the player is on
+    pub fn matches(&self, current_level: &CurrentLevel) -> bool {
+        self.level == current_level.level
+            && current_level.completion == LevelCompletion::Incomplete { stage: self.stage }
+    }
+
+    /// Whether the shapes are all in nearly the same place as in the other save
+    pub fn is_close_to(&self, other: &Self) -> bool {
+        self.level == other.level
+            && self.stage == other.stage
+            && self.shapes.len() == other.shapes.len()
+            && self.shapes.iter().zip(other.shapes.iter()).all(|(a, b)| {
+                a.shape.shape == b.shape.shape
+                    && a.shape.state == b.shape.state
+                    && a.id == b.id
+                    // Moving shapes never stop so only the others are compared
+                    && (a.frame.is_some()
+                        || a.shape
+                            .location
+                            .position
+                            .distance(b.shape.location.position)
+                            < AUTOSAVE_POSITION_TOLERANCE
+                            && (a.shape.location.angle - b.shape.location.angle).abs()
+                                < AUTOSAVE_ANGLE_TOLERANCE)
+            })
+    }
+}
+
+impl SavedShape {
+    pub fn velocity(&self) -> Velocity {
+        Velocity {
+            linvel: Vec2::from_array(self.linvel),
+            angvel: self.angvel,
+        }
+    }
+}
+
+fn autosave_when_settled(
+    current_level: Res<CurrentLevel>,
+    shapes: Query<SavedShapeQueryItem>,
+    dragged: Query<(), With<BeingDragged>>,
+    mut autosave: ResMut<Autosave>,
+) {
+    if !dragged.is_empty() {
+        return;
+    }
+
+    let settled = shapes.iter().all(|(_, _, _, _, velocity, _, _, moving)| {
+        // Moving shapes are kinematic and never settle
+        moving.is_some()
+            || (velocity.linvel.length() < SETTLED_LINEAR_SPEED
+                && velocity.angvel.abs() < SETTLED_ANGULAR_SPEED)
+    });
+    if !settled {
+        return;
+    }
+
+    match WorldSave::new(&current_level, &shapes) {
+        Some(save) => {
+            if autosave.0.as_ref().is_some_and(|x| x.is_close_to(&save)) {
+                return;
+            }
+            debug!("Autosaving {} shapes", save.shapes.len());
+            autosave.0 = Some(save);
+        }
+        None => {
+            if autosave.0.is_some() {
+                autosave.0 = None;
+            }
+        }
+    }
+}
+
+/// The app may never come back from the background so save immediately, settled or not
+fn autosave_when_backgrounded(
+    mut lifetime_events: EventReader<ApplicationLifetime>,
+    mut focus_events: EventReader<WindowFocused>,
+    current_level: Res<CurrentLevel>,
+    shapes: Query<SavedShapeQueryItem>,
+    mut autosave: ResMut<Autosave>,
+) {
+    let suspended = lifetime_events
+        .read()
+        .any(|x| matches!(x, ApplicationLifetime::Suspended));
+    let unfocused = focus_events.read().any(|x| !x.focused);
+
+    if !suspended && !unfocused {
+        return;
+    }
+
+    let save = WorldSave::new(&current_level, &shapes);
+    if autosave.0 != save {
+        debug!("Autosaving on entering background");
+        autosave.0 = save;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn save(x: f32) -> WorldSave {
+        WorldSave {
+            level: GameLevel::Infinite {
+                seed: 123,
+                target: 0,
+            },
+            stage: 2,
+            shapes: vec![SavedShape {
+                shape: EncodableShape {
+                    shape: ShapeIndex(0),
+                    location: Location::new(x, 0.0, 0.0),
+                    state: ShapeState::Normal,
+                    modifiers: ShapeModifiers::Normal,
+                },
+                linvel: [0.0, 0.0],
+                angvel: 0.0,
+                stage: 0,
+                id: None,
+                frame: None,
+            }],
+        }
+    }
+
+    #[test]
+    pub fn test_is_close_to() {
+        assert!(save(0.0).is_close_to(&save(0.1)));
+        assert!(!save(0.0).is_close_to(&save(10.0)));
+
+        let mut later_stage = save(0.0);
+        later_stage.stage = 3;
+        assert!(!save(0.0).is_close_to(&later_stage));
+    }
+
+    #[test]
+    pub fn test_moving_shapes_are_always_close() {
+        let moving = |x: f32, frame: u32| {
+            let mut save = save(x);
+            save.shapes[0].frame = Some(frame);
+            save
+        };
+
+        assert!(moving(0.0, 10).is_close_to(&moving(10.0, 20)));
+    }
+
+    #[test]
+    pub fn test_matches() {
+        let current_level = CurrentLevel::new(
+            GameLevel::Infinite {
+                seed: 123,
+                target: 0,
+            },
+            LevelCompletion::Incomplete { stage: 2 },
+            None,
+        );
+        assert!(save(0.0).matches(&current_level));
+
+        let other_seed = CurrentLevel::new(
+            GameLevel::Infinite {
+                seed: 456,
+                target: 0,
+            },
+            LevelCompletion::Incomplete { stage: 2 },
+            None,
+        );
+        assert!(!save(0.0).matches(&other_seed));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use chrono::Days;
+use chrono::{Days, NaiveDate};
 use itertools::Itertools;
 use std::marker::PhantomData;
 
@@ -10,6 +10,8 @@ impl<U: UITrait> Plugin for ChangeLevelPlugin<U> {
     fn build(&self, app: &mut App) {
         app.add_systems(First, handle_change_level_events::<U>);
         app.add_systems(Update, skip_tutorial_completion);
+        app.init_resource::<ReplayPlayback>();
+        app.init_resource::<PendingReset>();
         app.register_async_event::<ChangeLevelEvent>();
     }
 }
@@ -21,6 +23,8 @@ pub fn handle_change_level_events<U: UITrait>(
     streak: Res<Streak>,
     completion: Res<CampaignCompletion>,
     demo_resource: Res<DemoResource>,
+    mut replay_playback: ResMut<ReplayPlayback>,
+    mut pending_reset: ResMut<PendingReset>,
 ) {
     if let Some(event) = change_level_events.read().next() {
         let (level, stage) =
@@ -30,7 +34,30 @@ pub fn handle_change_level_events<U: UITrait>(
 
         let saved_data = event.get_saved_data();
 
-        current_level.set_if_neq(CurrentLevel::new(level, completion, saved_data));
+        let new_level = CurrentLevel::new(level, completion, saved_data);
+        if matches!(event, ChangeLevelEvent::ResetLevel) {
+            // Resetting must respawn the shapes even if the level has not changed
+            *current_level = new_level;
+            pending_reset.0 = true;
+        } else {
+            current_level.set_if_neq(new_level);
+        }
+
+        match event {
+            ChangeLevelEvent::PlayReplay { replay, mode } => {
+                *replay_playback = ReplayPlayback::new(replay.clone(), *mode);
+            }
+            ChangeLevelEvent::ResetLevel => {
+                if let Some((replay, mode)) = replay_playback.replay.clone() {
+                    *replay_playback = ReplayPlayback::new(replay, mode);
+                }
+            }
+            _ => {
+                if replay_playback.replay.is_some() {
+                    *replay_playback = ReplayPlayback::default();
+                }
+            }
+        }
 
         global_ui_state.minimize();
     }
@@ -53,7 +80,17 @@ pub enum ChangeLevelEvent {
     ResetLevel,
     //StartTutorial,
     StartInfinite,
+    /// Play infinite mode with a particular seed, e.g. one that was shared.
+    /// The target is the stage to beat, or zero.
+    ChooseInfinite {
+        seed: u64,
+        target: usize,
+    },
     StartChallenge,
+    /// Replay the challenge from a particular day. This does not count towards the streak.
+    ChooseChallenge {
+        date: NaiveDate,
+    },
     Load(std::sync::Arc<Vec<u8>>),
     Credits,
     Begging,
@@ -61,6 +98,11 @@ pub enum ChangeLevelEvent {
     Custom {
         level: std::sync::Arc<DesignedLevel>,
     },
+
+    PlayReplay {
+        replay: std::sync::Arc<Replay>,
+        mode: ReplayMode,
+    },
 }
 
 impl ChangeLevelEvent {
@@ -70,22 +112,54 @@ impl ChangeLevelEvent {
         }
 
         use base64::Engine;
-        if path.to_ascii_lowercase().starts_with("/game") {
+        if let Some(data) = strip_path_prefix(&path, "/game") {
             //info!("Path starts with game");
-            let data = path[6..].to_string();
             //info!("{data}");
-            match base64::engine::general_purpose::URL_SAFE.decode(data) {
+            match base64::engine::general_purpose::URL_SAFE
+                .decode(data)
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| {
+                    ShapesVec::from_bytes(&bytes)?;
+                    Ok(bytes)
+                }) {
                 Ok(bytes) => {
                     //info!("Decoded data");
                     return Some(ChangeLevelEvent::Load(std::sync::Arc::new(bytes)));
                 }
+                Err(err) => warn!("Could not load game: {err}"),
+            }
+        }
+
+        if let Some(data) = strip_path_prefix(&path, "/replay") {
+            match base64::engine::general_purpose::URL_SAFE
+                .decode(data)
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| Replay::from_bytes(&bytes))
+            {
+                Ok(replay) => {
+                    return Some(ChangeLevelEvent::PlayReplay {
+                        replay: replay.into(),
+                        mode: ReplayMode::Playback,
+                    });
+                }
+                Err(err) => warn!("{err}"),
+            }
+        }
+
+        if let Some(data) = strip_path_prefix(&path, "/infinite") {
+            match data.parse::<InfiniteShareCode>() {
+                Ok(code) => {
+                    return Some(ChangeLevelEvent::ChooseInfinite {
+                        seed: code.seed,
+                        target: code.stage,
+                    })
+                }
                 Err(err) => warn!("{err}"),
             }
         }
 
-        if path.to_ascii_lowercase().starts_with("/custom") {
-            let data = path[8..].to_string();
-            return Some(ChangeLevelEvent::make_custom(data.as_str()));
+        if let Some(data) = strip_path_prefix(&path, "/custom") {
+            return Some(ChangeLevelEvent::make_custom(data));
         }
 
         if path.to_ascii_lowercase().starts_with("/cheat") {
@@ -108,7 +182,7 @@ impl ChangeLevelEvent {
     pub fn get_saved_data(&self) -> Option<ShapesVec> {
         match self {
             ChangeLevelEvent::ChooseCampaignLevel { saved_data, .. } => {
-                saved_data.as_ref().map(|data| ShapesVec::from_bytes(&data))
+                saved_data.as_ref().and_then(|data| ShapesVec::from_bytes(&data).ok())
             }
             _ => None,
         }
@@ -163,6 +237,13 @@ impl ChangeLevelEvent {
             },
             ChangeLevelEvent::ResetLevel => (level.clone(), 0),
             ChangeLevelEvent::StartInfinite => (GameLevel::new_infinite(), 0),
+            ChangeLevelEvent::ChooseInfinite { seed, target } => (
+                GameLevel::Infinite {
+                    seed: *seed,
+                    target: *target,
+                },
+                0,
+            ),
             ChangeLevelEvent::StartChallenge => {
                 let today = get_today_date();
 
@@ -188,6 +269,24 @@ impl ChangeLevelEvent {
                     0,
                 )
             }
+            ChangeLevelEvent::ChooseChallenge { date } => {
+                if *date == get_today_date() {
+                    ChangeLevelEvent::StartChallenge.get_new_level(
+                        level,
+                        streak_data,
+                        completion,
+                        demo_resource,
+                    )
+                } else {
+                    (
+                        GameLevel::Challenge {
+                            date: *date,
+                            streak: 0,
+                        },
+                        0,
+                    )
+                }
+            }
 
             ChangeLevelEvent::ChooseCampaignLevel { index, stage, .. } => {
                 let index = *index;
@@ -208,17 +307,26 @@ impl ChangeLevelEvent {
                 )
             }
             ChangeLevelEvent::Load(bytes) => {
-                let decoded = ShapesVec::from_bytes(&bytes);
+                let (decoded, text) = match ShapesVec::from_bytes(&bytes) {
+                    Ok(decoded) => (decoded, None),
+                    Err(err) => {
+                        warn!("Could not load game: {err}");
+                        (ShapesVec::default(), Some(err.to_string()))
+                    }
+                };
                 let shapes: Vec<ShapeCreation> =
                     decoded.0.into_iter().map(|x| x.into()).collect_vec();
                 let initial_stage = LevelStage {
-                    text: None,
+                    text,
                     mouse_text: None,
                     text_forever: false,
                     shapes,
                     updates: vec![],
                     outlines: vec![],
                     arrows: vec![],
+                    joints: vec![],
+                    zones: vec![],
+                    water: None,
                     gravity: None,
                     rainfall: None,
                     fireworks: FireworksSettings::default(),
@@ -233,6 +341,7 @@ impl ChangeLevelEvent {
                     leaderboard_id: None,
                     end_fireworks: FireworksSettings::default(),
                     stars: None,
+                    objectives: LevelObjectives::default(),
                     flashing_button: None,
                 };
 
@@ -253,6 +362,7 @@ impl ChangeLevelEvent {
                 },
                 0,
             ),
+            ChangeLevelEvent::PlayReplay { replay, .. } => (replay.level.clone(), 0),
             ChangeLevelEvent::Begging => (GameLevel::Begging, 0),
             ChangeLevelEvent::Credits => (GameLevel::CREDITS, 0),
         }
@@ -291,3 +401,43 @@ impl ChangeLevelEvent {
         })
     }
 }
+
+/// The rest of the path after `prefix` and any slashes. The prefix is not case sensitive.
+fn strip_path_prefix<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    let start = path.get(..prefix.len())?;
+    start
+        .eq_ignore_ascii_case(prefix)
+        .then(|| path[prefix.len()..].trim_start_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_strip_path_prefix() {
+        assert_eq!(strip_path_prefix("/replay", "/replay"), Some(""));
+        assert_eq!(strip_path_prefix("/Replay/abc", "/replay"), Some("abc"));
+        assert_eq!(strip_path_prefix("/game", "/replay"), None);
+        assert_eq!(strip_path_prefix("/g", "/game"), None);
+    }
+
+    #[test]
+    pub fn test_bare_paths_do_not_panic() {
+        for path in ["/game", "/replay", "/infinite", "/custom", "/replay/"] {
+            let _ = ChangeLevelEvent::try_from_path(path.to_string());
+        }
+    }
+
+    #[test]
+    pub fn test_infinite_path_keeps_target() {
+        let event = ChangeLevelEvent::try_from_path("/infinite/42/7".to_string());
+        assert!(matches!(
+            event,
+            Some(ChangeLevelEvent::ChooseInfinite {
+                seed: 42,
+                target: 7
+            })
+        ));
+    }
+}
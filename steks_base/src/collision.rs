@@ -72,7 +72,7 @@ fn display_collision_markers(
 
     for (sensor_entity, wall_transform, wall) in walls
         .iter()
-        .filter(|x| x.2.show_marker(current_level.as_ref()))
+        .filter(|x| show_wall_marker(x.2, current_level.as_ref()))
     {
         for contact in rapier_context
             .contacts_with(sensor_entity)
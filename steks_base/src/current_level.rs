@@ -55,6 +55,10 @@ impl CurrentLevel {
     }
 }
 
+/// Set when the level is reset so the shapes are spawned from scratch, even though the level is the same
+#[derive(Resource, Default, Debug, PartialEq)]
+pub struct PendingReset(pub bool);
+
 #[derive(Default, Debug, PartialEq)]
 pub struct PreviousLevel(pub Option<(GameLevel, LevelCompletion)>);
 
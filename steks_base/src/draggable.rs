@@ -8,10 +8,6 @@ use crate::prelude::*;
 use std::f32::consts::TAU;
 use std::marker::PhantomData;
 
-const POSITION_DAMPING: f32 = 1.0;
-const POSITION_STIFFNESS: f32 = 20.0;
-const MAX_FORCE: f32 = 800.0;
-
 #[derive(Debug, Default)]
 pub struct DragPlugin<U: UITrait>(PhantomData<U>);
 
@@ -23,6 +19,8 @@ impl<U: UITrait> Plugin for DragPlugin<U> {
                 drag_start::<U>
                     .after(input::mousebutton_listener)
                     .after(input::touch_listener)
+                    .after(keyboard_cursor_listener)
+                    .after(gamepad_listener::<U>)
                     .before(handle_drag_changes),
             )
             .add_systems(
@@ -30,6 +28,8 @@ impl<U: UITrait> Plugin for DragPlugin<U> {
                 drag_move
                     .after(input::mousebutton_listener)
                     .after(input::touch_listener)
+                    .after(keyboard_cursor_listener)
+                    .after(gamepad_listener::<U>)
                     .before(handle_drag_changes),
             )
             .add_systems(Update, assign_padlock)
@@ -38,6 +38,7 @@ impl<U: UITrait> Plugin for DragPlugin<U> {
                 handle_rotate_events
                     .after(input::keyboard_listener)
                     .after(input::mousewheel_listener)
+                    .after(gamepad_listener::<U>)
                     .before(handle_drag_changes),
             )
             .add_systems(
@@ -45,6 +46,8 @@ impl<U: UITrait> Plugin for DragPlugin<U> {
                 drag_end
                     .after(input::mousebutton_listener)
                     .after(input::touch_listener)
+                    .after(keyboard_cursor_listener)
+                    .after(gamepad_listener::<U>)
                     .before(handle_drag_changes),
             )
             .add_systems(Update, detach_stuck_shapes_on_pickup)
@@ -193,18 +196,7 @@ fn apply_forces(
     for (transform, mut external_force, velocity, dragged) in dragged_entities.iter_mut() {
         let distance = dragged.desired_position - transform.translation.truncate();
 
-        let force = (distance * POSITION_STIFFNESS) - (velocity.linvel * POSITION_DAMPING);
-
-        let clamped_force = if force.length() > 0.
-            && velocity.linvel.length() > 0.
-            && force.angle_between(velocity.linvel).abs() > std::f32::consts::FRAC_PI_2
-        {
-            force // force is in opposite direction to velocity so don't clamp it
-        } else {
-            force.clamp_length_max(MAX_FORCE)
-        };
-
-        external_force.force = clamped_force;
+        external_force.force = drag_force(distance, velocity.linvel);
     }
 }
 
@@ -503,6 +495,7 @@ pub fn handle_drag_changes(
             &mut ExternalForce,
             &mut Restitution,
             &mut CollisionGroups,
+            &ShapeModifiers,
         ),
         Changed<ShapeComponent>,
     >,
@@ -520,13 +513,14 @@ pub fn handle_drag_changes(
         mut external_force,
         mut restitution,
         mut collision_groups,
+        modifiers,
     ) in query.iter_mut()
     {
         *locked_axes = draggable.locked_axes();
-        *mass = draggable.collider_mass_properties();
+        *mass = draggable.collider_mass_properties(modifiers);
         *gravity_scale = draggable.gravity_scale();
         *dominance = draggable.dominance();
-        restitution.coefficient = draggable.restitution_coefficient();
+        *restitution = draggable.restitution(modifiers);
         collision_groups.filters = draggable.collision_group_filters();
 
         if !draggable.is_free() {
@@ -621,6 +615,8 @@ pub struct CheckForTowerEvent;
 pub enum DragSource {
     Mouse,
     Touch { touch_id: u64 },
+    Keyboard,
+    Gamepad,
 }
 
 impl DragSource {
@@ -643,13 +639,13 @@ impl ShapeComponent {
         }
     }
 
-    pub fn collider_mass_properties(&self) -> ColliderMassProperties {
+    pub fn collider_mass_properties(&self, modifiers: &ShapeModifiers) -> ColliderMassProperties {
         match self {
-            ShapeComponent::Free => ColliderMassProperties::default(),
-            ShapeComponent::Fixed => ColliderMassProperties::default(),
             ShapeComponent::Dragged(_) => ColliderMassProperties::Density(DRAGGED_DENSITY),
-            ShapeComponent::Locked => ColliderMassProperties::default(),
-            ShapeComponent::Void => ColliderMassProperties::default(),
+            ShapeComponent::Free
+            | ShapeComponent::Fixed
+            | ShapeComponent::Locked
+            | ShapeComponent::Void => ColliderMassProperties::Density(modifiers.density()),
         }
     }
 
@@ -673,13 +669,16 @@ impl ShapeComponent {
         }
     }
 
-    pub fn restitution_coefficient(&self) -> f32 {
+    pub fn restitution(&self, modifiers: &ShapeModifiers) -> Restitution {
         match self {
-            ShapeComponent::Free => DEFAULT_RESTITUTION,
-            ShapeComponent::Fixed => DEFAULT_RESTITUTION,
-            ShapeComponent::Dragged(_) => 0.0,
-            ShapeComponent::Locked => DEFAULT_RESTITUTION,
-            ShapeComponent::Void => DEFAULT_RESTITUTION,
+            ShapeComponent::Dragged(_) => Restitution {
+                coefficient: 0.0,
+                combine_rule: CoefficientCombineRule::Min,
+            },
+            ShapeComponent::Free
+            | ShapeComponent::Fixed
+            | ShapeComponent::Locked
+            | ShapeComponent::Void => modifiers.restitution(),
         }
     }
 
@@ -0,0 +1,247 @@
+use bevy_prototype_lyon::prelude::*;
+
+use crate::prelude::*;
+
+/// Streaks move this many pixels per second for each unit of strength
+const STREAK_SPEED_PER_STRENGTH: f32 = 0.25;
+const STREAK_LENGTH: f32 = 20.0;
+/// One streak is drawn for each of this many square pixels of zone
+const STREAK_AREA: f32 = 5000.0;
+const MAX_STREAKS: usize = 40;
+
+#[derive(Debug, Default)]
+pub struct ForceZonePlugin;
+
+impl Plugin for ForceZonePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveForceZones>()
+            .add_systems(Update, manage_force_zones)
+            .add_systems(Update, animate_force_zones.after(manage_force_zones))
+            .add_systems(
+                FixedUpdate,
+                push_free_shapes.before(PhysicsSet::SyncBackend),
+            );
+    }
+}
+
+/// The force zones of the current stage
+#[derive(Debug, Clone, Default, PartialEq, Resource)]
+pub struct ActiveForceZones {
+    pub zones: Vec<ForceZone>,
+    /// Physics frames since the zones appeared
+    pub frame: u32,
+}
+
+impl ActiveForceZones {
+    /// The force on a shape at this position after this many more frames
+    pub fn force_after(&self, position: Vec2, frames: u32) -> Vec2 {
+        ForceZone::total_force(&self.zones, position, self.frame + frames)
+    }
+}
+
+/// The translucent area of a force zone
+#[derive(Debug, Component)]
+pub struct ZoneRegion {
+    pub zone: ForceZone,
+}
+
+/// A streak drifting through a force zone in the direction of the push
+#[derive(Debug, Component)]
+pub struct ZoneStreak {
+    pub zone: ForceZone,
+    /// Where the streak starts, relative to the center of the zone
+    pub start: Vec2,
+}
+
+fn manage_force_zones(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    mut active: ResMut<ActiveForceZones>,
+    regions: Query<Entity, With<ZoneRegion>>,
+) {
+    if !current_level.is_changed() {
+        return;
+    }
+
+    let zones = current_level
+        .level
+        .get_force_zones(current_level.completion);
+    if zones == active.zones {
+        return;
+    }
+
+    for entity in regions.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for zone in zones.iter() {
+        spawn_zone(&mut commands, zone);
+    }
+
+    *active = ActiveForceZones { zones, frame: 0 };
+}
+
+fn spawn_zone(commands: &mut Commands, zone: &ForceZone) {
+    let half_extents = zone.half_extents();
+    let path = match zone.kind {
+        ZoneKind::Rectangle => GeometryBuilder::build_as(&shapes::Rectangle {
+            extents: half_extents * 2.0,
+            origin: RectangleOrigin::Center,
+        }),
+        ZoneKind::Circle => GeometryBuilder::build_as(&shapes::Circle {
+            radius: zone.radius,
+            center: Vec2::ZERO,
+        }),
+    };
+
+    let direction = zone.direction_vector();
+    let streak_path = GeometryBuilder::build_as(&shapes::Line(
+        direction * STREAK_LENGTH * -0.5,
+        direction * STREAK_LENGTH * 0.5,
+    ));
+    let streaks =
+        ((half_extents.x * half_extents.y * 4.0 / STREAK_AREA) as usize).clamp(1, MAX_STREAKS);
+
+    commands
+        .spawn((
+            ShapeBundle {
+                path,
+                spatial: SpatialBundle::from_transform(Transform::from_translation(
+                    zone.position().extend(0.5),
+                )),
+                ..Default::default()
+            },
+            Fill::color(FORCE_ZONE_FILL),
+            ZoneRegion { zone: *zone },
+        ))
+        .with_children(|cb| {
+            for index in 0..streaks {
+                // Spread the streaks out without lining them up
+                let start = Vec2::new(
+                    (index as f32 * 0.618_034).fract(),
+                    (index as f32 * 0.754_878).fract(),
+                );
+                let start = ((start * 2.0) - Vec2::ONE) * half_extents;
+
+                cb.spawn((
+                    ShapeBundle {
+                        path: streak_path.clone(),
+                        spatial: SpatialBundle::from_transform(Transform::from_translation(
+                            start.extend(0.1),
+                        )),
+                        ..Default::default()
+                    },
+                    Stroke {
+                        color: FORCE_ZONE_STREAK,
+                        options: StrokeOptions::default()
+                            .with_line_width(FORCE_ZONE_STREAK_WIDTH)
+                            .with_start_cap(LineCap::Round)
+                            .with_end_cap(LineCap::Round),
+                    },
+                    ZoneStreak { zone: *zone, start },
+                ));
+            }
+        });
+}
+
+/// Fades the zones in and out with their gusts and drifts the streaks along
+fn animate_force_zones(
+    time: Res<Time>,
+    active: Res<ActiveForceZones>,
+    mut regions: Query<(&ZoneRegion, &mut Fill)>,
+    mut streaks: Query<(&ZoneStreak, &mut Transform, &mut Visibility, &mut Stroke)>,
+) {
+    for (region, mut fill) in regions.iter_mut() {
+        let gust = region.zone.gust_factor(active.frame);
+        fill.color = FORCE_ZONE_FILL.with_a(FORCE_ZONE_FILL.a() * (0.5 + (gust * 0.5)));
+    }
+
+    for (streak, mut transform, mut visibility, mut stroke) in streaks.iter_mut() {
+        let zone = &streak.zone;
+        let half_extents = (zone.half_extents() - Vec2::splat(STREAK_LENGTH * 0.5)).max(Vec2::ZERO);
+        let travelled = zone.direction_vector()
+            * zone.strength
+            * STREAK_SPEED_PER_STRENGTH
+            * time.elapsed_seconds();
+        let position = wrap(streak.start + travelled, half_extents);
+
+        transform.translation = position.extend(transform.translation.z);
+        *visibility = if zone.kind.is_circle() && position.length() > half_extents.x {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+        stroke.color =
+            FORCE_ZONE_STREAK.with_a(FORCE_ZONE_STREAK.a() * zone.gust_factor(active.frame));
+    }
+}
+
+/// Wraps the position around so it stays within the extents
+fn wrap(position: Vec2, half_extents: Vec2) -> Vec2 {
+    let wrap_axis = |p: f32, half: f32| {
+        if half <= 0.0 {
+            0.0
+        } else {
+            (p + half).rem_euclid(half * 2.0) - half
+        }
+    };
+
+    Vec2::new(
+        wrap_axis(position.x, half_extents.x),
+        wrap_axis(position.y, half_extents.y),
+    )
+}
+
+/// Sets the force from the force zones and the water on each free shape before the physics step.
+/// `PredictionContext` does the same so the win countdown takes them into account.
+fn push_free_shapes(
+    mut active: ResMut<ActiveForceZones>,
+    water: Res<ActiveWater>,
+    rapier_config: Res<RapierConfiguration>,
+    mut shapes: Query<(
+        &Transform,
+        &ShapeComponent,
+        &ShapeIndex,
+        &Velocity,
+        &mut ExternalForce,
+    )>,
+) {
+    if active.zones.is_empty() && water.0.is_none() && !active.is_changed() && !water.is_changed() {
+        return;
+    }
+    active.frame += 1;
+
+    for (transform, shape_component, shape_index, velocity, mut external_force) in shapes.iter_mut()
+    {
+        if !shape_component.is_free() {
+            continue;
+        }
+
+        let location: Location = transform.into();
+        let water_force = water.force_on(
+            shape_index.game_shape(),
+            &location,
+            velocity,
+            rapier_config.gravity,
+        );
+
+        external_force.force = active.force_after(location.position, 0) + water_force.force;
+        external_force.torque = water_force.torque;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_wrap() {
+        let half_extents = Vec2::new(10.0, 5.0);
+
+        assert_eq!(wrap(Vec2::new(3.0, 2.0), half_extents), Vec2::new(3.0, 2.0));
+        assert_eq!(
+            wrap(Vec2::new(12.0, -7.0), half_extents),
+            Vec2::new(-8.0, 3.0)
+        );
+        assert_eq!(wrap(Vec2::new(12.0, 1.0), Vec2::ZERO), Vec2::ZERO);
+    }
+}
@@ -11,12 +11,24 @@ use crate::prelude::*;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, EnumIs)]
 pub enum GameLevel {
-    Designed { meta: DesignedLevelMeta },
-
-    Infinite { seed: u64 },
-    Challenge { date: NaiveDate, streak: u16 },
-
-    Loaded { bytes: Arc<Vec<u8>> },
+    Designed {
+        meta: DesignedLevelMeta,
+    },
+
+    Infinite {
+        seed: u64,
+        /// The stage reached by whoever shared this seed, or zero
+        #[serde(default)]
+        target: usize,
+    },
+    Challenge {
+        date: NaiveDate,
+        streak: u16,
+    },
+
+    Loaded {
+        bytes: Arc<Vec<u8>>,
+    },
 
     Begging,
 }
@@ -35,15 +47,27 @@ impl GameLevel {
         shapes: &ShapesVec,
         world_records: &Res<WorldRecords>,
         pbs: &Res<PersonalBests>,
+        tracker: &ObjectiveTracker,
     ) -> ScoreInfo {
-        let height = shapes.calculate_tower_height();
+        let height = shapes.calculate_tower_height_above(self.water_line());
         let hash = shapes.hash();
 
-        let old_wr: Option<f32> = world_records.map.get(&hash).map(|x| x.calculate_height());
-        let old_height = pbs.map.get(&hash);
+        let key = RecordKey::new(self, hash);
+
+        let old_wr: Option<f32> = world_records
+            .get(&key)
+            .map(|x| x.calculate_height_above(self.water_line()));
+        let old_height = pbs.get(&key);
 
         let pb = old_height.map(|x| x.height).unwrap_or(0.0);
-        let star = self.get_level_stars().map(|x| x.get_star(height));
+        let seconds = tracker.seconds();
+        let moves = tracker.pickups;
+        let score = LevelScore {
+            height,
+            seconds,
+            moves,
+        };
+        let star = self.get_level_stars().map(|x| x.get_star(&score));
 
         let wr = match old_wr {
             Some(old_wr) => {
@@ -63,6 +87,8 @@ impl GameLevel {
             wr,
             pb,
             star,
+            seconds,
+            moves,
         }
     }
 
@@ -151,6 +177,40 @@ impl GameLevel {
         }
     }
 
+    pub fn get_water(&self, completion: LevelCompletion) -> Option<WaterSettings> {
+        match self {
+            GameLevel::Designed { meta, .. } => {
+                meta.get_level().get_current_stage(completion).water
+            }
+            _ => None,
+        }
+    }
+
+    /// Towers are measured from here instead of from their lowest shape.
+    /// This is the water of the last stage, so it can differ from `get_water` before the level is complete.
+    pub fn water_line(&self) -> Option<f32> {
+        match self {
+            GameLevel::Designed { meta, .. } => meta.get_level().water_line(),
+            _ => None,
+        }
+    }
+
+    pub fn get_force_zones(&self, completion: LevelCompletion) -> Vec<ForceZone> {
+        match self {
+            GameLevel::Designed { meta, .. } => {
+                meta.get_level().get_current_stage(completion).zones.clone()
+            }
+            _ => vec![],
+        }
+    }
+
+    pub fn get_objectives(&self) -> LevelObjectives {
+        match self {
+            GameLevel::Designed { meta, .. } => meta.get_level().objectives,
+            _ => LevelObjectives::default(),
+        }
+    }
+
     pub fn create_initial_shapes(&self) -> Vec<ShapeCreationData> {
         let mut shapes: Vec<ShapeCreationData> = match self {
             GameLevel::Designed { meta, .. } => match meta.get_level().get_stage(&0) {
@@ -163,30 +223,31 @@ impl GameLevel {
                     .collect_vec(),
                 None => vec![],
             },
-            GameLevel::Loaded { bytes } => ShapesVec::from_bytes(&bytes)
-                .0
-                .into_iter()
-                .map(|encodable_shape| {
-                    ShapeCreationData::from_encodable(encodable_shape, ShapeStage(0))
-                })
-                .collect_vec(),
+            GameLevel::Loaded { bytes } => match ShapesVec::from_bytes(&bytes) {
+                Ok(shapes) => shapes
+                    .0
+                    .into_iter()
+                    .map(|encodable_shape| {
+                        ShapeCreationData::from_encodable(encodable_shape, ShapeStage(0))
+                    })
+                    .collect_vec(),
+                Err(err) => {
+                    warn!("Could not load level: {err}");
+                    vec![]
+                }
+            },
             GameLevel::Challenge { date, .. } => {
                 //let today = get_today_date();
-                let seed = ((date.year().unsigned_abs() * 2000) + (date.month() * 100) + date.day())
-                    as u64;
-                let mut shape_rng: rand::rngs::StdRng = rand::SeedableRng::seed_from_u64(seed);
-                (0..CHALLENGE_SHAPES)
-                    .map(|_| {
-                        ShapeCreationData::from_shape_index(
-                            ShapeIndex::random_no_circle(&mut shape_rng),
-                            ShapeStage(0),
-                        )
-                        .with_random_velocity()
+                ShapeIndex::challenge_shapes(date.year().unsigned_abs(), date.month(), date.day())
+                    .into_iter()
+                    .map(|shape_index| {
+                        ShapeCreationData::from_shape_index(shape_index, ShapeStage(0))
+                            .with_random_velocity()
                     })
                     .collect_vec()
             }
 
-            GameLevel::Infinite { seed } => {
+            GameLevel::Infinite { seed, .. } => {
                 crate::infinity::get_all_shapes(*seed, INFINITE_MODE_STARTING_SHAPES)
             }
 
@@ -224,7 +285,7 @@ impl GameLevel {
                     }
                 }
             }
-            GameLevel::Infinite { seed } => {
+            GameLevel::Infinite { seed, .. } => {
                 let next_shapes = crate::infinity::get_all_shapes(
                     *seed,
                     current_stage + INFINITE_MODE_STARTING_SHAPES,
@@ -239,6 +300,25 @@ impl GameLevel {
             GameLevel::Begging => {}
         }
     }
+
+    /// The joints to create when moving to the current stage.
+    /// If there is no previous stage, the joints from every stage so far are created.
+    pub fn generate_joints(
+        &self,
+        previous_stage: Option<usize>,
+        current_stage: usize,
+    ) -> Vec<ShapeJoint> {
+        let GameLevel::Designed { meta } = self else {
+            return vec![];
+        };
+        let level = meta.get_level();
+        let first_stage = previous_stage.map(|x| x + 1).unwrap_or_default();
+
+        (first_stage..=current_stage)
+            .filter_map(|stage| level.get_stage(&stage))
+            .flat_map(|stage| stage.joints.iter().copied())
+            .collect()
+    }
 }
 
 impl GameLevel {
@@ -286,9 +366,16 @@ impl GameLevel {
                     }
                 })
             }
-            GameLevel::Infinite { .. } => {
+            GameLevel::Infinite { seed, target } => {
                 if stage == 0 {
-                    None
+                    if *target > 0 {
+                        let target_shapes = target + INFINITE_MODE_STARTING_SHAPES - 1;
+                        Some(format!("Seed {seed}\nBeat {target_shapes} shapes"))
+                    } else {
+                        Some(format!("Seed {seed}"))
+                    }
+                } else if *target > 0 && stage == target + 1 {
+                    Some("You beat the shared tower!".to_string())
                 } else {
                     let shapes = stage + INFINITE_MODE_STARTING_SHAPES - 1;
                     let line = crate::infinity::INFINITE_COMMENTS
@@ -339,7 +426,13 @@ impl GameLevel {
                 }
             }
             GameLevel::Infinite { .. } => (stage == 0).then(|| "Infinite Mode".to_string()),
-            GameLevel::Challenge { .. } => Some("Daily Challenge".to_string()),
+            GameLevel::Challenge { date, .. } => {
+                if get_today_date().eq(date) {
+                    Some("Daily Challenge".to_string())
+                } else {
+                    Some(format!("Challenge {date}"))
+                }
+            }
             GameLevel::Loaded { .. } => None,
             GameLevel::Begging { .. } => Some("Please buy the game!".to_string()), //users should not see this
         }
@@ -396,7 +489,7 @@ impl GameLevel {
         let mut rng: rand::rngs::ThreadRng = rand::rngs::ThreadRng::default();
         let seed = rng.next_u64();
 
-        Self::Infinite { seed }
+        Self::Infinite { seed, target: 0 }
     }
 
     pub const CREDITS: Self = GameLevel::Designed {
@@ -0,0 +1,171 @@
+use std::marker::PhantomData;
+
+use bevy_prototype_lyon::prelude::*;
+
+use crate::input::ONE_THIRTY_SECOND;
+use crate::prelude::*;
+
+/// Pixels per second with the stick pushed all the way
+const CURSOR_SPEED: f32 = 500.0;
+/// Stick movements smaller than this are ignored
+const STICK_DEADZONE: f32 = 0.2;
+const CURSOR_RADIUS: f32 = 8.0;
+const CURSOR_Z: f32 = 46.0;
+
+#[derive(Debug, Default)]
+pub struct GamepadPlugin<U: UITrait>(PhantomData<U>);
+
+impl<U: UITrait> Plugin for GamepadPlugin<U> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GamepadCursor>()
+            .add_systems(Update, gamepad_listener::<U>)
+            .add_systems(
+                Update,
+                draw_gamepad_cursor::<U>.after(gamepad_listener::<U>),
+            );
+    }
+}
+
+/// A virtual pointer moved with the left stick.
+/// The south face button picks up and drops, the east face button drops and the shoulder buttons rotate.
+#[derive(Debug, Default, Resource, PartialEq)]
+pub struct GamepadCursor {
+    pub position: Vec2,
+    pub holding: bool,
+}
+
+#[derive(Debug, Component)]
+pub struct GamepadCursorMarker;
+
+/// The first connected gamepad
+pub fn first_gamepad(gamepads: &Gamepads) -> Option<Gamepad> {
+    gamepads.iter().next()
+}
+
+pub fn gamepad_listener<U: UITrait>(
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    buttons: Res<Input<GamepadButton>>,
+    time: Res<Time>,
+    ui: Res<U>,
+    mut cursor: ResMut<GamepadCursor>,
+    shapes: Query<&ShapeComponent>,
+    mut ew_drag_start: EventWriter<DragStartEvent>,
+    mut ew_drag_move: EventWriter<DragMoveEvent>,
+    mut ew_drag_end: EventWriter<DragEndingEvent>,
+    mut ew_rotate: EventWriter<RotateEvent>,
+) {
+    const DRAG_SOURCE: DragSource = DragSource::Gamepad;
+
+    let Some(gamepad) = first_gamepad(&gamepads) else {
+        return;
+    };
+
+    // The shape may have been fixed in place or the pick up may have missed
+    if cursor.holding && !shapes.iter().any(|x| x.has_drag_source(DRAG_SOURCE)) {
+        cursor.holding = false;
+    }
+
+    // The menu uses the face buttons while it is open
+    if !ui.is_minimized() {
+        return;
+    }
+
+    let just_pressed = |button_type| buttons.just_pressed(GamepadButton::new(gamepad, button_type));
+
+    if cursor.holding {
+        if just_pressed(GamepadButtonType::South) || just_pressed(GamepadButtonType::East) {
+            cursor.holding = false;
+            ew_drag_end.send(DragEndingEvent {
+                drag_source: DRAG_SOURCE,
+            });
+        }
+    } else if just_pressed(GamepadButtonType::South) {
+        cursor.holding = true;
+        ew_drag_start.send(DragStartEvent {
+            drag_source: DRAG_SOURCE,
+            position: cursor.position,
+        });
+    }
+
+    for (button_type, signum) in [
+        (GamepadButtonType::LeftTrigger, 1.0),
+        (GamepadButtonType::RightTrigger, -1.0),
+    ] {
+        if just_pressed(button_type) {
+            ew_rotate.send(RotateEvent {
+                delta: ONE_THIRTY_SECOND * signum,
+                snap_resolution: Some(ONE_THIRTY_SECOND),
+            });
+        }
+    }
+
+    let axis = |axis_type| {
+        axes.get(GamepadAxis::new(gamepad, axis_type))
+            .unwrap_or_default()
+    };
+    let stick = Vec2::new(
+        axis(GamepadAxisType::LeftStickX),
+        axis(GamepadAxisType::LeftStickY),
+    );
+    if stick.length() < STICK_DEADZONE {
+        return;
+    }
+
+    let max = Vec2::new(MAX_WINDOW_WIDTH, MAX_WINDOW_HEIGHT) * 0.5;
+    let new_position = (cursor.position
+        + stick.clamp_length_max(1.0) * CURSOR_SPEED * time.delta_seconds())
+    .clamp(-max, max);
+    cursor.position = new_position;
+
+    if cursor.holding {
+        ew_drag_move.send(DragMoveEvent {
+            drag_source: DRAG_SOURCE,
+            new_position,
+        });
+    }
+}
+
+/// Shows the cursor while a gamepad is connected and the menu is closed
+fn draw_gamepad_cursor<U: UITrait>(
+    mut commands: Commands,
+    gamepads: Res<Gamepads>,
+    ui: Res<U>,
+    cursor: Res<GamepadCursor>,
+    mut markers: Query<(&mut Transform, &mut Visibility), With<GamepadCursorMarker>>,
+) {
+    let visibility = if first_gamepad(&gamepads).is_some() && ui.is_minimized() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    let translation = cursor.position.extend(CURSOR_Z);
+
+    if markers.is_empty() {
+        if visibility == Visibility::Hidden {
+            return;
+        }
+
+        commands.spawn((
+            ShapeBundle {
+                path: GeometryBuilder::build_as(&shapes::Circle {
+                    radius: CURSOR_RADIUS,
+                    center: Vec2::ZERO,
+                }),
+                spatial: SpatialBundle::from_transform(Transform::from_translation(translation)),
+                ..Default::default()
+            },
+            Stroke {
+                color: FOCUS_RING_STROKE,
+                options: StrokeOptions::default().with_line_width(FOCUS_RING_WIDTH),
+            },
+            GamepadCursorMarker,
+        ));
+        return;
+    }
+
+    for (mut transform, mut marker_visibility) in markers.iter_mut() {
+        transform.translation = translation;
+        marker_visibility.set_if_neq(visibility);
+    }
+}
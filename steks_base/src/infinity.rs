@@ -1,8 +1,44 @@
+use std::{fmt::Display, str::FromStr};
+
 use itertools::Itertools;
 use rand::{rngs::StdRng, SeedableRng};
 
 use crate::prelude::*;
 
+/// A shareable infinite mode run: the seed and the stage that was reached.
+/// Written as `<seed>/<stage>` - the stage may be left off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InfiniteShareCode {
+    pub seed: u64,
+    pub stage: usize,
+}
+
+impl InfiniteShareCode {
+    /// The path to share, relative to the site root
+    pub fn to_path(&self) -> String {
+        format!("infinite/{self}")
+    }
+}
+
+impl Display for InfiniteShareCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.seed, self.stage)
+    }
+}
+
+impl FromStr for InfiniteShareCode {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (seed, stage) = match s.trim_matches('/').split_once('/') {
+            Some((seed, stage)) => (seed.parse()?, stage.parse()?),
+            None => (s.trim_matches('/').parse()?, 0),
+        };
+
+        Ok(Self { seed, stage })
+    }
+}
+
 pub fn get_initial_shapes(seed: u64) -> Vec<ShapeIndex> {
     let mut rng: StdRng = StdRng::seed_from_u64(seed);
     let mut shapes: Vec<ShapeIndex> = vec![];
@@ -74,7 +110,23 @@ mod tests {
 
     use crate::shape_creation_data::ShapeCreationData;
 
-    use super::get_all_shapes;
+    use super::{get_all_shapes, InfiniteShareCode};
+
+    #[test]
+    pub fn test_share_code_round_trip() {
+        let code = InfiniteShareCode {
+            seed: 12345678901234567890,
+            stage: 12,
+        };
+
+        assert_eq!(code.to_path(), "infinite/12345678901234567890/12");
+        assert_eq!(code.to_string().parse(), Ok(code));
+        assert_eq!(
+            "42".parse(),
+            Ok(InfiniteShareCode { seed: 42, stage: 0 })
+        );
+        assert!("forty two".parse::<InfiniteShareCode>().is_err());
+    }
 
     #[test_case::case(123)]
     #[test_case::case(456)]
@@ -11,6 +11,7 @@ pub struct InputPlugin;
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<InputSettings>()
+            .add_plugins(KeyboardCursorPlugin)
             .add_systems(Update, touch_listener)
             .add_systems(Update, keyboard_listener)
             .add_systems(Update, mousewheel_listener)
@@ -135,6 +136,7 @@ pub fn touch_listener(
 pub fn keyboard_listener(
     mut keyboard_events: EventReader<KeyboardInput>,
     mut rotate_events: EventWriter<RotateEvent>,
+    mut undo_events: EventWriter<UndoEvent>,
 
     //time: Res<Time>,
     //mut recent: Local<DiscreetRotate>,
@@ -146,6 +148,10 @@ pub fn keyboard_listener(
                 let positive = match code {
                     KeyCode::E => false,
                     KeyCode::Q => true,
+                    KeyCode::Z => {
+                        undo_events.send(UndoEvent);
+                        continue 'events;
+                    }
                     _ => continue 'events,
                 };
                 let signum = if positive {1.0} else{-1.0};
@@ -182,4 +188,4 @@ pub fn mousewheel_listener(
     }
 }
 
-const ONE_THIRTY_SECOND: f32 = std::f32::consts::TAU / 32.0;
+pub(crate) const ONE_THIRTY_SECOND: f32 = std::f32::consts::TAU / 32.0;
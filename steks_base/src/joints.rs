@@ -0,0 +1,121 @@
+use bevy_prototype_lyon::prelude::*;
+
+use crate::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct JointsPlugin;
+
+impl Plugin for JointsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingJoints>()
+            .add_systems(Update, create_pending_joints.after(spawn_and_update_shapes))
+            .add_systems(Update, draw_joint_connectors.after(create_pending_joints));
+    }
+}
+
+/// Joints waiting for both of their shapes to be spawned
+#[derive(Debug, Default, Resource)]
+pub struct PendingJoints(pub Vec<ShapeJoint>);
+
+/// Draws the connector between two joined shapes
+#[derive(Debug, Component)]
+pub struct JointConnector {
+    pub joint: ShapeJoint,
+    pub a: Entity,
+    pub b: Entity,
+}
+
+fn create_pending_joints(
+    mut commands: Commands,
+    mut pending: ResMut<PendingJoints>,
+    shapes: Query<(Entity, &ShapeWithId, &Transform)>,
+) {
+    if pending.0.is_empty() {
+        return;
+    }
+
+    pending.0.retain(|joint| {
+        let find = |id: u32| shapes.iter().find(|(_, shape, _)| shape.id == id);
+        let (Some((a, _, transform_a)), Some((b, _, transform_b))) = (find(joint.a), find(joint.b))
+        else {
+            return true;
+        };
+
+        let location_a: Location = transform_a.into();
+        let location_b: Location = transform_b.into();
+        debug!(
+            "Creating {:?} joint from {} to {}",
+            joint.kind, joint.a, joint.b
+        );
+
+        let generic: GenericJoint = match joint.kind {
+            JointKind::Revolute => RevoluteJointBuilder::new()
+                .local_anchor1(joint.anchor_a)
+                .local_anchor2(joint.anchor_b)
+                .into(),
+            JointKind::Fixed => FixedJointBuilder::new()
+                .local_anchor1(joint.anchor_a)
+                .local_anchor2(joint.anchor_b)
+                .local_basis2(location_a.angle - location_b.angle)
+                .into(),
+            JointKind::Rope => RopeJointBuilder::new(joint.rope_length(&location_a, &location_b))
+                .local_anchor1(joint.anchor_a)
+                .local_anchor2(joint.anchor_b)
+                .into(),
+        };
+
+        commands.entity(b).insert(ImpulseJoint::new(a, generic));
+        commands.spawn((
+            ShapeBundle {
+                path: connector_path(joint, &location_a, &location_b),
+                spatial: SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, 2.0)),
+                ..Default::default()
+            },
+            Stroke {
+                color: JOINT_STROKE,
+                options: StrokeOptions::default()
+                    .with_line_width(JOINT_STROKE_WIDTH)
+                    .with_start_cap(LineCap::Round)
+                    .with_end_cap(LineCap::Round),
+            },
+            JointConnector {
+                joint: *joint,
+                a,
+                b,
+            },
+        ));
+        false
+    });
+}
+
+fn draw_joint_connectors(
+    mut commands: Commands,
+    mut connectors: Query<(Entity, &JointConnector, &mut Path)>,
+    shapes: Query<&Transform, With<ShapeComponent>>,
+) {
+    for (entity, connector, mut path) in connectors.iter_mut() {
+        let (Ok(transform_a), Ok(transform_b)) = (shapes.get(connector.a), shapes.get(connector.b))
+        else {
+            // One of the shapes has been despawned
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+
+        *path = connector_path(&connector.joint, &transform_a.into(), &transform_b.into());
+    }
+}
+
+/// A line between the anchors with a pin where shapes are hinged or welded
+fn connector_path(joint: &ShapeJoint, a: &Location, b: &Location) -> Path {
+    let start = joint.world_anchor_a(a);
+    let end = joint.world_anchor_b(b);
+
+    let mut builder = GeometryBuilder::new().add(&shapes::Line(start, end));
+    if !joint.kind.is_rope() {
+        builder = builder.add(&shapes::Circle {
+            radius: JOINT_PIN_RADIUS,
+            center: end,
+        });
+    }
+    builder.build()
+}
@@ -0,0 +1,265 @@
+use bevy::input::keyboard::*;
+use bevy_prototype_lyon::prelude::*;
+
+use crate::prelude::*;
+
+/// Pixels per second
+const SLOW_SPEED: f32 = 100.0;
+/// Pixels per second, while shift is held
+const FAST_SPEED: f32 = 400.0;
+const FOCUS_RING_Z: f32 = 45.0;
+/// The gap between the focus ring and the furthest corner of the shape
+const FOCUS_RING_PADDING: f32 = 5.0;
+
+#[derive(Debug, Default)]
+pub struct KeyboardCursorPlugin;
+
+impl Plugin for KeyboardCursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KeyboardCursor>()
+            .add_systems(Update, keyboard_cursor_listener)
+            .add_systems(Update, draw_focus_ring.after(keyboard_cursor_listener));
+    }
+}
+
+/// Lets the player pick up and move shapes without a pointer.
+/// Tab cycles through the shapes, space or enter picks up and drops, and the arrow keys move.
+#[derive(Debug, Default, Resource, PartialEq)]
+pub struct KeyboardCursor {
+    /// The shape which will be picked up
+    pub focused: Option<Entity>,
+    /// Where the shape is being moved to, while it is held
+    pub holding: Option<Vec2>,
+}
+
+#[derive(Debug, Component)]
+pub struct FocusRing;
+
+pub fn keyboard_cursor_listener(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    keys: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut cursor: ResMut<KeyboardCursor>,
+    shapes: Query<(Entity, &ShapeComponent, &ShapeIndex, &Transform)>,
+    mut ew_drag_start: EventWriter<DragStartEvent>,
+    mut ew_drag_move: EventWriter<DragMoveEvent>,
+    mut ew_drag_end: EventWriter<DragEndingEvent>,
+) {
+    const DRAG_SOURCE: DragSource = DragSource::Keyboard;
+
+    // The shape may have been removed, fixed in place or taken by another pointer
+    if let Some(focused) = cursor.focused {
+        let still_focusable = shapes.get(focused).is_ok_and(|(_, component, ..)| {
+            if cursor.holding.is_some() {
+                component.has_drag_source(DRAG_SOURCE)
+            } else {
+                is_focusable(component)
+            }
+        });
+        if !still_focusable {
+            *cursor = KeyboardCursor::default();
+        }
+    }
+
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    for ev in keyboard_events.read() {
+        let (Some(code), bevy::input::ButtonState::Pressed) = (ev.key_code, ev.state) else {
+            continue;
+        };
+
+        match code {
+            KeyCode::Tab => {
+                if cursor.holding.is_none() {
+                    cursor.focused = next_focus(&shapes, cursor.focused, shift);
+                }
+            }
+            KeyCode::Space | KeyCode::Return => {
+                if cursor.holding.take().is_some() {
+                    ew_drag_end.send(DragEndingEvent {
+                        drag_source: DRAG_SOURCE,
+                    });
+                } else if let Some(Ok((_, _, shape_index, transform))) =
+                    cursor.focused.map(|entity| shapes.get(entity))
+                {
+                    let position = pick_up_point(shape_index, &transform.into());
+                    ew_drag_start.send(DragStartEvent {
+                        drag_source: DRAG_SOURCE,
+                        position,
+                    });
+                    cursor.holding = Some(position);
+                }
+            }
+            KeyCode::Escape => {
+                if cursor.holding.is_some() {
+                    ew_drag_end.send(DragEndingEvent {
+                        drag_source: DRAG_SOURCE,
+                    });
+                }
+                *cursor = KeyboardCursor::default();
+            }
+            _ => {}
+        }
+    }
+
+    let Some(position) = cursor.holding else {
+        return;
+    };
+
+    let mut direction = Vec2::ZERO;
+    for (key, delta) in [
+        (KeyCode::Left, Vec2::NEG_X),
+        (KeyCode::Right, Vec2::X),
+        (KeyCode::Up, Vec2::Y),
+        (KeyCode::Down, Vec2::NEG_Y),
+    ] {
+        if keys.pressed(key) {
+            direction += delta;
+        }
+    }
+    if direction == Vec2::ZERO {
+        return;
+    }
+
+    let speed = if shift { FAST_SPEED } else { SLOW_SPEED };
+    let max = Vec2::new(MAX_WINDOW_WIDTH, MAX_WINDOW_HEIGHT) * 0.5;
+    let new_position =
+        (position + direction.normalize() * speed * time.delta_seconds()).clamp(-max, max);
+
+    cursor.holding = Some(new_position);
+    ew_drag_move.send(DragMoveEvent {
+        drag_source: DRAG_SOURCE,
+        new_position,
+    });
+}
+
+fn is_focusable(component: &ShapeComponent) -> bool {
+    component.is_free() || component.is_locked()
+}
+
+/// The next shape from left to right, or right to left if `backwards`
+fn next_focus(
+    shapes: &Query<(Entity, &ShapeComponent, &ShapeIndex, &Transform)>,
+    current: Option<Entity>,
+    backwards: bool,
+) -> Option<Entity> {
+    let mut focusable: Vec<(Entity, Vec2)> = shapes
+        .iter()
+        .filter(|(_, component, ..)| is_focusable(component))
+        .map(|(entity, _, _, transform)| (entity, transform.translation.truncate()))
+        .collect();
+    focusable.sort_by(|a, b| a.1.x.total_cmp(&b.1.x).then(a.1.y.total_cmp(&b.1.y)));
+    if backwards {
+        focusable.reverse();
+    }
+
+    let next_index = current
+        .and_then(|current| focusable.iter().position(|x| x.0 == current))
+        .map(|index| index + 1)
+        .unwrap_or_default();
+
+    focusable.get(next_index).or(focusable.first()).map(|x| x.0)
+}
+
+/// A point inside the shape.
+/// The center of some shapes, like the U, is outside the shape so try points towards each corner.
+fn pick_up_point(shape_index: &ShapeIndex, location: &Location) -> Vec2 {
+    let body = &shape_index.game_shape().body;
+    let collider = body.to_collider_shape(SHAPE_SIZE);
+    let rotation = Vec2::from_angle(location.angle);
+
+    std::iter::once(location.position)
+        .chain(
+            body.get_vertices(SHAPE_SIZE)
+                .into_iter()
+                .map(|vertex| location.position + rotation.rotate(vertex) * 0.5),
+        )
+        .find(|point| collider.contains_point(location.position, location.angle, *point))
+        .unwrap_or(location.position)
+}
+
+fn draw_focus_ring(
+    mut commands: Commands,
+    cursor: Res<KeyboardCursor>,
+    shape_query: Query<(&ShapeIndex, &Transform), Without<FocusRing>>,
+    mut rings: Query<(Entity, &mut Transform), With<FocusRing>>,
+    mut ring_focus: Local<Option<Entity>>,
+) {
+    let focused = cursor
+        .focused
+        .and_then(|entity| shape_query.get(entity).ok());
+
+    let Some((shape_index, shape_transform)) = focused else {
+        for (entity, _) in rings.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        *ring_focus = None;
+        return;
+    };
+
+    let translation = shape_transform.translation.truncate().extend(FOCUS_RING_Z);
+
+    if *ring_focus == cursor.focused && !rings.is_empty() {
+        for (_, mut transform) in rings.iter_mut() {
+            transform.translation = translation;
+        }
+        return;
+    }
+
+    for (entity, _) in rings.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    *ring_focus = cursor.focused;
+
+    let radius = shape_index
+        .game_shape()
+        .body
+        .get_vertices(SHAPE_SIZE)
+        .into_iter()
+        .map(|vertex| vertex.length())
+        .fold(SHAPE_SIZE * 0.5, f32::max)
+        + FOCUS_RING_PADDING;
+
+    commands.spawn((
+        ShapeBundle {
+            path: GeometryBuilder::build_as(&shapes::Circle {
+                radius,
+                center: Vec2::ZERO,
+            }),
+            spatial: SpatialBundle::from_transform(Transform::from_translation(translation)),
+            ..Default::default()
+        },
+        Stroke {
+            color: FOCUS_RING_STROKE,
+            options: StrokeOptions::default().with_line_width(FOCUS_RING_WIDTH),
+        },
+        FocusRing,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use steks_common::game_shape::ALL_SHAPES;
+
+    #[test]
+    pub fn test_pick_up_point_is_inside_every_shape() {
+        let location = Location::new(30.0, -20.0, 1.0);
+
+        for index in 0..ALL_SHAPES.len() {
+            let shape_index = ShapeIndex(index as u8);
+            let point = pick_up_point(&shape_index, &location);
+            let body = &shape_index.game_shape().body;
+
+            assert!(
+                body.to_collider_shape(SHAPE_SIZE).contains_point(
+                    location.position,
+                    location.angle,
+                    point
+                ),
+                "{} should contain {point}",
+                shape_index.game_shape().name
+            );
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct KinematicPlugin;
+
+impl Plugin for KinematicPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            drive_moving_shapes.before(PhysicsSet::SyncBackend),
+        );
+    }
+}
+
+/// A fixed shape which follows a repeating path
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct MovingShape {
+    pub motion: ShapeMotion,
+    pub origin: Location,
+    /// Physics frames since the shape was created
+    pub frame: u32,
+}
+
+impl MovingShape {
+    pub fn new(motion: ShapeMotion, origin: Location) -> Self {
+        Self {
+            motion,
+            origin,
+            frame: 0,
+        }
+    }
+
+    /// Where the shape will be after this many more frames
+    pub fn location_after(&self, frames: u32) -> Location {
+        self.motion.location(self.origin, self.frame + frames)
+    }
+}
+
+/// Moves each shape one frame along its path before the physics step, so the moving shapes push others around
+pub(crate) fn drive_moving_shapes(mut query: Query<(&mut MovingShape, &mut Transform)>) {
+    for (mut moving, mut transform) in query.iter_mut() {
+        moving.frame += 1;
+        let Location { position, angle } = moving.location_after(0);
+
+        transform.translation = position.extend(transform.translation.z);
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_location_after() {
+        let motion = ShapeMotion {
+            dy: 50.0,
+            period: 1.0,
+            easing: MotionEasing::Linear,
+            ..Default::default()
+        };
+        let mut moving = MovingShape::new(motion, Location::new(0.0, 0.0, 0.0));
+        moving.frame = 3;
+
+        assert_eq!(moving.location_after(2), motion.location(moving.origin, 5));
+    }
+}
@@ -7,6 +7,7 @@ pub struct LevelTransitionResult {
     pub despawn_existing: bool,
     pub creations: Vec<ShapeCreationData>,
     pub updates: Vec<ShapeUpdateData>,
+    pub joints: Vec<ShapeJoint>,
 }
 
 impl LevelTransitionResult {
@@ -15,6 +16,7 @@ impl LevelTransitionResult {
             despawn_existing: false,
             creations: vec![],
             updates: vec![],
+            joints: vec![],
         }
     }
 
@@ -56,6 +58,10 @@ impl LevelTransitionResult {
                 &mut shape_updates,
             );
         }
+        let joints = current_level
+            .level
+            .generate_joints(previous_stage.filter(|_| !despawn), current_stage);
+
         let mut new_updates = vec![];
         for update in shape_updates {
             match shape_creations.iter_mut().find(|x| x.id == Some(update.id)) {
@@ -72,9 +78,46 @@ impl LevelTransitionResult {
             despawn_existing: despawn,
             creations: shape_creations,
             updates: new_updates,
+            joints,
         }
     }
 
+    /// Replace the creations with the shapes exactly as they were autosaved
+    pub fn restore(&mut self, save: &WorldSave) {
+        let mut new_creations: Vec<ShapeCreationData> = vec![];
+
+        for saved in save.shapes.iter() {
+            let found = match saved.id {
+                Some(id) => self.creations.iter().position(|sc| sc.id == Some(id)),
+                None => self
+                    .creations
+                    .iter()
+                    .position(|sc| sc.id.is_none() && sc.fuzzy_match(&saved.shape)),
+            };
+
+            let mut creation = match found {
+                Some(position) => self.creations.remove(position),
+                None => ShapeCreationData::from_encodable(saved.shape, ShapeStage(saved.stage)),
+            };
+
+            creation.state = saved.shape.state;
+            creation.location = Some(saved.shape.location);
+            creation.velocity = Some(saved.velocity());
+            if let (Some(moving), Some(frame)) = (creation.motion.as_mut(), saved.frame) {
+                moving.frame = frame;
+            }
+            creation.stage = ShapeStage(saved.stage);
+            creation.from_saved_game = true;
+            new_creations.push(creation);
+        }
+
+        if !self.creations.is_empty() {
+            debug!("{} shapes were not in the autosave", self.creations.len());
+        }
+
+        self.creations = new_creations;
+    }
+
     pub fn mogrify(&mut self, sv: &ShapesVec) {
         let mut new_creations: Vec<ShapeCreationData> = vec![];
 
@@ -89,8 +132,10 @@ impl LevelTransitionResult {
                 let mut creation = self.creations.remove(position);
 
                 creation.state = encodable.state;
-                creation.location = Some(encodable.location);
-                creation.velocity = Some(Velocity::zero());
+                if creation.motion.is_none() {
+                    creation.location = Some(encodable.location);
+                    creation.velocity = Some(Velocity::zero());
+                }
                 creation.from_saved_game = true;
                 new_creations.push(creation);
             } else {
@@ -1,6 +1,7 @@
 #![allow(clippy::too_many_arguments)]
 
 pub mod arrow;
+pub mod autosave;
 pub mod camera;
 pub mod change_level_event;
 pub mod collision;
@@ -10,17 +11,23 @@ pub mod demo_resource;
 pub mod designed_level_meta;
 pub mod draggable;
 pub mod fireworks;
+pub mod force_zone;
 pub mod game_level;
+pub mod gamepad;
 pub mod has_acted;
 pub mod infinity;
 pub mod input;
 pub mod insets;
+pub mod joints;
+pub mod keyboard_cursor;
+pub mod kinematic;
 pub mod level;
 pub mod level_transition;
+pub mod objectives;
 pub mod padlock;
 pub mod prediction;
 pub mod records;
-pub mod rectangle_set;
+pub mod replay;
 pub mod settings;
 pub mod shape_component;
 pub mod shape_creation_data;
@@ -33,7 +40,9 @@ pub mod streak;
 pub mod text_button;
 pub mod ui;
 pub mod ui_trait;
+pub mod undo;
 pub mod walls;
+pub mod water;
 pub mod win;
 pub mod win_timer_state;
 pub mod window_size_helpers;
@@ -51,6 +60,7 @@ pub mod prelude {
     pub use steks_common::prelude::*;
 
     pub use crate::arrow::*;
+    pub use crate::autosave::*;
     pub use crate::camera::*;
     pub use crate::change_level_event::*;
     pub use crate::collision::*;
@@ -60,17 +70,24 @@ pub mod prelude {
     pub use crate::designed_level_meta::*;
     pub use crate::draggable::*;
     pub use crate::fireworks::*;
+    pub use crate::force_zone::*;
     pub use crate::game_level::*;
+    pub use crate::gamepad::*;
     pub use crate::has_acted::*;
     pub use crate::infinity::*;
     pub use crate::input::*;
     pub use crate::insets::*;
+    pub use crate::joints::*;
+    pub use crate::keyboard_cursor::*;
+    pub use crate::kinematic::*;
     pub use crate::level::*;
     pub use crate::level_transition::*;
+    pub use crate::objectives::*;
     pub use crate::padlock::*;
     pub use crate::prediction::*;
     pub use crate::prediction::*;
     pub use crate::records::*;
+    pub use crate::replay::*;
     pub use crate::settings::*;
     pub use crate::shape_component::*;
     pub use crate::shape_creation_data::*;
@@ -83,7 +100,9 @@ pub mod prelude {
     pub use crate::text_button::*;
     pub use crate::ui::*;
     pub use crate::ui_trait::*;
+    pub use crate::undo::*;
     pub use crate::walls::*;
+    pub use crate::water::*;
     pub use crate::win::*;
     pub use crate::win_timer_state::*;
 
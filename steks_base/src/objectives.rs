@@ -0,0 +1,177 @@
+use bevy_prototype_lyon::prelude::*;
+use maveric::prelude::*;
+
+use crate::prelude::*;
+
+const TARGET_Z: f32 = 0.5;
+
+#[derive(Debug, Default)]
+pub struct ObjectivesPlugin;
+
+impl Plugin for ObjectivesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ObjectiveTracker>()
+            .init_resource::<ObjectiveStatus>()
+            .add_systems(Update, manage_objectives)
+            .add_systems(Update, count_pickups.after(manage_objectives))
+            .add_systems(FixedUpdate, tick_objectives)
+            .add_systems(Update, update_objective_status.after(count_pickups));
+    }
+}
+
+/// Progress towards the objectives of the current level
+#[derive(Debug, Clone, Default, PartialEq, Resource)]
+pub struct ObjectiveTracker {
+    pub objectives: LevelObjectives,
+    /// Shapes picked up since the level started
+    pub pickups: u32,
+    /// Physics frames since the first pickup
+    pub frames: u32,
+    pub failure: Option<ObjectiveFailure>,
+}
+
+impl ObjectiveTracker {
+    pub fn new(objectives: LevelObjectives) -> Self {
+        Self {
+            objectives,
+            ..Default::default()
+        }
+    }
+
+    pub fn seconds(&self) -> f32 {
+        (self.frames as f64 * SECONDS_PER_FRAME) as f32
+    }
+
+    /// Whether the level can be won with these shapes
+    pub fn allows_win(&self, shapes: &ShapesVec, is_last_stage: bool) -> bool {
+        self.failure.is_none() && (!is_last_stage || self.objectives.is_target_reached(shapes))
+    }
+
+    fn check_for_failure(&mut self) {
+        if self.failure.is_none() {
+            self.failure = self.objectives.check(self.seconds(), self.pickups);
+        }
+    }
+}
+
+/// What the HUD shows about the objectives.
+/// This only changes once a second so the UI is not rebuilt every frame.
+#[derive(Debug, Clone, Default, PartialEq, Resource, MavericContext)]
+pub struct ObjectiveStatus {
+    pub seconds_remaining: Option<u32>,
+    pub moves_remaining: Option<u32>,
+    pub has_target: bool,
+    pub failure: Option<ObjectiveFailure>,
+}
+
+/// The outline which the tower must reach
+#[derive(Debug, Component)]
+pub struct TargetOutlineMarker;
+
+fn manage_objectives(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    mut previous_level: Local<PreviousLevel>,
+    mut tracker: ResMut<ObjectiveTracker>,
+    existing: Query<Entity, With<TargetOutlineMarker>>,
+) {
+    if !current_level.is_changed() {
+        return;
+    }
+
+    let is_new_attempt = match previous_level.compare(&current_level) {
+        PreviousLevelType::DifferentLevel => true,
+        // The level has been reset
+        PreviousLevelType::SameLevelSameStage => current_level.completion.is_incomplete(),
+        PreviousLevelType::SameLevelEarlierStage(_) => false,
+    };
+    *previous_level = current_level.as_ref().into();
+
+    if !is_new_attempt {
+        return;
+    }
+
+    let objectives = current_level.level.get_objectives();
+    *tracker = ObjectiveTracker::new(objectives);
+
+    for entity in existing.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if let Some(target) = objectives.target {
+        spawn_target(&mut commands, &target);
+    }
+}
+
+fn spawn_target(commands: &mut Commands, target: &TargetOutline) {
+    let path = GeometryBuilder::build_as(&shapes::Circle {
+        radius: target.radius,
+        center: Vec2::ZERO,
+    });
+
+    commands.spawn((
+        ShapeBundle {
+            path,
+            spatial: SpatialBundle::from_transform(Transform::from_translation(
+                target.position().extend(TARGET_Z),
+            )),
+            ..Default::default()
+        },
+        Stroke {
+            color: TARGET_OUTLINE_STROKE,
+            options: StrokeOptions::default().with_line_width(TARGET_OUTLINE_WIDTH),
+        },
+        TargetOutlineMarker,
+    ));
+}
+
+fn count_pickups(
+    current_level: Res<CurrentLevel>,
+    mut tracker: ResMut<ObjectiveTracker>,
+    mut pickup_events: EventReader<ShapePickedUpEvent>,
+) {
+    let pickups = pickup_events.read().count() as u32;
+    if pickups == 0 || !current_level.completion.is_incomplete() {
+        return;
+    }
+
+    tracker.pickups += pickups;
+    tracker.check_for_failure();
+}
+
+/// The clock starts when the first shape is picked up and stops when the level is complete
+fn tick_objectives(current_level: Res<CurrentLevel>, mut tracker: ResMut<ObjectiveTracker>) {
+    if tracker.pickups == 0 || !current_level.completion.is_incomplete() {
+        return;
+    }
+
+    tracker.frames += 1;
+    tracker.check_for_failure();
+}
+
+fn update_objective_status(
+    tracker: Res<ObjectiveTracker>,
+    current_level: Res<CurrentLevel>,
+    mut status: ResMut<ObjectiveStatus>,
+) {
+    if !tracker.is_changed() && !current_level.is_changed() {
+        return;
+    }
+
+    let objectives = &tracker.objectives;
+    let new_status = if current_level.completion.is_incomplete() {
+        ObjectiveStatus {
+            seconds_remaining: objectives
+                .time_limit
+                .map(|limit| (limit - tracker.seconds()).max(0.0).ceil() as u32),
+            moves_remaining: objectives
+                .move_limit
+                .map(|limit| limit.saturating_sub(tracker.pickups)),
+            has_target: objectives.target.is_some(),
+            failure: tracker.failure,
+        }
+    } else {
+        ObjectiveStatus::default()
+    };
+
+    status.set_if_neq(new_status);
+}
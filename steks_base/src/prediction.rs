@@ -1,11 +1,11 @@
-use std::sync::atomic::{AtomicBool, AtomicI8};
-
 use bevy::prelude::*;
 use bevy_rapier2d::{prelude::*, rapier::prelude::IntegrationParameters, na::Vector2};
 use strum::EnumIs;
 
 use crate::prelude::*;
-use bevy_rapier2d::rapier::prelude::{EventHandler, PhysicsPipeline, Real};
+use bevy_rapier2d::rapier::prelude::{
+    Isometry, PhysicsPipeline, Real, RigidBodyHandle,
+};
 
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -19,15 +19,15 @@ impl From<&HasActed> for PredictionSettings {
     fn from(val: &HasActed) -> Self {
         match val {
             HasActed::HasActed => PredictionSettings {
-                max_substeps: FRAMES_PER_SECOND * 6,
-                early_sensor_substeps: SHORT_WIN_FRAMES,
-                max_non_sensor_collisions: 3,
+                max_substeps: PREDICTION_MAX_SUBSTEPS,
+                early_sensor_substeps: PREDICTION_EARLY_SENSOR_SUBSTEPS,
+                max_non_sensor_collisions: PREDICTION_MAX_NON_SENSOR_COLLISIONS,
             },
 
             HasActed::HasNotActed => PredictionSettings {
-                max_substeps: FRAMES_PER_SECOND * 6,
-                early_sensor_substeps: SHORT_WIN_FRAMES,
-                max_non_sensor_collisions: 3,
+                max_substeps: PREDICTION_MAX_SUBSTEPS,
+                early_sensor_substeps: PREDICTION_EARLY_SENSOR_SUBSTEPS,
+                max_non_sensor_collisions: PREDICTION_MAX_NON_SENSOR_COLLISIONS,
             },
         }
     }
@@ -69,6 +69,14 @@ pub struct PredictionContext {
     ccd_solver: bevy_rapier2d::rapier::prelude::CCDSolver,
     substep: u32,
     physics_pipeline: PhysicsPipeline,
+    /// Moving shapes as they were when the prediction started
+    moving_shapes: Vec<(RigidBodyHandle, MovingShape)>,
+    /// Force zones as they were when the prediction started
+    force_zones: ActiveForceZones,
+    water: ActiveWater,
+    /// The shapes pushed by the force zones and the water
+    free_shapes: Vec<(RigidBodyHandle, &'static GameShape)>,
+    physics_scale: f32,
 }
 
 impl PredictionContext {
@@ -76,6 +84,10 @@ impl PredictionContext {
         context: &RapierContext,
         config: RapierConfiguration,
         prediction_settings: PredictionSettings,
+        moving_shapes: impl Iterator<Item = (Entity, MovingShape)>,
+        force_zones: &ActiveForceZones,
+        water: &ActiveWater,
+        free_shapes: impl Iterator<Item = (Entity, ShapeIndex)>,
     ) -> Self {
         let physics_pipeline: PhysicsPipeline = PhysicsPipeline::default();
 
@@ -128,6 +140,23 @@ impl PredictionContext {
             } => dt / (substeps as f32) * time_scale,
         };
 
+        let moving_shapes = moving_shapes
+            .flat_map(|(entity, moving)| {
+                context
+                    .entity2body()
+                    .get(&entity)
+                    .map(|handle| (*handle, moving))
+            })
+            .collect();
+        let free_shapes = free_shapes
+            .flat_map(|(entity, shape_index)| {
+                context
+                    .entity2body()
+                    .get(&entity)
+                    .map(|handle| (*handle, shape_index.game_shape()))
+            })
+            .collect();
+
         let mut integration_parameters = context.integration_parameters;
         integration_parameters.dt = dt;
 
@@ -147,10 +176,69 @@ impl PredictionContext {
             multibody_joints,
             ccd_solver,
             substep: 0,
+            moving_shapes,
+            force_zones: force_zones.clone(),
+            water: *water,
+            free_shapes,
+            physics_scale: context.physics_scale(),
+        }
+    }
+
+    /// Moves the kinematic shapes to where the game will put them in the next substep
+    fn drive_moving_shapes(&mut self) {
+        for (handle, moving) in self.moving_shapes.iter() {
+            let Some(body) = self.bodies.get_mut(*handle) else {
+                continue;
+            };
+            let Location { position, angle } = next_moving_location(moving, self.substep);
+            let position = position / self.physics_scale;
+
+            body.set_next_kinematic_position(Isometry::new(
+                Vector2::new(position.x as Real, position.y as Real),
+                angle as Real,
+            ));
+        }
+    }
+
+    /// Sets the force on each free shape, exactly as the game will
+    fn push_free_shapes(&mut self) {
+        if self.force_zones.zones.is_empty() && self.water.0.is_none() {
+            return;
+        }
+
+        let scale = self.physics_scale;
+        let gravity = Vec2::new(self.gravity.x, self.gravity.y) * scale;
+
+        for (handle, shape) in self.free_shapes.iter() {
+            let Some(body) = self.bodies.get_mut(*handle) else {
+                continue;
+            };
+            let translation = body.translation();
+            let location = Location {
+                position: Vec2::new(translation.x, translation.y) * scale,
+                angle: body.rotation().angle(),
+            };
+            let linvel = body.linvel();
+            let velocity = Velocity {
+                linvel: Vec2::new(linvel.x, linvel.y) * scale,
+                angvel: body.angvel(),
+            };
+
+            let water_force = self.water.force_on(shape, &location, &velocity, gravity);
+            // The game moves the zones on one frame before each physics step
+            let force = (self.force_zones.force_after(location.position, self.substep + 1)
+                + water_force.force)
+                / scale;
+
+            body.reset_forces(false);
+            body.add_force(Vector2::new(force.x as Real, force.y as Real), false);
+            body.add_torque(water_force.torque / (scale * scale), true);
         }
     }
 
-    fn step(&mut self, event_handler: &PredictionCollisionHandler) {
+    fn step(&mut self, event_handler: &CollisionCounter) {
+        self.drive_moving_shapes();
+        self.push_free_shapes();
         self.physics_pipeline.step(
             &self.gravity,
             &self.integration_parameters,
@@ -169,15 +257,15 @@ impl PredictionContext {
     }
 
     pub fn advance(&mut self, max_steps_to_do: u32) -> Option<PredictionResult> {
-        let event_handler = PredictionCollisionHandler::default();
+        let event_handler = CollisionCounter::default();
         let to = self
             .prediction_settings
             .max_substeps
             .min(self.substep + max_steps_to_do);
         while self.substep < to {
+            self.step(&event_handler);
             self.substep += 1;
             let i = self.substep;
-            self.step(&event_handler);
 
             let sensor_found = event_handler
                 .sensor_collision_found
@@ -228,39 +316,43 @@ impl PredictionContext {
     }
 }
 
-#[derive(Default, Debug)]
-struct PredictionCollisionHandler {
-    pub sensor_collision_found: AtomicBool,
-    pub total_collisions_found: AtomicI8,
+/// Where a moving shape will be after the next substep.
+/// The game moves the shape on one frame before each physics step.
+fn next_moving_location(moving: &MovingShape, substeps_done: u32) -> Location {
+    moving.location_after(substeps_done + 1)
 }
 
-impl EventHandler for PredictionCollisionHandler {
-    fn handle_collision_event(
-        &self,
-        _bodies: &bevy_rapier2d::rapier::prelude::RigidBodySet,
-        _colliders: &bevy_rapier2d::rapier::prelude::ColliderSet,
-        event: bevy_rapier2d::rapier::prelude::CollisionEvent,
-        _contact_pair: Option<&bevy_rapier2d::rapier::prelude::ContactPair>,
-    ) {
-        // let c1 = _colliders.get(event.collider1()).map(|x|x.user_data);
-        // let c2 = _colliders.get(event.collider2()).map(|x|x.user_data);
-        // info!("Event detected sensor:{sensor} {c1:?} {c2:?}", sensor = event.sensor(), );
-        if event.sensor() {
-            self.sensor_collision_found
-                .store(true, std::sync::atomic::Ordering::Relaxed);
-        }
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
 
-        self.total_collisions_found
-            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-    }
+    use super::*;
+
+    #[test]
+    pub fn test_moving_shapes_match_game() {
+        let motion = ShapeMotion {
+            dx: 100.0,
+            dr: 0.25,
+            period: 1.5,
+            ..Default::default()
+        };
+        let mut moving = MovingShape::new(motion, Location::new(10.0, 20.0, 0.0));
+        moving.frame = 7;
 
-    fn handle_contact_force_event(
-        &self,
-        _dt: bevy_rapier2d::rapier::prelude::Real,
-        _bodies: &bevy_rapier2d::rapier::prelude::RigidBodySet,
-        _colliders: &bevy_rapier2d::rapier::prelude::ColliderSet,
-        _contact_pair: &bevy_rapier2d::rapier::prelude::ContactPair,
-        _total_force_magnitude: bevy_rapier2d::rapier::prelude::Real,
-    ) {
+        let mut world = World::new();
+        let entity = world.spawn((moving, Transform::default())).id();
+
+        for substeps_done in 0..(FRAMES_PER_SECOND * 3) {
+            let predicted = next_moving_location(&moving, substeps_done);
+
+            world.run_system_once(crate::kinematic::drive_moving_shapes);
+            let actual: Location = world.get::<Transform>(entity).unwrap().into();
+
+            assert!(
+                predicted.position.distance(actual.position) < 0.001,
+                "Positions differ after {substeps_done} substeps"
+            );
+            assert!((predicted.angle - actual.angle).abs() < 0.001);
+        }
     }
 }
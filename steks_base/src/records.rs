@@ -1,6 +1,6 @@
 use crate::prelude::*;
 use bevy::prelude::*;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use maveric::helpers::MavericContext;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -13,12 +13,41 @@ impl Plugin for RecordsPlugin {
         app.init_tracked_resource::<WorldRecords>();
         app.init_tracked_resource::<PersonalBests>();
         app.init_tracked_resource::<MaxInfiniteStage>();
+        app.init_tracked_resource::<InfiniteBests>();
     }
 }
 
 pub type PbMap = BTreeMap<u64, LevelPB>;
 pub type WrMAP = BTreeMap<u64, LevelWR>;
 
+/// What a record is for.
+/// Challenges are keyed by date as different days can have the same shapes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RecordKey {
+    Shapes { hash: u64 },
+    Challenge { date: NaiveDate },
+}
+
+impl RecordKey {
+    pub fn new(level: &GameLevel, hash: u64) -> Self {
+        match level {
+            GameLevel::Challenge { date, .. } => RecordKey::Challenge { date: *date },
+            _ => RecordKey::Shapes { hash },
+        }
+    }
+
+    pub fn challenge_date(&self) -> Option<NaiveDate> {
+        match self {
+            RecordKey::Shapes { .. } => None,
+            RecordKey::Challenge { date } => Some(*date),
+        }
+    }
+    /// Challenge records used to be kept by the hash of the day's shapes
+    fn legacy_challenge_hash(date: &NaiveDate) -> u64 {
+        ShapesVec::challenge_hash(date.year().unsigned_abs(), date.month(), date.day())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct LevelPB {
     #[deprecated = "not set correctly historically"]
@@ -42,13 +71,44 @@ impl LevelWR {
     }
 
     pub fn calculate_height(&self) -> f32 {
-        ShapesVec::from_bytes(&self.image_blob).calculate_tower_height()
+        self.calculate_height_above(None)
+    }
+
+    pub fn calculate_height_above(&self, water_line: Option<f32>) -> f32 {
+        ShapesVec::from_bytes(&self.image_blob)
+            .map(|shapes| shapes.calculate_tower_height_above(water_line))
+            .unwrap_or_default()
     }
 }
 
-#[derive(Debug, Resource, Default, Serialize, Deserialize, Clone)]
+#[derive(Debug, Resource, Default, Serialize, Deserialize, Clone, MavericContext)]
 pub struct WorldRecords {
     pub map: WrMAP,
+    #[serde(default)]
+    pub challenges: BTreeMap<NaiveDate, LevelWR>,
+}
+
+impl WorldRecords {
+    pub fn get(&self, key: &RecordKey) -> Option<&LevelWR> {
+        match key {
+            RecordKey::Shapes { hash } => self.map.get(hash),
+            RecordKey::Challenge { date } => self.challenges.get(date),
+        }
+    }
+
+    pub fn get_mut(&mut self, key: &RecordKey) -> Option<&mut LevelWR> {
+        match key {
+            RecordKey::Shapes { hash } => self.map.get_mut(hash),
+            RecordKey::Challenge { date } => self.challenges.get_mut(date),
+        }
+    }
+
+    pub fn insert(&mut self, key: RecordKey, wr: LevelWR) {
+        match key {
+            RecordKey::Shapes { hash } => self.map.insert(hash, wr),
+            RecordKey::Challenge { date } => self.challenges.insert(date, wr),
+        };
+    }
 }
 
 impl TrackableResource for WorldRecords {
@@ -58,6 +118,8 @@ impl TrackableResource for WorldRecords {
 #[derive(Debug, Resource, Default, Serialize, Deserialize, Clone, MavericContext)]
 pub struct PersonalBests {
     pub map: PbMap,
+    #[serde(default)]
+    pub challenges: BTreeMap<NaiveDate, LevelPB>,
 }
 
 impl TrackableResource for PersonalBests {
@@ -72,6 +134,23 @@ impl PersonalBests {
 
         self.map.get(&hash)
     }
+
+    pub fn get(&self, key: &RecordKey) -> Option<&LevelPB> {
+        match key {
+            RecordKey::Shapes { hash } => self.map.get(hash),
+            RecordKey::Challenge { date } => self
+                .challenges
+                .get(date)
+                .or_else(|| self.map.get(&RecordKey::legacy_challenge_hash(date))),
+        }
+    }
+
+    pub fn insert(&mut self, key: RecordKey, pb: LevelPB) {
+        match key {
+            RecordKey::Shapes { hash } => self.map.insert(hash, pb),
+            RecordKey::Challenge { date } => self.challenges.insert(date, pb),
+        };
+    }
 }
 
 #[derive(Debug, Resource, Default, Serialize, Deserialize, Clone)]
@@ -80,3 +159,86 @@ pub struct MaxInfiniteStage(pub usize);
 impl TrackableResource for MaxInfiniteStage {
     const KEY: &'static str = "MaxInfinite";
 }
+
+/// How many infinite mode seeds to remember the best stage for
+const MAX_INFINITE_BESTS: usize = 32;
+
+/// The best stage reached for each infinite mode seed.
+/// Only the best few seeds are kept as every new run has a new seed.
+#[derive(Debug, Resource, Default, Serialize, Deserialize, Clone)]
+pub struct InfiniteBests {
+    pub map: BTreeMap<u64, usize>,
+}
+
+impl TrackableResource for InfiniteBests {
+    const KEY: &'static str = "InfiniteBests";
+}
+
+impl InfiniteBests {
+    pub fn get(&self, seed: u64) -> usize {
+        self.map.get(&seed).copied().unwrap_or_default()
+    }
+
+    /// Record the stage reached with a seed, forgetting the worst seed if there are too many
+    pub fn record(&mut self, seed: u64, stage: usize) {
+        if stage <= self.get(seed) {
+            return;
+        }
+        self.map.insert(seed, stage);
+
+        if self.map.len() > MAX_INFINITE_BESTS {
+            if let Some(worst) = self
+                .map
+                .iter()
+                .min_by_key(|(_, stage)| **stage)
+                .map(|x| *x.0)
+            {
+                self.map.remove(&worst);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_challenge_pb_falls_back_to_legacy_hash() {
+        let date = NaiveDate::from_ymd_opt(2023, 12, 25).unwrap();
+        let key = RecordKey::Challenge { date };
+        let legacy = LevelPB {
+            height: 100.0,
+            ..Default::default()
+        };
+        let mut pbs = PersonalBests::default();
+        pbs.map.insert(RecordKey::legacy_challenge_hash(&date), legacy.clone());
+
+        assert_eq!(pbs.get(&key), Some(&legacy));
+
+        let new = LevelPB {
+            height: 50.0,
+            ..Default::default()
+        };
+        pbs.insert(key, new.clone());
+        assert_eq!(pbs.get(&key), Some(&new));
+    }
+
+    #[test]
+    pub fn test_infinite_bests_are_capped() {
+        let mut bests = InfiniteBests::default();
+        for seed in 0..(MAX_INFINITE_BESTS as u64 * 2) {
+            bests.record(seed, seed as usize + 1);
+        }
+
+        assert_eq!(bests.map.len(), MAX_INFINITE_BESTS);
+        assert_eq!(bests.get(0), 0);
+        assert_eq!(
+            bests.get(MAX_INFINITE_BESTS as u64 * 2 - 1),
+            MAX_INFINITE_BESTS * 2
+        );
+
+        bests.record(0, 1);
+        assert_eq!(bests.get(0), 0);
+    }
+}
@@ -0,0 +1,554 @@
+use std::sync::Arc;
+
+use maveric::helpers::MavericContext;
+use serde::{Deserialize, Serialize};
+
+use crate::input;
+use crate::prelude::*;
+
+/// First byte of every binary replay
+pub const REPLAY_MAGIC: u8 = 0xF7;
+/// Increment this whenever the replay layout changes
+pub const REPLAY_VERSION: u8 = 2;
+
+#[derive(Debug, Default)]
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayRecorder>()
+            .init_resource::<ReplayPlayback>()
+            .add_systems(FixedUpdate, count_replay_frames)
+            .add_systems(FixedUpdate, play_replay_inputs.after(count_replay_frames))
+            .add_systems(
+                Update,
+                record_replay_inputs
+                    .after(input::mousebutton_listener)
+                    .after(input::touch_listener)
+                    .after(input::keyboard_listener)
+                    .after(input::mousewheel_listener),
+            )
+            .add_systems(
+                Update,
+                manage_recording
+                    .before(record_replay_inputs)
+                    .before(spawn_and_update_shapes),
+            );
+    }
+}
+
+/// Everything needed to watch a tower being built
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub level: GameLevel,
+    pub inputs: Vec<TimedInput>,
+    /// The shapes when the level was completed
+    pub shapes: ShapesVec,
+    /// Where the randomly placed shapes were created, in the order they were created
+    #[serde(default)]
+    pub placements: Vec<ShapePlacement>,
+}
+
+/// Where a shape was created and how it was moving, so that playback starts from the same world
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ShapePlacement {
+    pub location: Location,
+    pub linvel: Vec2,
+    pub angvel: f32,
+}
+
+impl Replay {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = vec![REPLAY_MAGIC, REPLAY_VERSION];
+        // MessagePack rather than something smaller because levels use `#[serde(flatten)]`
+        rmp_serde::encode::write(&mut bytes, self)?;
+
+        let checksum = share_code_checksum(&bytes[1..]);
+        bytes.extend_from_slice(&checksum.to_be_bytes());
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> anyhow::Result<Self> {
+        if data.first() != Some(&REPLAY_MAGIC) {
+            anyhow::bail!("Data is not a replay");
+        }
+        if data.len() < SHARE_CODE_HEADER_LENGTH + SHARE_CODE_CHECKSUM_LENGTH {
+            anyhow::bail!("Replay is truncated ({} bytes)", data.len());
+        }
+
+        let version = data[1];
+        if version > REPLAY_VERSION {
+            anyhow::bail!("Replay version {version} is not supported - please update the game");
+        }
+
+        let (body, checksum) = data.split_at(data.len() - SHARE_CODE_CHECKSUM_LENGTH);
+        let expected = u16::from_be_bytes([checksum[0], checksum[1]]);
+        let actual = share_code_checksum(&body[1..]);
+        if expected != actual {
+            anyhow::bail!("Replay is corrupt");
+        }
+
+        let replay = rmp_serde::from_slice(&body[SHARE_CODE_HEADER_LENGTH..])?;
+        Ok(replay)
+    }
+
+    pub fn to_yaml(&self) -> anyhow::Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    pub fn from_yaml(data: &str) -> anyhow::Result<Self> {
+        Ok(serde_yaml::from_str(data)?)
+    }
+
+    /// The frame of the last input
+    pub fn last_frame(&self) -> u32 {
+        self.inputs.last().map(|x| x.frame).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplayMode {
+    /// Show the finished tower as outlines while the player builds their own
+    Ghost,
+    /// Play the recorded inputs back
+    Playback,
+}
+
+/// The replay currently being watched, if any
+#[derive(Debug, Clone, Default, PartialEq, Resource, MavericContext)]
+pub struct ReplayPlayback {
+    pub replay: Option<(Arc<Replay>, ReplayMode)>,
+    frame: u32,
+    next_input: usize,
+    next_placement: usize,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Arc<Replay>, mode: ReplayMode) -> Self {
+        Self {
+            replay: Some((replay, mode)),
+            frame: 0,
+            next_input: 0,
+            next_placement: 0,
+        }
+    }
+
+    /// Where the next randomly placed shape should be created
+    pub fn next_placement(&mut self) -> Option<ShapePlacement> {
+        if !self.is_playing() {
+            return None;
+        }
+        let (replay, _) = self.replay.as_ref()?;
+        let placement = replay.placements.get(self.next_placement).copied()?;
+        self.next_placement += 1;
+        Some(placement)
+    }
+
+    /// The shapes to draw as outlines
+    pub fn ghost_shapes(&self) -> Option<&ShapesVec> {
+        match &self.replay {
+            Some((replay, ReplayMode::Ghost)) => Some(&replay.shapes),
+            _ => None,
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        matches!(self.replay, Some((_, ReplayMode::Playback)))
+    }
+}
+
+/// Records the inputs for the current level
+#[derive(Debug, Clone, Default, Resource)]
+pub struct ReplayRecorder {
+    frame: u32,
+    inputs: Vec<TimedInput>,
+    drag_source: Option<DragSource>,
+    placements: Vec<ShapePlacement>,
+    /// The replay of the most recently completed level
+    pub latest: Option<Arc<Replay>>,
+}
+
+impl ReplayRecorder {
+    pub fn record_placement(&mut self, placement: ShapePlacement) {
+        self.placements.push(placement);
+    }
+
+    /// A replay of everything recorded since the level started
+    pub fn take_replay(&mut self, level: GameLevel, shapes: ShapesVec) -> Replay {
+        Replay {
+            level,
+            inputs: std::mem::take(&mut self.inputs),
+            shapes,
+            placements: std::mem::take(&mut self.placements),
+        }
+    }
+}
+
+fn count_replay_frames(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut playback: ResMut<ReplayPlayback>,
+) {
+    recorder.frame += 1;
+    if playback.replay.is_some() {
+        playback.frame += 1;
+    }
+}
+
+fn manage_recording(
+    current_level: Res<CurrentLevel>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut playback: ResMut<ReplayPlayback>,
+    shapes_query: Query<(&ShapeIndex, &Transform, &ShapeComponent, &ShapeModifiers)>,
+) {
+    if !current_level.is_changed() {
+        return;
+    }
+
+    match current_level.completion {
+        LevelCompletion::Incomplete { stage: 0 } => {
+            recorder.frame = 0;
+            recorder.inputs.clear();
+            recorder.drag_source = None;
+            recorder.placements.clear();
+            // Count playback frames from the same point as recorded frames
+            playback.frame = 0;
+        }
+        LevelCompletion::Incomplete { .. } => {}
+        LevelCompletion::Complete { .. } => {
+            if recorder.inputs.is_empty() {
+                return;
+            }
+
+            let replay = recorder.take_replay(
+                current_level.level.clone(),
+                shapes_vec_from_query(shapes_query),
+            );
+            info!("Recorded replay with {} inputs", replay.inputs.len());
+            recorder.latest = Some(Arc::new(replay));
+        }
+    }
+}
+
+fn record_replay_inputs(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut drag_start_events: EventReader<DragStartEvent>,
+    mut drag_move_events: EventReader<DragMoveEvent>,
+    mut drag_end_events: EventReader<DragEndingEvent>,
+    mut rotate_events: EventReader<RotateEvent>,
+    current_level: Res<CurrentLevel>,
+    playback: Res<ReplayPlayback>,
+) {
+    if current_level.completion.is_complete() || playback.is_playing() {
+        // The inputs sent by playback are not the player's
+        drag_start_events.clear();
+        drag_move_events.clear();
+        drag_end_events.clear();
+        rotate_events.clear();
+        return;
+    }
+    let frame = recorder.frame;
+
+    for event in drag_start_events.read() {
+        if recorder.drag_source.is_none() {
+            recorder.drag_source = Some(event.drag_source);
+            recorder.inputs.push(TimedInput::new(
+                frame,
+                ReplayInput::PickUp {
+                    position: event.position,
+                },
+            ));
+        }
+    }
+
+    for event in drag_move_events.read() {
+        if recorder.drag_source == Some(event.drag_source) {
+            recorder.inputs.push(TimedInput::new(
+                frame,
+                ReplayInput::DragTo {
+                    position: event.new_position,
+                },
+            ));
+        }
+    }
+
+    for event in rotate_events.read() {
+        recorder.inputs.push(TimedInput::new(
+            frame,
+            ReplayInput::Rotate {
+                delta: event.delta,
+                snap_resolution: event.snap_resolution,
+            },
+        ));
+    }
+
+    for event in drag_end_events.read() {
+        if recorder.drag_source == Some(event.drag_source) {
+            recorder.drag_source = None;
+            recorder.inputs.push(TimedInput::new(frame, ReplayInput::Drop));
+        }
+    }
+}
+
+fn play_replay_inputs(
+    mut playback: ResMut<ReplayPlayback>,
+    mut drag_start_events: EventWriter<DragStartEvent>,
+    mut drag_move_events: EventWriter<DragMoveEvent>,
+    mut drag_end_events: EventWriter<DragEndingEvent>,
+    mut rotate_events: EventWriter<RotateEvent>,
+) {
+    if !playback.is_playing() {
+        return;
+    }
+    let Some((replay, _)) = playback.replay.clone() else {
+        return;
+    };
+
+    const DRAG_SOURCE: DragSource = DragSource::Mouse;
+
+    while let Some(timed) = replay.inputs.get(playback.next_input) {
+        if timed.frame > playback.frame {
+            break;
+        }
+        playback.next_input += 1;
+
+        match timed.input {
+            ReplayInput::PickUp { position } => drag_start_events.send(DragStartEvent {
+                drag_source: DRAG_SOURCE,
+                position,
+            }),
+            ReplayInput::DragTo { position } => drag_move_events.send(DragMoveEvent {
+                drag_source: DRAG_SOURCE,
+                new_position: position,
+            }),
+            ReplayInput::Rotate {
+                delta,
+                snap_resolution,
+            } => rotate_events.send(RotateEvent {
+                delta,
+                snap_resolution,
+            }),
+            ReplayInput::Drop => drag_end_events.send(DragEndingEvent {
+                drag_source: DRAG_SOURCE,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_replay() -> Replay {
+        Replay {
+            level: GameLevel::Designed {
+                meta: DesignedLevelMeta::Campaign { index: 3 },
+            },
+            inputs: vec![
+                TimedInput::new(
+                    10,
+                    ReplayInput::PickUp {
+                        position: Vec2::new(1.0, 2.0),
+                    },
+                ),
+                TimedInput::new(
+                    12,
+                    ReplayInput::Rotate {
+                        delta: 0.5,
+                        snap_resolution: None,
+                    },
+                ),
+                TimedInput::new(20, ReplayInput::Drop),
+            ],
+            shapes: ShapesVec(vec![EncodableShape {
+                shape: ShapeIndex(0),
+                location: Location::new(10.0, 20.0, 1.0),
+                state: ShapeState::Normal,
+                modifiers: ShapeModifiers::Normal,
+            }]),
+            placements: vec![ShapePlacement {
+                location: Location::new(-50.0, 100.0, 2.0),
+                linvel: Vec2::new(10.0, -20.0),
+                angvel: 3.0,
+            }],
+        }
+    }
+
+    #[test]
+    pub fn test_binary_round_trip() {
+        let replay = test_replay();
+        let bytes = replay.to_bytes().unwrap();
+
+        assert_eq!(bytes[0], REPLAY_MAGIC);
+        assert_eq!(Replay::from_bytes(&bytes).unwrap(), replay);
+    }
+
+    #[test]
+    pub fn test_yaml_round_trip() {
+        let replay = test_replay();
+        let yaml = replay.to_yaml().unwrap();
+
+        assert_eq!(Replay::from_yaml(&yaml).unwrap(), replay);
+    }
+
+    #[test]
+    pub fn test_corrupt_replay() {
+        let mut bytes = test_replay().to_bytes().unwrap();
+        bytes[5] ^= 1;
+
+        assert!(Replay::from_bytes(&bytes).is_err());
+    }
+
+    #[derive(Debug, Default, Resource)]
+    struct TestUi;
+
+    impl UITrait for TestUi {
+        fn is_minimized(&self) -> bool {
+            true
+        }
+
+        fn minimize(&mut self) {}
+
+        fn on_level_complete(_m: &mut ResMut<Self>) {}
+    }
+
+    fn test_level() -> CurrentLevel {
+        let mut level = DesignedLevel::default();
+        level.initial_stage.shapes = [LevelShapeForm::O4, LevelShapeForm::T4, LevelShapeForm::L4]
+            .into_iter()
+            .map(|shape| ShapeCreation {
+                shape,
+                ..Default::default()
+            })
+            .collect();
+
+        CurrentLevel::new(
+            GameLevel::Designed {
+                meta: DesignedLevelMeta::Custom {
+                    level: level.into(),
+                },
+            },
+            LevelCompletion::Incomplete { stage: 0 },
+            None,
+        )
+    }
+
+    /// A headless app with the game's physics, one physics frame per update
+    fn test_app() -> App {
+        let frame_duration = std::time::Duration::from_secs_f64(SECONDS_PER_FRAME);
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::input::InputPlugin)
+            .add_plugins(bevy::window::WindowPlugin::default())
+            .add_plugins(TransformPlugin)
+            .add_plugins(HierarchyPlugin)
+            .init_asset::<Mesh>()
+            .init_resource::<ClearColor>()
+            .init_resource::<UiScale>()
+            .insert_resource(bevy::time::TimeUpdateStrategy::ManualDuration(
+                frame_duration,
+            ))
+            .add_plugins(WallsPlugin)
+            .add_plugins(nice_bevy_utils::window_size::WindowSizePlugin::<
+                SteksBreakpoints,
+            >::default())
+            .add_plugins(WindowSizeTrackingPlugin)
+            .add_plugins(SettingsPlugin)
+            .add_plugins(input::InputPlugin)
+            .add_plugins(HasActedPlugin)
+            .add_plugins(PadlockPlugin)
+            .add_plugins(CollisionPlugin)
+            .init_resource::<TestUi>()
+            .init_resource::<WorldRecords>()
+            .init_resource::<PersonalBests>()
+            .insert_resource(Insets::default())
+            .insert_resource(Time::<Fixed>::from_duration(frame_duration))
+            .insert_resource(RapierConfiguration {
+                gravity: GRAVITY,
+                timestep_mode: TimestepMode::Fixed {
+                    dt: SECONDS_PER_FRAME as f32,
+                    substeps: 1,
+                },
+                ..RapierConfiguration::default()
+            })
+            .add_plugins(
+                RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PHYSICS_SCALE)
+                    .in_fixed_schedule(),
+            )
+            .add_plugins(DragPlugin::<TestUi>::default())
+            .add_plugins(WinPlugin::<TestUi>::default())
+            .add_plugins(ReplayPlugin)
+            .insert_resource(test_level());
+        app
+    }
+
+    fn shapes_vec(app: &mut App) -> ShapesVec {
+        use bevy::ecs::system::RunSystemOnce;
+        app.world.run_system_once(
+            |query: Query<(&ShapeIndex, &Transform, &ShapeComponent, &ShapeModifiers)>| {
+                shapes_vec_from_query(query)
+            },
+        )
+    }
+
+    fn update_times(app: &mut App, times: usize) {
+        for _ in 0..times {
+            app.update();
+        }
+    }
+
+    #[test]
+    pub fn test_playback_matches_recording() {
+        const DRAG_SOURCE: DragSource = DragSource::Mouse;
+
+        let mut recording_app = test_app();
+        update_times(&mut recording_app, 10);
+
+        let start = shapes_vec(&mut recording_app).0[0].location.position;
+        recording_app.world.send_event(DragStartEvent {
+            drag_source: DRAG_SOURCE,
+            position: start,
+        });
+        update_times(&mut recording_app, 1);
+        for step in 1..=20 {
+            recording_app.world.send_event(DragMoveEvent {
+                drag_source: DRAG_SOURCE,
+                new_position: start + Vec2::new(step as f32 * 5.0, step as f32 * 2.0),
+            });
+            update_times(&mut recording_app, 1);
+        }
+        recording_app.world.send_event(RotateEvent {
+            delta: 0.5,
+            snap_resolution: None,
+        });
+        recording_app.world.send_event(DragEndingEvent {
+            drag_source: DRAG_SOURCE,
+        });
+        update_times(&mut recording_app, 100);
+
+        let recorded_shapes = shapes_vec(&mut recording_app);
+        let level = recording_app.world.resource::<CurrentLevel>().level.clone();
+        let replay = recording_app
+            .world
+            .resource_mut::<ReplayRecorder>()
+            .take_replay(level, recorded_shapes.clone());
+
+        assert_eq!(replay.placements.len(), 3);
+        assert!(replay.inputs.len() > 20);
+
+        let mut playback_app = test_app();
+        playback_app.insert_resource(ReplayPlayback::new(
+            Arc::new(Replay::from_bytes(&replay.to_bytes().unwrap()).unwrap()),
+            ReplayMode::Playback,
+        ));
+        update_times(&mut playback_app, 10 + 1 + 20 + 100);
+
+        assert_eq!(shapes_vec(&mut playback_app), recorded_shapes);
+        assert!(playback_app
+            .world
+            .resource::<ReplayRecorder>()
+            .inputs
+            .is_empty());
+    }
+}
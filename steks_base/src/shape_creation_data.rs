@@ -12,7 +12,9 @@ pub struct ShapeCreationData {
     pub id: Option<u32>,
     pub color: Option<Color>,
     pub stage: ShapeStage,
-    pub from_saved_game: bool
+    pub from_saved_game: bool,
+    /// Moving shapes follow their path from where the level puts them, wherever they are created
+    pub motion: Option<MovingShape>,
 }
 
 pub fn add_components(state: &ShapeState, ec: &mut EntityCommands) {
@@ -119,6 +121,12 @@ impl ShapeCreationData {
         Self::update_option_if_some(&mut self.velocity, update.velocity);
         self.modifiers = update.modifiers;
         Self::update_option_if_some(&mut self.color, update.color);
+        if let (Some(moving), Some(location)) = (self.motion.as_mut(), update.location) {
+            moving.origin = location;
+        }
+        if self.state != ShapeState::Fixed {
+            self.motion = None;
+        }
     }
 
     fn update_if_some<T>(base: &mut T, option: Option<T>){
@@ -152,7 +160,8 @@ impl ShapeCreationData {
             id: None,
             color: None,
             stage,
-            from_saved_game: false
+            from_saved_game: false,
+            motion: None,
         }
     }
 
@@ -200,7 +209,11 @@ impl ShapeCreationData {
             id: shape_creation.id,
             color: shape_creation.color.map(|(r, g, b)| Color::rgb_u8(r, g, b)),
             stage,
-            from_saved_game: false
+            from_saved_game: false,
+            motion: shape_creation
+                .motion
+                .filter(|_| shape_creation.state == ShapeState::Fixed)
+                .map(|motion| MovingShape::new(motion, fixed_location.unwrap_or_default())),
         }
     }
 
@@ -214,7 +227,8 @@ impl ShapeCreationData {
             id: None,
             color: None,
             stage,
-            from_saved_game: false
+            from_saved_game: false,
+            motion: None,
         }
     }
 
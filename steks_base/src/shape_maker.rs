@@ -1,7 +1,4 @@
-use crate::{
-    prelude::*,
-    rectangle_set::{self, RectangleSet},
-};
+use crate::prelude::*;
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 use nice_bevy_utils::window_size::WindowSize;
@@ -9,7 +6,8 @@ use nice_bevy_utils::window_size::WindowSize;
 pub fn spawn_and_update_shapes(
     mut commands: Commands,
     current_level: Res<CurrentLevel>,
-    previous_level: Local<PreviousLevel>,
+    mut previous_level: Local<PreviousLevel>,
+    mut pending_reset: ResMut<PendingReset>,
     existing_query: Query<(
         Entity,
         (
@@ -23,11 +21,22 @@ pub fn spawn_and_update_shapes(
     mut check_win: EventWriter<CheckForTowerEvent>,
     settings: Res<GameSettings>,
     window_size: Res<WindowSize<SteksBreakpoints>>,
+    autosave: Option<Res<Autosave>>,
+    mut pending_joints: ResMut<PendingJoints>,
+    mut replay_recorder: Option<ResMut<ReplayRecorder>>,
+    mut replay_playback: Option<ResMut<ReplayPlayback>>,
 ) {
+    let is_reset = std::mem::take(&mut pending_reset.0);
+
     if !current_level.is_changed() {
         return;
     }
 
+    if is_reset {
+        // Start from scratch as though this were a different level
+        *previous_level = PreviousLevel::default();
+    }
+
     let mut result = LevelTransitionResult::from_level(current_level.as_ref(), &previous_level);
 
     //info!("{result:?}");
@@ -36,9 +45,19 @@ pub fn spawn_and_update_shapes(
         for (e, _) in existing_query.iter() {
             commands.entity(e).despawn_recursive();
         }
+        pending_joints.0.clear();
     }
+    pending_joints.0.extend(result.joints.drain(..));
+
+    // The autosave is only used when the game starts
+    let autosave = autosave
+        .as_ref()
+        .and_then(|x| x.0.as_ref())
+        .filter(|save| !is_reset && previous_level.0.is_none() && save.matches(&current_level));
 
-    if let Some(saved_data) = &current_level.saved_data() {
+    if let Some(save) = autosave {
+        result.restore(save);
+    } else if let Some(saved_data) = &current_level.saved_data() {
         if previous_level.compare(&current_level) == PreviousLevelType::DifferentLevel {
             result.mogrify(saved_data);
         }
@@ -70,21 +89,44 @@ pub fn spawn_and_update_shapes(
 
     if !result.creations.is_empty() {
         let mut rng = rand::thread_rng();
-        let mut rectangle_set = rectangle_set::RectangleSet::new(
-            &window_size,
+        let mut rectangle_set = RectangleSet::new(
+            window_size.scaled_width,
+            window_size.scaled_height,
             existing_query
                 .iter()
-                .map(|x| (x.1 .2.clone(), x.1 .3.clone())),
+                .map(|x| (x.1 .2.clone(), Location::from(x.1 .3))),
         ); //TODO adjust positions based on updates
 
-        for creation in result.creations {
-            place_and_create_shape(
+        for mut creation in result.creations {
+            let is_random = creation.location.is_none() || creation.velocity.is_none();
+
+            if is_random {
+                // Replays must start from the same world as the recording
+                if let Some(placement) = replay_playback
+                    .as_mut()
+                    .and_then(|playback| playback.next_placement())
+                {
+                    creation.location.get_or_insert(placement.location);
+                    creation.velocity.get_or_insert(Velocity {
+                        linvel: placement.linvel,
+                        angvel: placement.angvel,
+                    });
+                }
+            }
+
+            let placement = place_and_create_shape(
                 &mut commands,
                 creation,
                 &mut rectangle_set,
                 &mut rng,
                 &settings,
             );
+
+            if is_random {
+                if let Some(recorder) = replay_recorder.as_mut() {
+                    recorder.record_placement(placement);
+                }
+            }
         }
     }
 
@@ -99,7 +141,7 @@ pub fn place_and_create_shape<RNG: rand::Rng>(
     rectangle_set: &mut RectangleSet,
     rng: &mut RNG,
     settings: &GameSettings,
-) {
+) -> ShapePlacement {
     let location: Location = if let Some(l) = shape_with_data.location {
         bevy::log::debug!(
             "Placed shape {} at {}",
@@ -111,18 +153,21 @@ pub fn place_and_create_shape<RNG: rand::Rng>(
         rectangle_set.do_place(shape_with_data.shape.body, rng)
     };
 
-    let velocity = shape_with_data.velocity.unwrap_or_else(|| Velocity {
-        linvel: Vec2 {
-            x: rng.gen_range(-200.0..200.0),
-            y: rng.gen_range(0.0..200.0),
-        },
-        angvel: rng.gen_range(0.0..std::f32::consts::TAU),
+    let velocity = shape_with_data.velocity.unwrap_or_else(|| {
+        let (linvel, angvel) = random_velocity(rng);
+        Velocity { linvel, angvel }
     });
 
     shape_with_data.location = Some(location);
     shape_with_data.velocity = Some(velocity);
 
     create_shape(commands, shape_with_data, settings);
+
+    ShapePlacement {
+        location,
+        linvel: velocity.linvel,
+        angvel: velocity.angvel,
+    }
 }
 
 #[derive(Component, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
@@ -180,15 +225,16 @@ pub fn create_shape(
 
     ec.insert(shape_bundle)
         .insert(shape_with_data.modifiers.friction())
-        .insert(Restitution {
-            coefficient: shape_component.restitution_coefficient(),
-            combine_rule: CoefficientCombineRule::Min,
-        })
+        .insert(shape_component.restitution(&shape_with_data.modifiers))
         .insert(Ccd::enabled())
         .insert(shape_with_data.fill(settings.high_contrast))
         .insert(shape_with_data.stroke(settings.high_contrast))
         .insert(shape_with_data.shape.index)
-        .insert(RigidBody::Dynamic)
+        .insert(if shape_with_data.motion.is_some() {
+            RigidBody::KinematicPositionBased
+        } else {
+            RigidBody::Dynamic
+        })
         .insert(collider_shape)
         .insert(Ccd::enabled())
         .insert(shape_component.locked_axes())
@@ -197,13 +243,14 @@ pub fn create_shape(
         .insert(shape_component.dominance())
         .insert(ExternalForce::default())
         .insert(Sleeping::disabled())
-        .insert(shape_component.collider_mass_properties())
+        .insert(shape_component.collider_mass_properties(&shape_with_data.modifiers))
         .insert(shape_with_data.stage)
         .insert(CollisionGroups {
             memberships: shape_component.collision_group(),
             filters: shape_component.collision_group_filters(),
         })
         .insert(shape_component)
+        .insert(shape_with_data.modifiers)
         .insert(transform);
 
     ec.with_children(|cb| {
@@ -218,6 +265,9 @@ pub fn create_shape(
     if let Some(id) = shape_with_data.id {
         ec.insert(ShapeWithId { id });
     }
+    if let Some(moving) = shape_with_data.motion {
+        ec.insert(moving);
+    }
     crate::shape_creation_data::add_components(&shape_with_data.state, &mut ec);
 }
 
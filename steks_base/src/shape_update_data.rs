@@ -84,7 +84,6 @@ impl ShapeUpdateData {
             ec.insert(shape_component.locked_axes())
                 .insert(shape_component.gravity_scale())
                 .insert(shape_component.dominance())
-                .insert(shape_component.collider_mass_properties())
                 .insert(CollisionGroups {
                     memberships: shape_component.collision_group(),
                     filters: shape_component.collision_group_filters(),
@@ -105,6 +104,10 @@ impl ShapeUpdateData {
         if let Some(state) = self.state {
             shape_creation_data::add_components(&state, &mut ec);
             shape_creation_data::remove_components(&state, &mut ec);
+
+            if state != ShapeState::Fixed {
+                ec.remove::<MovingShape>().insert(RigidBody::Dynamic);
+            }
         }
 
         //Velocity
@@ -115,8 +118,15 @@ impl ShapeUpdateData {
             }
         }
 
-        //Friction
-        ec.insert(self.modifiers.friction());
+        //Modifiers
+        let shape_component: ShapeComponent = self
+            .state
+            .map(|x| x.into())
+            .unwrap_or(*previous_state_component);
+        ec.insert(self.modifiers.friction())
+            .insert(shape_component.restitution(&self.modifiers))
+            .insert(shape_component.collider_mass_properties(&self.modifiers))
+            .insert(self.modifiers);
     }
 }
 
@@ -1,5 +1,6 @@
 use crate::prelude::*;
 
+use chrono::NaiveDate;
 use strum::Display;
 
 #[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
@@ -8,7 +9,13 @@ pub enum TextButton {
     GoFullscreen,
     Tutorial,
     Infinite,
+    ChooseSeed,
+    SeedDigit(u8),
+    DeleteSeedDigit,
+    PlaySeed { seed: u64 },
     DailyChallenge,
+    PastChallenges,
+    GotoChallenge { date: NaiveDate },
     Share,
     ChooseLevel,
     ViewPBs,
@@ -53,7 +60,13 @@ impl TextButton {
             TextButton::GoFullscreen => true,
             TextButton::Tutorial => true,
             TextButton::Infinite => true,
+            TextButton::ChooseSeed => false,
+            TextButton::SeedDigit(_) => false,
+            TextButton::DeleteSeedDigit => false,
+            TextButton::PlaySeed { .. } => true,
             TextButton::DailyChallenge => true,
+            TextButton::PastChallenges => false,
+            TextButton::GotoChallenge { .. } => true,
             TextButton::Share => true,
             TextButton::BackToMenu => false,
             TextButton::ChooseLevel => false,
@@ -87,7 +100,13 @@ impl TextButton {
             TextButton::GoFullscreen => "Fullscreen".to_string(),
             TextButton::Tutorial => "Tutorial".to_string(),
             TextButton::Infinite => "Infinite Mode".to_string(),
+            TextButton::ChooseSeed => "Infinite Seed".to_string(),
+            TextButton::SeedDigit(digit) => digit.to_string(),
+            TextButton::DeleteSeedDigit => "Delete".to_string(),
+            TextButton::PlaySeed { seed } => format!("Play Seed {seed}"),
             TextButton::DailyChallenge => "Daily Challenge".to_string(),
+            TextButton::PastChallenges => "Past Challenges".to_string(),
+            TextButton::GotoChallenge { date } => date.to_string(),
             TextButton::Share => "Share".to_string(),
             TextButton::ChooseLevel => "Choose Level".to_string(),
             TextButton::ViewPBs => "Personal Bests".to_string(),
@@ -175,6 +175,7 @@ pub enum IconButtonStyle {
     Menu,
     News,
     Snow,
+    Undo,
     Big,
 }
 
@@ -248,6 +249,19 @@ impl IntoBundle for IconButtonStyle {
 
                 ..Default::default()
             },
+            IconButtonStyle::Undo => Style {
+                width: Val::Px(ICON_BUTTON_WIDTH),
+                height: Val::Px(ICON_BUTTON_HEIGHT),
+                margin: UiRect::DEFAULT,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_grow: 0.0,
+                flex_shrink: 0.0,
+                left: Val::Auto,
+                top: Val::Percent(0.0),
+
+                ..Default::default()
+            },
             IconButtonStyle::Snow => Style {
                 width: Val::Px(ICON_BUTTON_WIDTH),
                 height: Val::Px(ICON_BUTTON_HEIGHT),
@@ -342,6 +356,29 @@ pub fn text_button_node_with_text(
     }
 }
 
+/// A small square text button, e.g. a key on a keypad
+pub fn key_button_node(button_action: TextButton) -> impl MavericNode<Context = ()> {
+    ButtonNode {
+        style: TextButtonStyle::KEY,
+        visibility: Visibility::Visible,
+        background_color: TEXT_BUTTON_BACKGROUND,
+        border_color: BUTTON_BORDER,
+        marker: TextButtonComponent {
+            disabled: false,
+            button_action,
+            button_type: ButtonType::Text,
+        },
+        children: (TextNode {
+            text: button_action.text(),
+            font_size: BUTTON_FONT_SIZE,
+            color: BUTTON_TEXT_COLOR,
+            font: MENU_TEXT_FONT_PATH,
+            alignment: TextAlignment::Center,
+            linebreak_behavior: bevy::text::BreakLineOn::NoWrap,
+        },),
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct TextButtonStyle {
     pub ui_border_width: Val,
@@ -365,6 +402,10 @@ impl TextButtonStyle {
         ui_border_width: Val::Px(UI_BORDER_WIDTH),
         width: Val::Px(180.),
     };
+    pub const KEY: Self = Self {
+        ui_border_width: Val::Px(UI_BORDER_WIDTH),
+        width: Val::Px(TEXT_BUTTON_HEIGHT),
+    };
 }
 
 impl IntoBundle for TextButtonStyle {
@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+use maveric::helpers::MavericContext;
+
+use crate::prelude::*;
+
+/// How many pickups can be undone
+pub const MAX_UNDO_SNAPSHOTS: usize = 16;
+
+#[derive(Debug, Default)]
+pub struct UndoPlugin<U: UITrait>(PhantomData<U>);
+
+impl<U: UITrait> Plugin for UndoPlugin<U> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UndoHistory>()
+            .add_event::<UndoEvent>()
+            .add_systems(
+                Update,
+                take_snapshot_on_pickup
+                    .after(drag_start::<U>)
+                    .before(handle_drag_changes),
+            )
+            .add_systems(Update, clear_history_on_level_change)
+            .add_systems(
+                Update,
+                handle_undo_events
+                    .after(clear_history_on_level_change)
+                    .before(handle_drag_changes),
+            );
+    }
+}
+
+/// Restore the world to just before the last shape was picked up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct UndoEvent;
+
+#[derive(Debug, Clone, PartialEq)]
+struct ShapeSnapshot {
+    entity: Entity,
+    transform: Transform,
+    velocity: Velocity,
+    shape_component: ShapeComponent,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct WorldSnapshot {
+    shapes: Vec<ShapeSnapshot>,
+}
+
+/// Snapshots taken when shapes were picked up during the current stage
+#[derive(Debug, Clone, Default, Resource, MavericContext)]
+pub struct UndoHistory {
+    snapshots: VecDeque<WorldSnapshot>,
+}
+
+impl UndoHistory {
+    pub fn can_undo(&self) -> bool {
+        !self.snapshots.is_empty()
+    }
+
+    fn push(&mut self, snapshot: WorldSnapshot) {
+        if self.snapshots.len() >= MAX_UNDO_SNAPSHOTS {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+}
+
+fn take_snapshot_on_pickup(
+    mut picked_up_events: EventReader<ShapePickedUpEvent>,
+    shapes: Query<(Entity, &Transform, &Velocity, &ShapeComponent)>,
+    padlock: Res<PadlockResource>,
+    mut history: ResMut<UndoHistory>,
+) {
+    if picked_up_events.is_empty() {
+        return;
+    }
+    picked_up_events.clear();
+
+    let shapes = shapes
+        .iter()
+        .map(|(entity, transform, velocity, shape_component)| {
+            // The picked up shape is already being dragged so work out what it was before
+            let shape_component = match shape_component {
+                ShapeComponent::Dragged(_) if padlock.has_entity(entity) => ShapeComponent::Locked,
+                ShapeComponent::Dragged(_) => ShapeComponent::Free,
+                other => other.clone(),
+            };
+
+            ShapeSnapshot {
+                entity,
+                transform: *transform,
+                velocity: *velocity,
+                shape_component,
+            }
+        })
+        .collect();
+
+    history.push(WorldSnapshot { shapes });
+}
+
+/// Snapshots never survive a stage change, so undo can't go back across a stage boundary
+fn clear_history_on_level_change(
+    current_level: Res<CurrentLevel>,
+    mut history: ResMut<UndoHistory>,
+) {
+    if current_level.is_changed() {
+        history.snapshots.clear();
+    }
+}
+
+fn handle_undo_events(
+    mut undo_events: EventReader<UndoEvent>,
+    mut history: ResMut<UndoHistory>,
+    mut shapes: Query<(&mut Transform, &mut Velocity, &mut ShapeComponent)>,
+    mut countdown: ResMut<WinCountdown>,
+    current_level: Res<CurrentLevel>,
+    mut check_events: EventWriter<CheckForTowerEvent>,
+) {
+    if undo_events.is_empty() {
+        return;
+    }
+    undo_events.clear();
+
+    if current_level.is_changed() {
+        return;
+    }
+
+    let Some(snapshot) = history.snapshots.pop_back() else {
+        return;
+    };
+
+    debug!("Undoing to snapshot of {} shapes", snapshot.shapes.len());
+
+    *countdown = WinCountdown(None);
+
+    for shape in snapshot.shapes {
+        // Shapes may have been removed since the snapshot was taken
+        let Ok((mut transform, mut velocity, mut shape_component)) = shapes.get_mut(shape.entity)
+        else {
+            continue;
+        };
+
+        *transform = shape.transform;
+        *velocity = shape.velocity;
+        // Always set this so that `handle_drag_changes` fixes up the physics and the padlock
+        *shape_component = shape.shape_component;
+    }
+
+    // The restored tower might already be complete
+    check_events.send(CheckForTowerEvent);
+}
@@ -1,7 +1,7 @@
 use bevy_prototype_lyon::{prelude::*, shapes::Rectangle};
 use maveric::prelude::*;
 use nice_bevy_utils::window_size::WindowSize;
-use strum::{Display, EnumIs, EnumIter, IntoEnumIterator};
+use strum::IntoEnumIterator;
 
 use crate::prelude::*;
 
@@ -93,14 +93,15 @@ impl MavericNode for WallNode {
         commands.insert_with_node_and_context(|node, context| {
             let (window_size, insets, settings, rapier) = context;
             let wall = node.0;
-            let point = wall.get_position(
+            let point = wall_translation(
+                &wall,
                 window_size.scaled_height,
                 window_size.scaled_width,
                 rapier.gravity,
                 insets,
                 &window_size,
             );
-            let color = wall.color(settings);
+            let color = wall_color(&wall, settings);
 
             (Fill::color(color), Transform::from_translation(point))
         });
@@ -156,116 +157,56 @@ impl MavericNode for WallSensorNode {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug, EnumIter, Display, Hash)]
-pub enum MarkerType {
-    Horizontal,
-    Vertical,
-}
-
-#[derive(Component, PartialEq, Eq, Clone, Copy, Debug, EnumIter, Display, EnumIs)]
-pub enum WallPosition {
-    Top,
-    Bottom,
-    Left,
-    Right,
-    TopLeft,
-}
-
 #[derive(Debug, Component)]
 pub struct WallSensor;
 
 const WALL_Z: f32 = 2.0;
 const TOP_LEFT_Z: f32 = 1.0;
 
-const TOP_BOTTOM_OFFSET: f32 = 10.0;
-
-impl WallPosition {
-    pub fn get_position(
-        &self,
-        height: f32,
-        width: f32,
-        gravity: Vec2,
-        insets: &Insets,
-        windows_size: &WindowSize<SteksBreakpoints>,
-    ) -> Vec3 {
-        use WallPosition::*;
-        const OFFSET: f32 = WALL_WIDTH / 2.0;
-        const IOS_BOTTOM_OFFSET: f32 = 30.0;
-        let scale = windows_size.object_scale;
-
-        let top_offset = if gravity.y > 0.0 {
-            if cfg!(feature = "ios") {
-                (TOP_BOTTOM_OFFSET).max(insets.real_top()) * -1.0
-            } else {
-                scale * TOP_BOTTOM_OFFSET * -1.0
-            }
-        } else {
-            0.0
-        };
-        let bottom_offset = if gravity.y > 0.0 {
-            0.0
-        } else if cfg!(feature = "ios") {
-            IOS_BOTTOM_OFFSET
+fn wall_translation(
+    wall: &WallPosition,
+    height: f32,
+    width: f32,
+    gravity: Vec2,
+    insets: &Insets,
+    windows_size: &WindowSize<SteksBreakpoints>,
+) -> Vec3 {
+    const IOS_BOTTOM_OFFSET: f32 = 30.0;
+    let scale = windows_size.object_scale;
+
+    let top_offset = if gravity.y > 0.0 {
+        if cfg!(feature = "ios") {
+            (TOP_BOTTOM_OFFSET).max(insets.real_top()) * -1.0
         } else {
-            scale * TOP_BOTTOM_OFFSET
-        };
-
-        match self {
-            Top => Vec3::new(0.0, height / 2.0 + OFFSET + top_offset, WALL_Z),
-            Bottom => Vec3::new(0.0, -height / 2.0 - OFFSET + bottom_offset, WALL_Z),
-            Left => Vec3::new(-width / 2.0 - OFFSET, 0.0, WALL_Z),
-            Right => Vec3::new(width / 2.0 + OFFSET, 0.0, WALL_Z),
-            TopLeft => Vec3 {
-                x: (-width / 2.0) + (TOP_LEFT_SQUARE_SIZE / 2.0),
-                y: (height / 2.0) - (TOP_LEFT_SQUARE_SIZE / 2.0),
-                z: TOP_LEFT_Z,
-            },
+            scale * TOP_BOTTOM_OFFSET * -1.0
         }
-    }
-
-    pub fn show_marker(&self, current_level: &CurrentLevel) -> bool {
-        if !self.is_bottom() {
-            return true;
-        }
-        current_level.level.show_bottom_markers()
-    }
-
-    pub fn get_extents(&self) -> Vec2 {
-        const EXTRA_WIDTH: f32 = WALL_WIDTH * 2.0;
-        use WallPosition::*;
-
-        match self {
-            Top | Bottom => Vec2 {
-                x: MAX_WINDOW_WIDTH + EXTRA_WIDTH,
-                y: WALL_WIDTH,
-            },
-            Left | Right => Vec2 {
-                x: WALL_WIDTH,
-                y: MAX_WINDOW_HEIGHT,
-            },
-            TopLeft => Vec2 {
-                x: TOP_LEFT_SQUARE_SIZE,
-                y: TOP_LEFT_SQUARE_SIZE,
-            },
-        }
-    }
+    } else {
+        0.0
+    };
+    let bottom_offset = if gravity.y > 0.0 {
+        0.0
+    } else if cfg!(feature = "ios") {
+        IOS_BOTTOM_OFFSET
+    } else {
+        scale * TOP_BOTTOM_OFFSET
+    };
+
+    let z = if wall.is_top_left() { TOP_LEFT_Z } else { WALL_Z };
+
+    wall.get_center(height, width, top_offset, bottom_offset).extend(z)
+}
 
-    pub fn marker_type(&self) -> MarkerType {
-        use WallPosition::*;
-        match self {
-            Top | Bottom => MarkerType::Horizontal,
-            Left | Right => MarkerType::Vertical,
-            TopLeft => MarkerType::Horizontal,
-        }
+pub fn show_wall_marker(wall: &WallPosition, current_level: &CurrentLevel) -> bool {
+    if !wall.is_bottom() {
+        return true;
     }
+    current_level.level.show_bottom_markers()
+}
 
-    pub fn color(&self, settings: &GameSettings) -> Color {
-        use WallPosition::*;
-        match self {
-            Top | Bottom | Left | Right => ACCENT_COLOR,
-            TopLeft => settings.background_color(),
-        }
+fn wall_color(wall: &WallPosition, settings: &GameSettings) -> Color {
+    use WallPosition::*;
+    match wall {
+        Top | Bottom | Left | Right => ACCENT_COLOR,
+        TopLeft => settings.background_color(),
     }
 }
-
-const TOP_LEFT_SQUARE_SIZE: f32 = 60.0;
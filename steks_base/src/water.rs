@@ -0,0 +1,146 @@
+use bevy_prototype_lyon::prelude::*;
+
+use crate::prelude::*;
+
+const WATER_Z: f32 = 3.0;
+const WAVE_HEIGHT: f32 = 4.0;
+const WAVE_LENGTH: f32 = 120.0;
+/// Pixels per second
+const WAVE_SPEED: f32 = 40.0;
+/// Horizontal pixels between the points of the surface line
+const WAVE_RESOLUTION: f32 = 10.0;
+
+#[derive(Debug, Default)]
+pub struct WaterPlugin;
+
+impl Plugin for WaterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveWater>()
+            .add_systems(Update, manage_water)
+            .add_systems(Update, animate_water_surface.after(manage_water));
+    }
+}
+
+/// The water of the current stage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Resource)]
+pub struct ActiveWater(pub Option<WaterSettings>);
+
+impl ActiveWater {
+    /// The buoyancy and drag on a shape. This is zero if there is no water.
+    pub fn force_on(
+        &self,
+        shape: &GameShape,
+        location: &Location,
+        velocity: &Velocity,
+        gravity: Vec2,
+    ) -> WaterForce {
+        let Some(water) = self.0 else {
+            return WaterForce::default();
+        };
+
+        water.force_on(
+            &shape.body.get_vertices(SHAPE_SIZE),
+            location,
+            velocity.linvel,
+            velocity.angvel,
+            gravity,
+        )
+    }
+}
+
+/// The pool of water
+#[derive(Debug, Component)]
+pub struct WaterBody;
+
+/// The animated line along the top of the water
+#[derive(Debug, Component)]
+pub struct WaterSurface;
+
+fn manage_water(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    mut active: ResMut<ActiveWater>,
+    existing: Query<Entity, With<WaterBody>>,
+) {
+    if !current_level.is_changed() {
+        return;
+    }
+
+    let water = current_level.level.get_water(current_level.completion);
+    if water == active.0 {
+        return;
+    }
+
+    for entity in existing.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if let Some(water) = water {
+        spawn_water(&mut commands, &water);
+    }
+
+    active.0 = water;
+}
+
+fn spawn_water(commands: &mut Commands, water: &WaterSettings) {
+    let depth = (water.surface + MAX_WINDOW_HEIGHT).max(0.0);
+    let path = GeometryBuilder::build_as(&shapes::Rectangle {
+        extents: Vec2::new(MAX_WINDOW_WIDTH, depth),
+        origin: RectangleOrigin::CustomCenter(Vec2::new(0.0, depth * -0.5)),
+    });
+
+    commands
+        .spawn((
+            ShapeBundle {
+                path,
+                spatial: SpatialBundle::from_transform(Transform::from_xyz(
+                    0.0,
+                    water.surface,
+                    WATER_Z,
+                )),
+                ..Default::default()
+            },
+            Fill::color(WATER_FILL),
+            WaterBody,
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                ShapeBundle {
+                    path: surface_path(0.0),
+                    spatial: SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, 0.1)),
+                    ..Default::default()
+                },
+                Stroke {
+                    color: WATER_SURFACE,
+                    options: StrokeOptions::default()
+                        .with_line_width(WATER_SURFACE_WIDTH)
+                        .with_line_join(LineJoin::Round),
+                },
+                WaterSurface,
+            ));
+        });
+}
+
+fn animate_water_surface(time: Res<Time>, mut surfaces: Query<&mut Path, With<WaterSurface>>) {
+    for mut path in surfaces.iter_mut() {
+        *path = surface_path(time.elapsed_seconds());
+    }
+}
+
+/// A gentle wave across the whole width of the largest window
+fn surface_path(elapsed: f32) -> Path {
+    let half_width = MAX_WINDOW_WIDTH * 0.5;
+    let wave = |x: f32| {
+        let phase = (x - (elapsed * WAVE_SPEED)) / WAVE_LENGTH;
+        (phase * std::f32::consts::TAU).sin() * WAVE_HEIGHT
+    };
+
+    let mut builder = PathBuilder::new();
+    builder.move_to(Vec2::new(-half_width, wave(-half_width)));
+
+    let mut x = -half_width;
+    while x < half_width {
+        x = (x + WAVE_RESOLUTION).min(half_width);
+        builder.line_to(Vec2::new(x, wave(x)));
+    }
+    builder.build()
+}
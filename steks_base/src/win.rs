@@ -16,9 +16,15 @@ impl<U: UITrait> Plugin for WinPlugin<U> {
         app.add_systems(FixedUpdate, check_for_collisions)
             .add_systems(FixedUpdate, check_for_win::<U>)
             .add_event::<LevelWonEvent>()
+            .init_resource::<PendingReset>()
             .add_systems(Update, spawn_and_update_shapes)
             .add_systems(Update, check_for_tower.before(drag_end));
         app.add_plugins(WinCountdownPlugin);
+        app.add_plugins(JointsPlugin);
+        app.add_plugins(KinematicPlugin);
+        app.add_plugins(ForceZonePlugin);
+        app.add_plugins(WaterPlugin);
+        app.add_plugins(ObjectivesPlugin);
     }
 }
 
@@ -29,7 +35,7 @@ pub struct LevelWonEvent {
 
 pub fn check_for_win<U: UITrait>(
     mut countdown: ResMut<WinCountdown>,
-    shapes_query: Query<(&ShapeIndex, &Transform, &ShapeComponent, &Friction)>,
+    shapes_query: Query<(&ShapeIndex, &Transform, &ShapeComponent, &ShapeModifiers)>,
     //time: Res<FixedTime>,
     mut current_level: ResMut<CurrentLevel>,
     mut events: EventWriter<LevelWonEvent>,
@@ -38,6 +44,7 @@ pub fn check_for_win<U: UITrait>(
 
     wrs: Res<WorldRecords>,
     pbs: Res<PersonalBests>,
+    tracker: Res<ObjectiveTracker>,
 ) {
     if current_level.is_changed() {
         *countdown = WinCountdown(None);
@@ -62,13 +69,22 @@ pub fn check_for_win<U: UITrait>(
 
     let shapes = shapes_vec_from_query(shapes_query);
 
+    if let LevelCompletion::Incomplete { stage } = current_level.completion {
+        let is_last_stage = !current_level.level.has_stage(&(stage + 1));
+        if !tracker.allows_win(&shapes, is_last_stage) {
+            return;
+        }
+    }
+
     match current_level.completion {
         LevelCompletion::Incomplete { stage } => {
             let next_stage = stage + 1;
             if current_level.level.has_stage(&next_stage) {
                 current_level.completion = LevelCompletion::Incomplete { stage: next_stage }
             } else {
-                let score_info = current_level.level.generate_score_info(&shapes, &wrs, &pbs);
+                let score_info = current_level
+                    .level
+                    .generate_score_info(&shapes, &wrs, &pbs, &tracker);
                 current_level.completion = LevelCompletion::Complete { score_info };
                 U::on_level_complete(&mut global_ui);
                 //info!("Score info height {}", score_info.height);
@@ -76,7 +92,9 @@ pub fn check_for_win<U: UITrait>(
         }
 
         LevelCompletion::Complete { .. } => {
-            let score_info = current_level.level.generate_score_info(&shapes, &wrs, &pbs);
+            let score_info = current_level
+                .level
+                .generate_score_info(&shapes, &wrs, &pbs, &tracker);
             if score_info.is_pb() {
                 U::on_level_complete(&mut global_ui);
             }
@@ -103,9 +121,20 @@ pub fn check_for_tower(
     walls: Query<Entity, With<WallPosition>>,
     current_level: Res<CurrentLevel>,
     has_acted: Res<HasActed>,
+    moving_shapes: Query<(Entity, &MovingShape)>,
+    force_zones: Res<ActiveForceZones>,
+    water: Res<ActiveWater>,
+    shapes: Query<(Entity, &ShapeComponent, &ShapeIndex)>,
 
+    mut undo_events: EventReader<UndoEvent>,
     mut prediction_context: Local<Option<PredictionContext>>,
 ) {
+    if !undo_events.is_empty() {
+        undo_events.clear();
+        *prediction_context = None;
+        return; // The shapes are about to move
+    }
+
     if check_events.is_empty() && prediction_context.is_none() {
         return;
     }
@@ -161,6 +190,15 @@ pub fn check_for_tower(
                     &rapier_context,
                     rapier_config.clone(),
                     has_acted.as_ref().into(),
+                    moving_shapes
+                        .iter()
+                        .map(|(entity, moving)| (entity, *moving)),
+                    &force_zones,
+                    &water,
+                    shapes
+                        .iter()
+                        .filter(|(_, shape_component, _)| shape_component.is_free())
+                        .map(|(entity, _, shape_index)| (entity, *shape_index)),
                 ));
 
                 None
@@ -1,4 +1,4 @@
-use crate::{prelude::*, rectangle_set};
+use crate::prelude::*;
 use bevy::prelude::*;
 use maveric::node_context::NodeContext;
 use nice_bevy_utils::window_size::{handle_window_resized, Breakpoints, WindowSize};
@@ -54,7 +54,11 @@ fn track_window_size_changes(
         return;
     }
 
-    let mut rectangle_set = rectangle_set::RectangleSet::new(&window_size, std::iter::empty());
+    let mut rectangle_set = RectangleSet::new(
+        window_size.scaled_width,
+        window_size.scaled_height,
+        std::iter::empty(),
+    );
     let mut shapes_to_add: Vec<(Mut<Transform>, &ShapeComponent, &ShapeIndex)> = vec![];
     for shape in draggables_query.iter_mut() {
         if shape.1.is_free() || shape.1.is_locked() {
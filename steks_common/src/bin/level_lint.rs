@@ -0,0 +1,36 @@
+use steks_common::prelude::*;
+
+/// Usage: `level_lint <path>...`
+/// Prints every problem found in the levels in each yaml file
+pub fn main() {
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() {
+        eprintln!("Usage: level_lint <path>...");
+        std::process::exit(2);
+    }
+
+    let mut any_problems = false;
+
+    for path in paths {
+        let levels: Vec<DesignedLevel> = match std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| serde_yaml::from_str(&data).map_err(anyhow::Error::from))
+        {
+            Ok(levels) => levels,
+            Err(err) => {
+                println!("{path}: could not read levels: {err}");
+                any_problems = true;
+                continue;
+            }
+        };
+
+        for diagnostic in lint_levels(levels.iter()) {
+            println!("{path}: {diagnostic}");
+            any_problems = true;
+        }
+    }
+
+    if any_problems {
+        std::process::exit(1);
+    }
+}
@@ -11,6 +11,19 @@ pub const FIXED_SHAPE_STROKE: Color = Color::BLACK;
 pub const VOID_SHAPE_STROKE: Color = WARN_COLOR;
 pub const ICE_SHAPE_STROKE: Color = Color::WHITE;
 pub const ICE_SHAPE_STROKE_HIGH_CONTRAST: Color = Color::GRAY;
+pub const JOINT_STROKE: Color = Color::hsla(30., 0.4, 0.3, 1.0);
+pub const FORCE_ZONE_FILL: Color = Color::hsla(200., 0.7, 0.8, 0.2);
+pub const FORCE_ZONE_STREAK: Color = Color::hsla(200., 0.7, 0.95, 0.6);
+pub const WATER_FILL: Color = Color::hsla(205., 0.8, 0.45, 0.45);
+pub const WATER_SURFACE: Color = Color::hsla(200., 0.8, 0.85, 0.9);
+pub const TARGET_OUTLINE_STROKE: Color = Color::hsla(50., 0.9, 0.6, 0.9);
+pub const FOCUS_RING_STROKE: Color = Color::hsla(219.0, 0.29, 0.34, 0.8);
+pub const BOUNCY_SHAPE_STROKE: Color = Color::hsla(120., 0.8, 0.6, 1.0);
+pub const BOUNCY_SHAPE_STROKE_HIGH_CONTRAST: Color = Color::hsla(120., 1.0, 0.3, 1.0);
+pub const HEAVY_SHAPE_STROKE: Color = Color::hsla(0., 0.0, 0.2, 1.0);
+pub const HEAVY_SHAPE_STROKE_HIGH_CONTRAST: Color = Color::BLACK;
+pub const STICKY_SHAPE_STROKE: Color = Color::hsla(40., 0.9, 0.55, 1.0);
+pub const STICKY_SHAPE_STROKE_HIGH_CONTRAST: Color = Color::hsla(40., 1.0, 0.35, 1.0);
 
 pub const SHADOW_STROKE: Color = Color::hsla(0.0, 0.0, 0.0, 0.8);
 pub const ARROW_STROKE: Color = Color::hsla(219.0, 0.29, 0.34, 1.0);
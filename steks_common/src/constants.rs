@@ -17,6 +17,10 @@ pub const FRAMES_PER_SECOND: u32 = 128;
 pub const LONG_WIN_FRAMES: u32 = FRAMES_PER_SECOND * 5;
 pub const SHORT_WIN_FRAMES: u32 = 3 * (FRAMES_PER_SECOND / 2);
 
+pub const PREDICTION_MAX_SUBSTEPS: u32 = FRAMES_PER_SECOND * 6;
+pub const PREDICTION_EARLY_SENSOR_SUBSTEPS: u32 = SHORT_WIN_FRAMES;
+pub const PREDICTION_MAX_NON_SENSOR_COLLISIONS: i8 = 3;
+
 pub const SHAPE_COLLISION_GROUP: Group = Group::GROUP_1;
 pub const WALL_COLLISION_GROUP: Group = Group::GROUP_2;
 pub const SNOW_COLLISION_GROUP: Group = Group::GROUP_3;
@@ -39,7 +43,12 @@ pub const SNOW_COLLISION_FILTERS: Group = SHAPE_COLLISION_GROUP;
 pub const FIREWORK_COLLISION_FILTERS: Group = Group::NONE;
 
 pub const DRAGGED_DENSITY: f32 = 0.10;
+pub const DEFAULT_DENSITY: f32 = 1.0;
+pub const HEAVY_DENSITY: f32 = 3.0;
 pub const DEFAULT_RESTITUTION: f32 = 0.3;
+pub const BOUNCY_RESTITUTION: f32 = 0.9;
+pub const WATER_DENSITY: f32 = 2.0;
+pub const WATER_DRAG: f32 = 2.0;
 
 pub const INFINITE_MODE_STARTING_SHAPES: usize = 3;
 
@@ -52,6 +61,7 @@ pub const SHAPE_SIZE: f32 = 50f32;
 
 pub const DEFAULT_FRICTION: f32 = 1.0;
 pub const LOW_FRICTION: f32 = 0.1;
+pub const HIGH_FRICTION: f32 = 4.0;
 
 pub const PADLOCK_SCALE: Vec3 = Vec3::new(0.04, 0.04, 1.);
 pub const SKULL_SCALE: Vec3 = Vec3::new(0.35, 0.35, 1.);
@@ -87,5 +97,12 @@ pub const LEVEL_HEIGHT_FONT_SIZE: f32 = 40.0;
 pub const LEVEL_NUMBER_FONT_SIZE: f32 = 30.0;
 
 pub const ICE_STROKE_WIDTH: f32 = 1.0; //TODO make 2 and make the shapes smaller
+pub const MODIFIER_STROKE_WIDTH: f32 = 2.0;
+pub const JOINT_STROKE_WIDTH: f32 = 3.0;
+pub const JOINT_PIN_RADIUS: f32 = 4.0;
+pub const FORCE_ZONE_STREAK_WIDTH: f32 = 2.0;
+pub const WATER_SURFACE_WIDTH: f32 = 3.0;
+pub const TARGET_OUTLINE_WIDTH: f32 = 3.0;
+pub const FOCUS_RING_WIDTH: f32 = 3.0;
 pub const VOID_STROKE_WIDTH: f32 = 1.0;
 pub const FIXED_STROKE_WIDTH: f32 = 1.0;
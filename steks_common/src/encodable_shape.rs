@@ -18,10 +18,35 @@ impl EncodableShape {
         self.shape.game_shape().body.to_collider_shape(SHAPE_SIZE).contains_point(self.location.position, self.location.angle, point)
     }
 
+    /// How far this shape penetrates the other shape, in pixels, if they overlap at all
+    pub fn overlap_depth(&self, other: &Self) -> Option<f32> {
+        use bevy_rapier2d::rapier::math::{Isometry, Vector};
+        let isometry = |location: &Location| {
+            Isometry::new(
+                Vector::new(location.position.x, location.position.y),
+                location.angle,
+            )
+        };
+
+        let collider = self.shape.game_shape().body.to_collider_shape(SHAPE_SIZE);
+        let other_collider = other.shape.game_shape().body.to_collider_shape(SHAPE_SIZE);
+
+        let contact = bevy_rapier2d::parry::query::contact(
+            &isometry(&self.location),
+            &*collider.raw,
+            &isometry(&other.location),
+            &*other_collider.raw,
+            0.0,
+        )
+        .ok()
+        .flatten()?;
+
+        (contact.dist < 0.0).then_some(-contact.dist)
+    }
+
     pub fn stroke_color(&self) -> Option<Color> {
-        match self.modifiers {
-            ShapeModifiers::Normal => (),
-            ShapeModifiers::Ice => return Some(ICE_SHAPE_STROKE),
+        if let Some(color) = self.modifiers.stroke_color(false) {
+            return Some(color);
         }
 
         use ShapeState::*;
@@ -52,14 +77,21 @@ impl EncodableShape {
         (state * 16) + modifiers
     }
 
-    fn decode_state_and_modifiers(byte: u8) -> (ShapeState, ShapeModifiers) {
-        let state = ShapeState::try_from(byte / 16).unwrap_or_default();
-        let modifiers = ShapeModifiers::try_from(byte % 16).unwrap_or_default();
-
-        (state, modifiers)
+    fn decode_state_and_modifiers(
+        byte: u8,
+    ) -> Result<(ShapeState, ShapeModifiers), ShareCodeError> {
+        let state = ShapeState::try_from(byte / 16)
+            .map_err(|_| ShareCodeError::UnknownState { state: byte / 16 })?;
+        let modifiers = ShapeModifiers::try_from(byte % 16).map_err(|_| {
+            ShareCodeError::UnknownModifiers {
+                modifiers: byte % 16,
+            }
+        })?;
+
+        Ok((state, modifiers))
     }
 
-    pub fn encode(&self) -> [u8; 7] {
+    pub fn encode(&self) -> [u8; ENCODED_SHAPE_LENGTH] {
         let Self {
             shape,
             location,
@@ -86,11 +118,17 @@ impl EncodableShape {
         ]
     }
 
-    pub fn decode(arr: &[u8]) -> Self {
+    pub fn decode(arr: &[u8]) -> Result<Self, ShareCodeError> {
+        if arr.len() < ENCODED_SHAPE_LENGTH {
+            return Err(ShareCodeError::Truncated { length: arr.len() });
+        }
         let shape_index = arr[0];
-        let (state, modifiers) = Self::decode_state_and_modifiers(arr[1]);
+        let (state, modifiers) = Self::decode_state_and_modifiers(arr[1])?;
 
-        let shape = ShapeIndex(shape_index % (ALL_SHAPES.len() as u8));
+        if shape_index >= ShapeIndex::exclusive_max().0 {
+            return Err(ShareCodeError::UnknownShape { index: shape_index });
+        }
+        let shape = ShapeIndex(shape_index);
         let x_u16 = u16::from_be_bytes([arr[2], arr[3]]);
         let y_u16 = u16::from_be_bytes([arr[4], arr[5]]);
         let x = denormalize_from_range(x_u16, X_RANGE);
@@ -99,12 +137,12 @@ impl EncodableShape {
         let position = Vec2 { x, y };
         let location = Location { position, angle };
 
-        EncodableShape {
+        Ok(EncodableShape {
             shape,
             location,
             state,
             modifiers,
-        }
+        })
     }
 }
 
@@ -178,11 +216,42 @@ mod tests {
 
         let encoded = fs.encode();
 
-        let decoded = EncodableShape::decode(&encoded);
+        let decoded = EncodableShape::decode(&encoded).unwrap();
 
         assert_eq!(fs, decoded)
     }
 
+    #[test]
+    fn test_modifier_encoding_roundtrip() {
+        use strum::IntoEnumIterator;
+        let shape = GameShape::by_name("O4").unwrap().index;
+
+        for modifiers in ShapeModifiers::iter() {
+            let fs = EncodableShape {
+                shape,
+                location: Location::new(10.0, 20.0, 0.0),
+                state: ShapeState::Fixed,
+                modifiers,
+            };
+
+            let decoded = EncodableShape::decode(&fs.encode()).unwrap();
+
+            assert_eq!(fs, decoded)
+        }
+    }
+
+    #[test]
+    fn test_decode_unknown_shape() {
+        let encoded = [ALL_SHAPES.len() as u8, 0, 0, 0, 0, 0, 0];
+
+        assert_eq!(
+            EncodableShape::decode(&encoded),
+            Err(ShareCodeError::UnknownShape {
+                index: ALL_SHAPES.len() as u8
+            })
+        );
+    }
+
     #[test]
     fn test_normalize_to_range() {
         let range = (-5.0)..=5.0;
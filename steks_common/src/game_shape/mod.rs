@@ -8,14 +8,17 @@ use crate::{
 use bevy::prelude::{Color, Rect, Vec2};
 use bevy_prototype_lyon::prelude::*;
 use bevy_rapier2d::prelude::Collider;
-use geometrid::polyomino::Polyomino;
+use geometrid::{polyomino::Polyomino, prelude::DynamicVertex};
+use strum::{EnumIter, IntoEnumIterator};
 
 pub mod circle;
 pub mod polyomino;
+pub mod regular_polygon;
 mod rounded_polygon;
 pub mod triangle;
 pub use circle::*;
 use once_cell::sync::Lazy;
+pub use regular_polygon::*;
 pub use triangle::*;
 
 pub trait GameShapeBody: Send + Sync {
@@ -35,6 +38,7 @@ pub struct GameShape {
     pub name: &'static str,
     pub body: &'static dyn GameShapeBody,
     pub index: ShapeIndex,
+    pub family: ShapeFamily,
 }
 
 impl PartialOrd for GameShape {
@@ -100,39 +104,175 @@ impl std::fmt::Display for GameShape {
     }
 }
 
-pub static ALL_SHAPES: Lazy<Vec<GameShape>> = Lazy::new(|| {
-    let v1: [(&'static dyn GameShapeBody, &'static str); 2] =
-        [(&Circle {}, "Circle"), (&TRIANGLE, "Triangle")];
+/// Groups of related shapes. Shape indices are assigned in family order,
+/// so new families must be added at the end to keep old share codes and record hashes working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumIter)]
+pub enum ShapeFamily {
+    Circle,
+    Triangle,
+    Tetromino,
+    Pentomino,
+    Domino,
+    Tromino,
+    Hexomino,
+    ExtraTriangle,
+    RegularPolygon,
+}
 
-    let tetrominos = Polyomino::TETROMINOS
-        .iter()
-        .map(|x| x as &'static dyn GameShapeBody)
-        .zip(Polyomino::TETROMINO_NAMES.map(|tn| {
-            let r: &'static str = Box::leak((tn.to_string() + "4").into_boxed_str());
-            r
-        }));
+impl ShapeFamily {
+    /// The shapes in this family, in index order
+    pub fn shapes(&self) -> Vec<(&'static dyn GameShapeBody, &'static str)> {
+        match self {
+            ShapeFamily::Circle => CIRCLES.to_vec(),
+            ShapeFamily::Triangle => TRIANGLES.to_vec(),
+            ShapeFamily::Tetromino => Polyomino::TETROMINOS
+                .iter()
+                .map(|x| x as &'static dyn GameShapeBody)
+                .zip(Polyomino::TETROMINO_NAMES.map(|tn| {
+                    let r: &'static str = Box::leak((tn.to_string() + "4").into_boxed_str());
+                    r
+                }))
+                .collect(),
+            ShapeFamily::Pentomino => STEKS_FREE_PENTOMINOS
+                .iter()
+                .map(|x| x as &'static dyn GameShapeBody)
+                .zip(STEKS_FREE_PENTOMINO_NAMES.map(|tn| {
+                    let r: &'static str = Box::leak((tn.to_string() + "5").into_boxed_str());
+                    r
+                }))
+                .collect(),
+            ShapeFamily::Domino => DOMINOS.to_vec(),
+            ShapeFamily::Tromino => TROMINOS.to_vec(),
+            ShapeFamily::Hexomino => HEXOMINOS.to_vec(),
+            ShapeFamily::ExtraTriangle => EXTRA_TRIANGLES.to_vec(),
+            ShapeFamily::RegularPolygon => REGULAR_POLYGONS.to_vec(),
+        }
+    }
 
-    let pentominos = STEKS_FREE_PENTOMINOS
-        .iter()
-        .map(|x| x as &'static dyn GameShapeBody)
-        .zip(STEKS_FREE_PENTOMINO_NAMES.map(|tn| {
-            let r: &'static str = Box::leak((tn.to_string() + "5").into_boxed_str());
-            r
-        }));
-
-    v1.into_iter()
-        .chain(tetrominos)
-        .chain(pentominos)
+    /// Classic shapes are the ones randomly chosen for challenges and infinite mode.
+    /// Adding families must not change which shapes a seed produces.
+    pub fn is_classic(&self) -> bool {
+        matches!(
+            self,
+            ShapeFamily::Circle
+                | ShapeFamily::Triangle
+                | ShapeFamily::Tetromino
+                | ShapeFamily::Pentomino
+        )
+    }
+}
+
+pub static ALL_SHAPES: Lazy<Vec<GameShape>> = Lazy::new(|| {
+    ShapeFamily::iter()
+        .flat_map(|family| {
+            family
+                .shapes()
+                .into_iter()
+                .map(move |(body, name)| (family, body, name))
+        })
         .enumerate()
-        .map(|(index, (body, name))| GameShape {
+        .map(|(index, (family, body, name))| GameShape {
             name,
             body,
             index: ShapeIndex(index as u8),
+            family,
         })
         .collect()
 });
 
-const TRIANGLE: Triangle = Triangle(&[(-1, -1), (-1, 2), (2, -1)]);
+/// The number of classic shapes. These always come first.
+pub static CLASSIC_SHAPE_COUNT: Lazy<usize> = Lazy::new(|| {
+    ALL_SHAPES
+        .iter()
+        .take_while(|x| x.family.is_classic())
+        .count()
+});
+
+type FamilyShapes = &'static [(&'static dyn GameShapeBody, &'static str)];
+
+const CIRCLES: FamilyShapes = &[(&Circle, "Circle")];
+const TRIANGLES: FamilyShapes = &[(&TRIANGLE, "Triangle")];
+const DOMINOS: FamilyShapes = &[(&DOMINO, "I2")];
+const TROMINOS: FamilyShapes = &[(&I_TROMINO, "I3"), (&L_TROMINO, "L3")];
+const HEXOMINOS: FamilyShapes = &[
+    (&O_HEXOMINO, "O6"),
+    (&L_HEXOMINO, "L6"),
+    (&T_HEXOMINO, "T6"),
+    (&X_HEXOMINO, "X6"),
+];
+const EXTRA_TRIANGLES: FamilyShapes = &[(&ISOSCELES, "Isosceles"), (&OBTUSE, "Obtuse")];
+const REGULAR_POLYGONS: FamilyShapes = &[
+    (&PENTAGON, "Pentagon"),
+    (&HEXAGON, "Hexagon"),
+    (&OCTAGON, "Octagon"),
+];
+
+const TRIANGLE: Triangle = Triangle {
+    vertices: &[(-1, -1), (-1, 2), (2, -1)],
+    slide_scale: 0.985,
+};
+const ISOSCELES: Triangle = Triangle {
+    vertices: &[(-2, -1), (2, -1), (0, 2)],
+    slide_scale: 1.0,
+};
+const OBTUSE: Triangle = Triangle {
+    vertices: &[(-1, -1), (3, -1), (-2, 2)],
+    slide_scale: 1.0,
+};
+
+const PENTAGON: RegularNgon = RegularNgon(5);
+const HEXAGON: RegularNgon = RegularNgon(6);
+const OCTAGON: RegularNgon = RegularNgon(8);
+
+const DOMINO: Polyomino<2> = Polyomino::new([DynamicVertex::new(0, 0), DynamicVertex::new(0, 1)]);
+
+const I_TROMINO: Polyomino<3> = Polyomino::new([
+    DynamicVertex::new(0, 0),
+    DynamicVertex::new(0, 1),
+    DynamicVertex::new(0, 2),
+]);
+
+const L_TROMINO: Polyomino<3> = Polyomino::new([
+    DynamicVertex::new(0, 0),
+    DynamicVertex::new(0, 1),
+    DynamicVertex::new(1, 0),
+]);
+
+const O_HEXOMINO: Polyomino<6> = Polyomino::new([
+    DynamicVertex::new(0, 0),
+    DynamicVertex::new(1, 0),
+    DynamicVertex::new(0, 1),
+    DynamicVertex::new(1, 1),
+    DynamicVertex::new(0, 2),
+    DynamicVertex::new(1, 2),
+]);
+
+const L_HEXOMINO: Polyomino<6> = Polyomino::new([
+    DynamicVertex::new(0, 0),
+    DynamicVertex::new(0, 1),
+    DynamicVertex::new(0, 2),
+    DynamicVertex::new(0, 3),
+    DynamicVertex::new(0, 4),
+    DynamicVertex::new(1, 0),
+]);
+
+const T_HEXOMINO: Polyomino<6> = Polyomino::new([
+    DynamicVertex::new(0, 0),
+    DynamicVertex::new(1, 0),
+    DynamicVertex::new(2, 0),
+    DynamicVertex::new(1, 1),
+    DynamicVertex::new(1, 2),
+    DynamicVertex::new(1, 3),
+]);
+
+const X_HEXOMINO: Polyomino<6> = Polyomino::new([
+    DynamicVertex::new(1, 0),
+    DynamicVertex::new(0, 1),
+    DynamicVertex::new(1, 1),
+    DynamicVertex::new(2, 1),
+    DynamicVertex::new(1, 2),
+    DynamicVertex::new(1, 3),
+]);
 
 const STEKS_FREE_PENTOMINOS: [Polyomino<5>; 12] = [
     Polyomino::<5>::F_PENTOMINO,
@@ -187,3 +327,69 @@ pub fn svg_style(fill: Option<Color>, stroke: Option<Color>) -> String {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_classic_shape_indices_are_stable() {
+        let names: Vec<&str> = ALL_SHAPES
+            .iter()
+            .take(*CLASSIC_SHAPE_COUNT)
+            .map(|x| x.name)
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "Circle", "Triangle", "I4", "O4", "T4", "J4", "L4", "S4", "Z4", "F5", "I5", "L5",
+                "N5", "P5", "T5", "U5", "V5", "W5", "X5", "Y5", "S5"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shape_names_are_unique() {
+        let mut names: Vec<String> = ALL_SHAPES.iter().map(|x| x.name.to_lowercase()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), ALL_SHAPES.len());
+    }
+
+    #[test]
+    fn test_shape_bodies_are_consistent() {
+        for shape in ALL_SHAPES.iter() {
+            let vertices = shape.body.get_vertices(SHAPE_SIZE);
+            assert!(vertices.len() >= 3, "{shape} has too few vertices");
+
+            let location = Location::new(100.0, 50.0, 0.0);
+            let bounding_box = shape.body.bounding_box(SHAPE_SIZE, &location);
+            for vertex in vertices {
+                assert!(
+                    bounding_box.contains(vertex + location.position),
+                    "{shape} vertex {vertex} is outside its bounding box"
+                );
+            }
+
+            assert!(!shape.body.as_svg(SHAPE_SIZE, None, None).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_triangle_colliders_match_vertices() {
+        for triangle in [ISOSCELES, OBTUSE] {
+            let drawn = triangle.get_vertices(SHAPE_SIZE);
+            let collider = triangle.collider_vertices(SHAPE_SIZE);
+            let scale = collider[0].x / drawn[0].x;
+
+            for (drawn, collider) in drawn.into_iter().zip(collider) {
+                assert!(
+                    (drawn * scale - collider).length() < 0.001,
+                    "{collider} does not match {drawn}"
+                );
+            }
+        }
+    }
+}
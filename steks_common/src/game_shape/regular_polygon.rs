@@ -0,0 +1,94 @@
+use std::f32::consts::{PI, TAU};
+
+use crate::prelude::*;
+
+use super::{GameShapeBody, SHAPE_RADIUS_RATIO};
+use bevy::prelude::{Color, Quat, Rect, Transform, Vec2};
+use bevy_prototype_lyon::{prelude::*, shapes::RoundedPolygon};
+use bevy_rapier2d::prelude::Collider;
+
+/// A regular polygon with this many sides and the same area as the other shapes
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub struct RegularNgon(pub usize);
+
+impl RegularNgon {
+    fn circumradius(&self, shape_size: f32) -> f32 {
+        let sides = self.0 as f32;
+        shape_size * (2.0 / (sides * (TAU / sides).sin())).sqrt()
+    }
+
+    /// Vertices with a flat edge at the bottom
+    fn vertices(&self, circumradius: f32) -> impl Iterator<Item = Vec2> {
+        let sides = self.0;
+        let offset = (PI / sides as f32) - (PI * 0.5);
+        (0..sides).map(move |index| {
+            Vec2::from_angle(offset + (index as f32 * TAU / sides as f32)) * circumradius
+        })
+    }
+}
+
+impl GameShapeBody for RegularNgon {
+    fn to_collider_shape(&self, shape_size: f32) -> Collider {
+        let shape_radius = shape_size * SHAPE_RADIUS_RATIO;
+        // Inset the edges by the corner radius, like the polyomino colliders
+        let circumradius =
+            self.circumradius(shape_size) - (shape_radius / (PI / self.0 as f32).cos());
+
+        let vertices: Vec<Vec2> = self.vertices(circumradius).collect();
+        let indices: Vec<[u32; 2]> = (0..self.0)
+            .map(|i| [i as u32, ((i + 1) % self.0) as u32])
+            .collect();
+
+        Collider::round_convex_decomposition(&vertices, &indices, shape_radius / PHYSICS_SCALE)
+    }
+
+    fn get_vertices(&self, shape_size: f32) -> Vec<Vec2> {
+        self.vertices(self.circumradius(shape_size)).collect()
+    }
+
+    fn get_shape_bundle(&self, shape_size: f32) -> ShapeBundle {
+        let shape = RoundedPolygon {
+            points: self.get_vertices(shape_size),
+            closed: true,
+            radius: shape_size * SHAPE_RADIUS_RATIO,
+        };
+
+        ShapeBundle {
+            path: GeometryBuilder::build_as(&shape),
+            ..Default::default()
+        }
+    }
+
+    fn bounding_box(&self, size: f32, location: &Location) -> Rect {
+        let rotation = Transform::from_rotation(Quat::from_rotation_z(location.angle));
+
+        let mut min_x = location.position.x;
+        let mut max_x = location.position.x;
+        let mut min_y = location.position.y;
+        let mut max_y = location.position.y;
+
+        for p in self.vertices(self.circumradius(size)) {
+            let p = rotation.transform_point(p.extend(0.0)).truncate() + location.position;
+
+            min_x = min_x.min(p.x);
+            min_y = min_y.min(p.y);
+
+            max_x = max_x.max(p.x);
+            max_y = max_y.max(p.y);
+        }
+
+        Rect::from_corners(Vec2::new(min_x, min_y), Vec2::new(max_x, max_y))
+    }
+
+    fn as_svg(&self, size: f32, fill: Option<Color>, stroke: Option<Color>) -> String {
+        let points = self.get_vertices(size);
+
+        let path = crate::game_shape::rounded_polygon::make_rounded_polygon_path(
+            points.as_slice(),
+            size * SHAPE_RADIUS_RATIO,
+        );
+        let style = svg_style(fill, stroke);
+
+        format!(r#"<path {style} d="{path}"  />"#)
+    }
+}
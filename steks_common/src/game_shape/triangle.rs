@@ -5,31 +5,43 @@ use bevy::prelude::{Color, Quat, Rect, Transform, Vec2};
 use bevy_prototype_lyon::{prelude::*, shapes::RoundedPolygon};
 use bevy_rapier2d::prelude::Collider;
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
-pub struct Triangle(pub &'static [(isize, isize); 3]);
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Triangle {
+    pub vertices: &'static [(isize, isize); 3],
+    /// Shrinks the collider along the legs of a right triangle so two triangles can slide against each other.
+    /// This tilts the edges of any other triangle so they should use 1.0
+    pub slide_scale: f32,
+}
 
 const ROOT_SQUARES: f32 = 2.0;
 
-impl GameShapeBody for Triangle {
-    fn to_collider_shape(&self, shape_size: f32) -> Collider {
+impl Triangle {
+    /// The vertices of the collider, before it is rounded
+    pub fn collider_vertices(&self, shape_size: f32) -> [Vec2; 3] {
         let u = (1.0 - (SHAPE_RADIUS_RATIO / (1.0 - SHAPE_RADIUS_RATIO))) * shape_size
             / (1.0 * ROOT_SQUARES);
-        let shape_radius = shape_size * SHAPE_RADIUS_RATIO;
 
-        let vertices = [
+        [
             Vec2 {
-                x: self.0[0].0 as f32 * u,
-                y: self.0[0].1 as f32 * u,
+                x: self.vertices[0].0 as f32 * u,
+                y: self.vertices[0].1 as f32 * u,
             },
             Vec2 {
-                x: self.0[1].0 as f32 * u * 1.00,
-                y: self.0[1].1 as f32 * u * 0.985, //The 0.985s are for if two triangles are sliding against each other
+                x: self.vertices[1].0 as f32 * u * 1.00,
+                y: self.vertices[1].1 as f32 * u * self.slide_scale,
             },
             Vec2 {
-                x: self.0[2].0 as f32 * u * 0.985, //
-                y: self.0[2].1 as f32 * u * 1.00,
+                x: self.vertices[2].0 as f32 * u * self.slide_scale,
+                y: self.vertices[2].1 as f32 * u * 1.00,
             },
-        ];
+        ]
+    }
+}
+
+impl GameShapeBody for Triangle {
+    fn to_collider_shape(&self, shape_size: f32) -> Collider {
+        let shape_radius = shape_size * SHAPE_RADIUS_RATIO;
+        let vertices = self.collider_vertices(shape_size);
 
         let start_indices: [[u32; 2]; 3] =
             core::array::from_fn(|i| [i as u32, ((i + 1) % 3) as u32]);
@@ -43,7 +55,7 @@ impl GameShapeBody for Triangle {
 
     fn get_vertices(&self, shape_size: f32) -> Vec<Vec2> {
         let u = shape_size / (1.0 * ROOT_SQUARES);
-        self.0
+        self.vertices
             .map(|(x, y)| Vec2::new((x as f32) * u, (y as f32) * u))
             .into_iter()
             .collect()
@@ -54,7 +66,7 @@ impl GameShapeBody for Triangle {
 
         let shape = RoundedPolygon {
             points: self
-                .0
+                .vertices
                 .map(|(x, y)| Vec2::new((x as f32) * u, (y as f32) * u))
                 .into(),
             closed: true,
@@ -75,7 +87,7 @@ impl GameShapeBody for Triangle {
         let mut min_y = location.position.y;
         let mut max_y = location.position.y;
 
-        for (x, y) in self.0 {
+        for (x, y) in self.vertices {
             let p = Vec2::new(*x as f32 * size, *y as f32 * size);
             let p = rotation.transform_point(p.extend(0.0)).truncate() + location.position;
 
@@ -92,7 +104,7 @@ impl GameShapeBody for Triangle {
     fn as_svg(&self, size: f32, fill: Option<Color>, stroke: Option<Color>) -> String {
         let u = size / (1.0 * ROOT_SQUARES);
         let points = self
-            .0
+            .vertices
             .map(|(x, y)| Vec2::new((x as f32) * u, (y as f32) * u));
 
         let path =
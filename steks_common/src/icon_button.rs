@@ -30,6 +30,8 @@ pub enum IconButton {
     FollowNewsLink,
 
     RefreshWR,
+    RetryLevel,
+    Undo,
     None,
 }
 
@@ -38,6 +40,8 @@ impl IconButton {
         use IconButton::*;
         match self {
             RefreshWR => "\u{e800}",
+            RetryLevel => "\u{e800}",
+            Undo => "\u{e80d}",
             OpenMenu => "\u{f0c9}",
             Share => "\u{f1e0}",
             SharePB => "\u{f1e0}",
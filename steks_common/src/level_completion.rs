@@ -24,9 +24,24 @@ pub struct ScoreInfo {
 
     pub wr: WRData,
     pub pb: f32,
+
+    /// Seconds from the first pickup until the level was won
+    #[serde(default)]
+    pub seconds: f32,
+    /// The number of shapes picked up
+    #[serde(default)]
+    pub moves: u32,
 }
 
 impl ScoreInfo {
+    pub fn score(&self) -> LevelScore {
+        LevelScore {
+            height: self.height,
+            seconds: self.seconds,
+            moves: self.moves,
+        }
+    }
+
     pub fn is_pb(&self) -> bool {
         self.height > self.pb
     }
@@ -0,0 +1,487 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use strum::EnumIs;
+
+use crate::prelude::*;
+
+/// Fixed shapes may touch but should not overlap by more than this many pixels
+pub const FIXED_OVERLAP_TOLERANCE: f32 = 1.0;
+
+/// A problem found in a designed level
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintDiagnostic {
+    /// Index of the level in its file
+    pub level: usize,
+    /// The stage the problem is in, if it is in a stage
+    pub stage: Option<usize>,
+    /// Path to the field with the problem e.g. `stages[0].updates[1].id`
+    pub path: String,
+    pub kind: LintKind,
+}
+
+#[derive(Debug, Clone, PartialEq, EnumIs)]
+pub enum LintKind {
+    /// A shape update targets an id which no shape has by that stage
+    UnknownUpdateId { id: u32 },
+    /// More than one shape has the same id
+    DuplicateId { id: u32, first: String },
+    /// A shape is placed outside the largest possible window
+    OutOfBounds { x: f32, y: f32 },
+    /// Two fixed shapes overlap
+    OverlappingFixedShapes { other: String, depth: f32 },
+    /// Three stars needs a taller tower than the shapes can make
+    StarsTooHigh { three: f32, max_height: f32 },
+    /// Three stars should be harder to get than two stars
+    StarsOutOfOrder { two: f32, three: f32 },
+    /// An outline or arrow is pinned to an id which no shape has by that stage
+    MissingPinnedShape { id: u32 },
+    /// A joint connects to an id which no shape has by that stage
+    UnknownJointId { id: u32 },
+    /// A shape is the second shape of more than one joint
+    DuplicateJointShape { id: u32, first: String },
+}
+
+impl Display for LintKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintKind::UnknownUpdateId { id } => write!(f, "update to unknown shape id {id}"),
+            LintKind::DuplicateId { id, first } => {
+                write!(f, "shape id {id} is already used by {first}")
+            }
+            LintKind::OutOfBounds { x, y } => write!(f, "shape at ({x}, {y}) is out of bounds"),
+            LintKind::OverlappingFixedShapes { other, depth } => {
+                write!(f, "fixed shape overlaps {other} by {depth:.1}px")
+            }
+            LintKind::StarsTooHigh { three, max_height } => write!(
+                f,
+                "three stars needs {three} but the tallest possible tower is {max_height:.1}"
+            ),
+            LintKind::StarsOutOfOrder { two, three } => {
+                write!(
+                    f,
+                    "three stars ({three}) should be harder to get than two stars ({two})"
+                )
+            }
+            LintKind::MissingPinnedShape { id } => write!(f, "pinned to unknown shape id {id}"),
+            LintKind::UnknownJointId { id } => write!(f, "joint to unknown shape id {id}"),
+            LintKind::DuplicateJointShape { id, first } => {
+                write!(f, "shape id {id} is already the second shape of {first}")
+            }
+        }
+    }
+}
+
+impl Display for LintDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "level {}", self.level)?;
+        if let Some(stage) = self.stage {
+            write!(f, " stage {stage}")?;
+        }
+        write!(f, " {}: {}", self.path, self.kind)
+    }
+}
+
+/// Lint every level in a file
+pub fn lint_levels<'a>(levels: impl IntoIterator<Item = &'a DesignedLevel>) -> Vec<LintDiagnostic> {
+    levels
+        .into_iter()
+        .enumerate()
+        .flat_map(|(index, level)| lint_level(level, index))
+        .collect()
+}
+
+/// Lint a single level. `index` is only used to label the diagnostics.
+pub fn lint_level(level: &DesignedLevel, index: usize) -> Vec<LintDiagnostic> {
+    let mut diagnostics = vec![];
+    let mut push = |stage: Option<usize>, path: String, kind: LintKind| {
+        diagnostics.push(LintDiagnostic {
+            level: index,
+            stage,
+            path,
+            kind,
+        })
+    };
+
+    // Every shape which exists so far, keyed by the path where it was created
+    let mut world: BTreeMap<String, ShapeCreation> = Default::default();
+    let mut ids: BTreeMap<u32, String> = Default::default();
+    let mut joint_shapes: BTreeMap<u32, String> = Default::default();
+    let mut any_unknown_ids = false;
+
+    for (stage_index, stage) in level.all_stages().enumerate() {
+        let prefix = stage_prefix(stage_index);
+        let mut changed: Vec<String> = vec![];
+
+        for (shape_index, creation) in stage.shapes.iter().enumerate() {
+            let path = format!("{prefix}shapes[{shape_index}]");
+
+            if let Some(id) = creation.id {
+                if let Some(first) = ids.get(&id) {
+                    push(
+                        Some(stage_index),
+                        format!("{path}.id"),
+                        LintKind::DuplicateId {
+                            id,
+                            first: first.clone(),
+                        },
+                    );
+                } else {
+                    ids.insert(id, path.clone());
+                }
+            }
+
+            world.insert(path.clone(), *creation);
+            changed.push(path);
+        }
+
+        for (update_index, update) in stage.updates.iter().enumerate() {
+            let Some(created_path) = ids.get(&update.id) else {
+                any_unknown_ids = true;
+                push(
+                    Some(stage_index),
+                    format!("{prefix}updates[{update_index}].id"),
+                    LintKind::UnknownUpdateId { id: update.id },
+                );
+                continue;
+            };
+
+            if let Some(creation) = world.get_mut(created_path) {
+                creation.apply_update(update);
+                changed.push(created_path.clone());
+            }
+        }
+
+        for path in changed.iter() {
+            let Some(creation) = world.get(path) else {
+                continue;
+            };
+            if let (Some(x), Some(y)) = (creation.x, creation.y) {
+                if x.abs() > MAX_WINDOW_WIDTH * 0.5 || y.abs() > MAX_WINDOW_HEIGHT * 0.5 {
+                    push(
+                        Some(stage_index),
+                        path.clone(),
+                        LintKind::OutOfBounds { x, y },
+                    );
+                }
+            }
+        }
+
+        let fixed_shapes: Vec<(&String, EncodableShape)> = world
+            .iter()
+            .filter(|(_, creation)| creation.state.is_fixed())
+            .filter_map(|(path, creation)| Some((path, placed_shape(creation)?)))
+            .collect();
+
+        for (i, (path, shape)) in fixed_shapes.iter().enumerate() {
+            for (other_path, other) in fixed_shapes.iter().skip(i + 1) {
+                // Only report each overlap in the stage where it starts
+                if !changed.contains(*path) && !changed.contains(*other_path) {
+                    continue;
+                }
+                if let Some(depth) = shape.overlap_depth(other) {
+                    if depth > FIXED_OVERLAP_TOLERANCE {
+                        push(
+                            Some(stage_index),
+                            path.to_string(),
+                            LintKind::OverlappingFixedShapes {
+                                other: other_path.to_string(),
+                                depth,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        let outlines = stage
+            .outlines
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (format!("{prefix}outlines[{i}].shape_id"), x.shape_id));
+        let arrows = stage
+            .arrows
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (format!("{prefix}arrows[{i}].shape_id"), x.shape_id));
+
+        for (path, shape_id) in outlines.chain(arrows) {
+            if let Some(id) = shape_id {
+                if !ids.contains_key(&id) {
+                    push(Some(stage_index), path, LintKind::MissingPinnedShape { id });
+                }
+            }
+        }
+
+        for (joint_index, joint) in stage.joints.iter().enumerate() {
+            let path = format!("{prefix}joints[{joint_index}]");
+            for (field, id) in [("a", joint.a), ("b", joint.b)] {
+                if !ids.contains_key(&id) {
+                    push(
+                        Some(stage_index),
+                        format!("{path}.{field}"),
+                        LintKind::UnknownJointId { id },
+                    );
+                }
+            }
+
+            if let Some(first) = joint_shapes.get(&joint.b) {
+                push(
+                    Some(stage_index),
+                    format!("{path}.b"),
+                    LintKind::DuplicateJointShape {
+                        id: joint.b,
+                        first: first.clone(),
+                    },
+                );
+            } else {
+                joint_shapes.insert(joint.b, path);
+            }
+        }
+    }
+
+    if let Some(stars) = level.stars {
+        if !stars.is_in_order() {
+            push(
+                None,
+                "stars".to_string(),
+                LintKind::StarsOutOfOrder {
+                    two: stars.two,
+                    three: stars.three,
+                },
+            );
+        }
+
+        // Converting to a shapes vec panics if there are unknown ids
+        if stars.measure.is_height() && !any_unknown_ids {
+            let max_height = ShapesVec::from(level).max_tower_height();
+            if stars.three > max_height {
+                push(
+                    None,
+                    "stars.three".to_string(),
+                    LintKind::StarsTooHigh {
+                        three: stars.three,
+                        max_height,
+                    },
+                );
+            }
+        }
+    }
+
+    diagnostics
+}
+
+fn stage_prefix(stage: usize) -> String {
+    match stage.checked_sub(1) {
+        Some(index) => format!("stages[{index}]."),
+        None => String::new(),
+    }
+}
+
+/// The shape, if it has a fixed position. Shapes without a position are placed randomly.
+fn placed_shape(creation: &ShapeCreation) -> Option<EncodableShape> {
+    let (x, y) = (creation.x?, creation.y?);
+    Some(EncodableShape {
+        shape: creation.shape.into(),
+        location: Location::new(x, y, creation.r.unwrap_or_default() * std::f32::consts::TAU),
+        state: creation.state,
+        modifiers: creation.modifiers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(x: f32, y: f32) -> ShapeCreation {
+        ShapeCreation {
+            shape: LevelShapeForm::O4,
+            x: Some(x),
+            y: Some(y),
+            state: ShapeState::Fixed,
+            ..Default::default()
+        }
+    }
+
+    fn kinds(level: &DesignedLevel) -> Vec<LintKind> {
+        lint_level(level, 0).into_iter().map(|x| x.kind).collect()
+    }
+
+    #[test]
+    pub fn test_bundled_levels_have_no_unknown_ids() {
+        let levels = CAMPAIGN_LEVELS
+            .iter()
+            .chain(TUTORIAL_LEVELS.iter())
+            .chain(CREDITS_LEVELS.iter())
+            .chain(AD_LEVELS.iter());
+
+        let errors: Vec<String> = lint_levels(levels)
+            .into_iter()
+            .filter(|x| {
+                matches!(
+                    x.kind,
+                    LintKind::UnknownUpdateId { .. }
+                        | LintKind::DuplicateId { .. }
+                        | LintKind::MissingPinnedShape { .. }
+                        | LintKind::UnknownJointId { .. }
+                        | LintKind::DuplicateJointShape { .. }
+                )
+            })
+            .map(|x| x.to_string())
+            .collect();
+
+        assert!(errors.is_empty(), "{}", errors.join("\n"));
+    }
+
+    #[test]
+    pub fn test_unknown_and_duplicate_ids() {
+        let mut level = DesignedLevel::default();
+        level.initial_stage.shapes = vec![
+            ShapeCreation {
+                id: Some(1),
+                ..Default::default()
+            },
+            ShapeCreation {
+                id: Some(1),
+                ..Default::default()
+            },
+        ];
+        level.stages = vec![LevelStage {
+            updates: vec![ShapeUpdate {
+                id: 2,
+                ..Default::default()
+            }],
+            arrows: vec![Arrow {
+                shape_id: Some(3),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }];
+
+        let diagnostics = lint_level(&level, 4);
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                LintDiagnostic {
+                    level: 4,
+                    stage: Some(0),
+                    path: "shapes[1].id".to_string(),
+                    kind: LintKind::DuplicateId {
+                        id: 1,
+                        first: "shapes[0]".to_string()
+                    }
+                },
+                LintDiagnostic {
+                    level: 4,
+                    stage: Some(1),
+                    path: "stages[0].updates[0].id".to_string(),
+                    kind: LintKind::UnknownUpdateId { id: 2 }
+                },
+                LintDiagnostic {
+                    level: 4,
+                    stage: Some(1),
+                    path: "stages[0].arrows[0].shape_id".to_string(),
+                    kind: LintKind::MissingPinnedShape { id: 3 }
+                },
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_joints() {
+        let mut level = DesignedLevel::default();
+        level.initial_stage.shapes = vec![
+            ShapeCreation {
+                id: Some(1),
+                ..Default::default()
+            },
+            ShapeCreation {
+                id: Some(2),
+                ..Default::default()
+            },
+        ];
+        level.initial_stage.joints = vec![
+            ShapeJoint {
+                a: 1,
+                b: 2,
+                ..Default::default()
+            },
+            ShapeJoint {
+                a: 3,
+                b: 2,
+                ..Default::default()
+            },
+        ];
+
+        let kinds = kinds(&level);
+
+        assert_eq!(
+            kinds,
+            vec![
+                LintKind::UnknownJointId { id: 3 },
+                LintKind::DuplicateJointShape {
+                    id: 2,
+                    first: "joints[0]".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_fixed_shapes() {
+        let mut level = DesignedLevel::default();
+        level.initial_stage.shapes = vec![fixed(0.0, 0.0), fixed(10.0, 0.0), fixed(5000.0, 0.0)];
+
+        let kinds = kinds(&level);
+
+        assert_eq!(kinds.len(), 2);
+        assert!(matches!(
+            kinds[0],
+            LintKind::OutOfBounds { x: 5000.0, y: 0.0 }
+        ));
+        assert!(matches!(kinds[1], LintKind::OverlappingFixedShapes { .. }));
+    }
+
+    #[test]
+    pub fn test_stars() {
+        let mut level = DesignedLevel::default();
+        level.initial_stage.shapes = vec![ShapeCreation {
+            shape: LevelShapeForm::O4,
+            ..Default::default()
+        }];
+        level.stars = Some(LevelStars {
+            two: 2000.0,
+            three: 1000.0,
+            measure: StarMeasure::Height,
+        });
+
+        let kinds = kinds(&level);
+
+        assert_eq!(kinds.len(), 2);
+        assert!(kinds[0].is_stars_out_of_order());
+        assert!(kinds[1].is_stars_too_high());
+    }
+
+    #[test]
+    pub fn test_time_stars() {
+        let mut level = DesignedLevel::default();
+        level.stars = Some(LevelStars {
+            two: 60.0,
+            three: 30.0,
+            measure: StarMeasure::Time,
+        });
+
+        assert!(kinds(&level).is_empty());
+
+        level.stars = Some(LevelStars {
+            two: 30.0,
+            three: 60.0,
+            measure: StarMeasure::Time,
+        });
+        let kinds = kinds(&level);
+
+        assert_eq!(kinds.len(), 1);
+        assert!(kinds[0].is_stars_out_of_order());
+    }
+}
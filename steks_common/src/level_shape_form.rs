@@ -58,6 +58,31 @@ pub enum LevelShapeForm {
     Y5 = 19,
     #[serde(alias = "s5")]
     S5 = 20,
+
+    #[serde(alias = "i2", alias = "Domino", alias = "domino")]
+    I2 = 21,
+    #[serde(alias = "i3")]
+    I3 = 22,
+    #[serde(alias = "l3")]
+    L3 = 23,
+    #[serde(alias = "o6")]
+    O6 = 24,
+    #[serde(alias = "l6")]
+    L6 = 25,
+    #[serde(alias = "t6")]
+    T6 = 26,
+    #[serde(alias = "x6")]
+    X6 = 27,
+    #[serde(alias = "isosceles", alias = "ISOSCELES")]
+    Isosceles = 28,
+    #[serde(alias = "obtuse", alias = "OBTUSE")]
+    Obtuse = 29,
+    #[serde(alias = "pentagon", alias = "PENTAGON")]
+    Pentagon = 30,
+    #[serde(alias = "hexagon", alias = "HEXAGON")]
+    Hexagon = 31,
+    #[serde(alias = "octagon", alias = "OCTAGON")]
+    Octagon = 32,
 }
 
 impl From<LevelShapeForm> for ShapeIndex {
@@ -90,3 +115,19 @@ impl LevelShapeForm {
         panic!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forms_match_shapes() {
+        assert_eq!(LevelShapeForm::COUNT, ALL_SHAPES.len());
+
+        for shape in ALL_SHAPES.iter() {
+            let form: LevelShapeForm = shape.into();
+            let name = serde_yaml::to_string(&form).unwrap();
+            assert_eq!(name.trim(), shape.name);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::prelude::*;
+
+/// The fraction of the shared tower's height needed for two stars
+const TWO_STAR_FRACTION: f32 = 0.75;
+
+pub fn levels_from_yaml(data: &str) -> anyhow::Result<Vec<DesignedLevel>> {
+    let levels: Vec<DesignedLevel> = serde_yaml::from_str(data)?;
+    Ok(levels)
+}
+
+/// Write levels the way they are written by hand, leaving out anything which is not set
+pub fn levels_to_yaml(levels: &[DesignedLevel]) -> anyhow::Result<String> {
+    let mut value = serde_yaml::to_value(levels)?;
+    remove_nulls(&mut value);
+    Ok(serde_yaml::to_string(&value)?)
+}
+
+fn remove_nulls(value: &mut serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::Sequence(sequence) => sequence.iter_mut().for_each(remove_nulls),
+        serde_yaml::Value::Mapping(mapping) => {
+            mapping.retain(|_, v| !v.is_null());
+            mapping.values_mut().for_each(remove_nulls);
+        }
+        _ => {}
+    }
+}
+
+impl DesignedLevel {
+    /// A level challenging the player to rebuild a shared tower.
+    /// Fixed and void shapes stay where they are and the other shapes are given to the player to place.
+    pub fn from_shared_shapes(shapes: &ShapesVec) -> Self {
+        let height = shapes.calculate_tower_height();
+
+        let shapes: Vec<ShapeCreation> = shapes
+            .iter()
+            .map(|shape| {
+                let creation = ShapeCreation::from(*shape);
+                if shape.state.is_fixed() || shape.state.is_void() {
+                    creation
+                } else {
+                    ShapeCreation {
+                        x: None,
+                        y: None,
+                        r: None,
+                        vel_x: None,
+                        vel_y: None,
+                        state: ShapeState::Normal,
+                        ..creation
+                    }
+                }
+            })
+            .collect();
+
+        let stars = (height > 0.0).then_some(LevelStars {
+            two: height * TWO_STAR_FRACTION,
+            three: height,
+            measure: StarMeasure::Height,
+        });
+
+        DesignedLevel {
+            title: Some("Shared Tower".to_string()),
+            initial_stage: LevelStage {
+                text: Some(format!("Can you build a tower {height:.2}m tall?")),
+                shapes,
+                ..Default::default()
+            },
+            stars,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for data in [
+            include_str!("levels.yaml"),
+            include_str!("tutorial_levels.yaml"),
+            include_str!("ad_levels.yaml"),
+            include_str!("credits.yaml"),
+        ] {
+            let levels = levels_from_yaml(data).unwrap();
+            let yaml = levels_to_yaml(&levels).unwrap();
+            let round_tripped = levels_from_yaml(&yaml).unwrap();
+
+            assert_eq!(levels, round_tripped);
+        }
+    }
+
+    #[test]
+    fn test_from_shared_shapes() {
+        let shape = |x: f32, y: f32, state: ShapeState| EncodableShape {
+            shape: ShapeIndex::from(LevelShapeForm::O4),
+            location: Location::new(x, y, 0.0),
+            state,
+            modifiers: ShapeModifiers::Normal,
+        };
+        let shapes = ShapesVec(vec![
+            shape(0.0, -100.0, ShapeState::Fixed),
+            shape(0.0, 0.0, ShapeState::Normal),
+            shape(0.0, 50.0, ShapeState::Locked),
+            shape(100.0, 0.0, ShapeState::Void),
+        ]);
+
+        let level = DesignedLevel::from_shared_shapes(&shapes);
+        let yaml = levels_to_yaml(&[level.clone()]).unwrap();
+        let imported = levels_from_yaml(&yaml).unwrap();
+        assert_eq!(imported, vec![level.clone()]);
+
+        let created = &level.initial_stage.shapes;
+        assert_eq!(created.len(), 4);
+        assert_eq!(created[0], ShapeCreation::from(shapes.0[0]));
+        assert_eq!(created[3], ShapeCreation::from(shapes.0[3]));
+
+        for creation in &created[1..3] {
+            assert_eq!(creation.state, ShapeState::Normal);
+            assert_eq!((creation.x, creation.y, creation.r), (None, None, None));
+        }
+
+        let stars = level.stars.unwrap();
+        assert_eq!(stars.three, shapes.calculate_tower_height());
+        assert!(stars.is_in_order());
+    }
+}
@@ -5,12 +5,21 @@ pub mod encodable_shape;
 pub mod game_shape;
 pub mod icon_button;
 pub mod level_completion;
+pub mod level_lint;
 pub mod level_shape_form;
+pub mod level_yaml;
 pub mod location;
+pub mod objectives;
+pub mod physics;
+pub mod rectangle_set;
+pub mod replay_input;
 pub mod shape_index;
 pub mod shape_state;
+pub mod share_code;
 pub mod shapes_vec;
 pub mod star_type;
+pub mod walls;
+pub mod water;
 
 pub mod prelude {
     pub use crate::color::*;
@@ -20,10 +29,19 @@ pub mod prelude {
     pub use crate::game_shape::*;
     pub use crate::icon_button::*;
     pub use crate::level_completion::*;
+    pub use crate::level_lint::*;
     pub use crate::level_shape_form::*;
+    pub use crate::level_yaml::*;
     pub use crate::location::*;
+    pub use crate::objectives::*;
+    pub use crate::physics::*;
+    pub use crate::rectangle_set::*;
+    pub use crate::replay_input::*;
     pub use crate::shape_index::*;
     pub use crate::shape_state::*;
+    pub use crate::share_code::*;
     pub use crate::shapes_vec::*;
     pub use crate::star_type::*;
+    pub use crate::walls::*;
+    pub use crate::water::*;
 }
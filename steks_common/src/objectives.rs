@@ -0,0 +1,105 @@
+use bevy::prelude::Vec2;
+use strum::EnumIs;
+
+use crate::prelude::*;
+
+/// Why a level can no longer be won without starting again
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIs)]
+pub enum ObjectiveFailure {
+    OutOfTime,
+    OutOfMoves,
+}
+
+impl ObjectiveFailure {
+    pub fn message(&self) -> &'static str {
+        match self {
+            ObjectiveFailure::OutOfTime => "Out of time",
+            ObjectiveFailure::OutOfMoves => "Out of moves",
+        }
+    }
+}
+
+impl LevelObjectives {
+    pub fn is_empty(&self) -> bool {
+        self.time_limit.is_none() && self.move_limit.is_none() && self.target.is_none()
+    }
+
+    /// The objective which has been failed, if any.
+    /// Picking up a shape after using every move fails the level.
+    pub fn check(&self, seconds: f32, moves: u32) -> Option<ObjectiveFailure> {
+        if self.time_limit.is_some_and(|limit| seconds > limit) {
+            Some(ObjectiveFailure::OutOfTime)
+        } else if self.move_limit.is_some_and(|limit| moves > limit) {
+            Some(ObjectiveFailure::OutOfMoves)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the tower satisfies the target, if there is one
+    pub fn is_target_reached(&self, shapes: &ShapesVec) -> bool {
+        self.target.map_or(true, |target| target.is_reached(shapes))
+    }
+}
+
+impl TargetOutline {
+    pub fn position(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    /// Whether any shape which the player can move touches the target
+    pub fn is_reached(&self, shapes: &ShapesVec) -> bool {
+        let center = self.position();
+        shapes
+            .0
+            .iter()
+            .filter(|x| x.state.is_normal() || x.state.is_locked())
+            .any(|x| {
+                let bb = x
+                    .shape
+                    .game_shape()
+                    .body
+                    .bounding_box(SHAPE_SIZE, &x.location);
+                let closest = center.clamp(bb.min, bb.max);
+                closest.distance(center) <= self.radius
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_objectives_check() {
+        let objectives = LevelObjectives {
+            time_limit: Some(30.0),
+            move_limit: Some(4),
+            target: None,
+        };
+
+        assert_eq!(objectives.check(29.0, 4), None);
+        assert_eq!(objectives.check(31.0, 0), Some(ObjectiveFailure::OutOfTime));
+        assert_eq!(objectives.check(0.0, 5), Some(ObjectiveFailure::OutOfMoves));
+        assert_eq!(LevelObjectives::default().check(1000.0, 1000), None);
+    }
+
+    #[test]
+    pub fn test_target_reached() {
+        let target = TargetOutline {
+            x: 0.0,
+            y: 200.0,
+            radius: 20.0,
+        };
+        let shape = |y: f32, state: ShapeState| EncodableShape {
+            shape: ShapeIndex::from(LevelShapeForm::O4),
+            location: Location::new(0.0, y, 0.0),
+            state,
+            modifiers: ShapeModifiers::Normal,
+        };
+
+        assert!(!target.is_reached(&ShapesVec(vec![shape(0.0, ShapeState::Normal)])));
+        assert!(target.is_reached(&ShapesVec(vec![shape(150.0, ShapeState::Normal)])));
+        assert!(!target.is_reached(&ShapesVec(vec![shape(150.0, ShapeState::Fixed)])));
+    }
+}
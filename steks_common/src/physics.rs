@@ -0,0 +1,55 @@
+use std::sync::atomic::{AtomicBool, AtomicI8, Ordering};
+
+use bevy::prelude::Vec2;
+use bevy_rapier2d::rapier::prelude as rapier;
+
+const POSITION_DAMPING: f32 = 1.0;
+const POSITION_STIFFNESS: f32 = 20.0;
+const MAX_FORCE: f32 = 800.0;
+
+/// The force pulling a dragged shape towards the pointer
+pub fn drag_force(distance: Vec2, velocity: Vec2) -> Vec2 {
+    let force = (distance * POSITION_STIFFNESS) - (velocity * POSITION_DAMPING);
+
+    if force.length() > 0.
+        && velocity.length() > 0.
+        && force.angle_between(velocity).abs() > std::f32::consts::FRAC_PI_2
+    {
+        force // force is in opposite direction to velocity so don't clamp it
+    } else {
+        force.clamp_length_max(MAX_FORCE)
+    }
+}
+
+/// Counts the collisions during a prediction
+#[derive(Default, Debug)]
+pub struct CollisionCounter {
+    pub sensor_collision_found: AtomicBool,
+    pub total_collisions_found: AtomicI8,
+}
+
+impl rapier::EventHandler for CollisionCounter {
+    fn handle_collision_event(
+        &self,
+        _bodies: &rapier::RigidBodySet,
+        _colliders: &rapier::ColliderSet,
+        event: rapier::CollisionEvent,
+        _contact_pair: Option<&rapier::ContactPair>,
+    ) {
+        if event.sensor() {
+            self.sensor_collision_found.store(true, Ordering::Relaxed);
+        }
+
+        self.total_collisions_found.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn handle_contact_force_event(
+        &self,
+        _dt: rapier::Real,
+        _bodies: &rapier::RigidBodySet,
+        _colliders: &rapier::ColliderSet,
+        _contact_pair: &rapier::ContactPair,
+        _total_force_magnitude: rapier::Real,
+    ) {
+    }
+}
@@ -1,10 +1,6 @@
-use bevy::{prelude::{Rect, Transform, Vec2}, log::warn};
-use itertools::Itertools;
-use steks_common::prelude::*;
+use bevy::{prelude::{Rect, Vec2}, log::warn};
 
-use nice_bevy_utils::window_size::WindowSize;
-
-use crate::prelude::SteksBreakpoints;
+use crate::prelude::*;
 
 pub struct RectangleSet {
     pub outer: Rect,
@@ -12,19 +8,16 @@ pub struct RectangleSet {
 }
 
 impl RectangleSet {
-    pub fn new(window: &WindowSize<SteksBreakpoints>, shapes: impl Iterator<Item = (ShapeIndex, Transform)>) -> Self {
-        let outer = Rect::from_center_size(
-            Vec2::ZERO,
-            Vec2::new(window.scaled_width, window.scaled_height),
-        );
+    pub fn new(
+        width: f32,
+        height: f32,
+        shapes: impl Iterator<Item = (ShapeIndex, Location)>,
+    ) -> Self {
+        let outer = Rect::from_center_size(Vec2::ZERO, Vec2::new(width, height));
 
         let existing = shapes
-            .map(|s| {
-                s.0.game_shape()
-                    .body
-                    .bounding_box(SHAPE_SIZE, &Location::from(&s.1))
-            })
-            .collect_vec();
+            .map(|s| s.0.game_shape().body.bounding_box(SHAPE_SIZE, &s.1))
+            .collect();
 
         Self { outer, existing }
     }
@@ -96,16 +89,24 @@ impl RectangleSet {
     }
 }
 
+/// The velocity given to shapes placed at random
+pub fn random_velocity(rng: &mut impl rand::Rng) -> (Vec2, f32) {
+    let linvel = Vec2 {
+        x: rng.gen_range(-200.0..200.0),
+        y: rng.gen_range(0.0..200.0),
+    };
+    let angvel = rng.gen_range(0.0..std::f32::consts::TAU);
+    (linvel, angvel)
+}
+
 #[cfg(test)]
 mod tests {
     use rand::rngs::StdRng;
     use rand::SeedableRng;
-    use steks_common::prelude::ALL_SHAPES;
     use test_case::test_case;
 
-    use nice_bevy_utils::window_size::WindowSize;
-
     use super::RectangleSet;
+    use crate::prelude::ALL_SHAPES;
 
     #[test_case(123)]
     #[test_case(456)]
@@ -113,9 +114,7 @@ mod tests {
     pub fn test_shape_placement(seed: u64) {
         let mut rng = StdRng::seed_from_u64(seed);
 
-        let window_size = WindowSize::new(360., 520.);
-
-        let mut set = RectangleSet::new(&window_size, std::iter::empty());
+        let mut set = RectangleSet::new(360., 520., std::iter::empty());
 
         let mut max_tries: usize = 0;
 
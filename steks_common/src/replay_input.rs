@@ -0,0 +1,32 @@
+use bevy::prelude::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// A player action, mirroring the drag and rotate events in `steks_base`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ReplayInput {
+    /// Pick up the shape at this position, like a `DragStartEvent`
+    PickUp { position: Vec2 },
+    /// Move the held shape towards this position, like a `DragMoveEvent`
+    DragTo { position: Vec2 },
+    /// Rotate the held shape, like a `RotateEvent`
+    Rotate {
+        delta: f32,
+        snap_resolution: Option<f32>,
+    },
+    /// Let go of the held shape, like a `DragEndingEvent`
+    Drop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimedInput {
+    /// The physics frame on which the input happens.
+    /// There are `FRAMES_PER_SECOND` frames per second.
+    pub frame: u32,
+    pub input: ReplayInput,
+}
+
+impl TimedInput {
+    pub fn new(frame: u32, input: ReplayInput) -> Self {
+        Self { frame, input }
+    }
+}
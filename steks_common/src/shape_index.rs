@@ -1,4 +1,7 @@
-use crate::{game_shape::ALL_SHAPES, prelude::GameShape};
+use crate::{
+    game_shape::{ALL_SHAPES, CLASSIC_SHAPE_COUNT},
+    prelude::{GameShape, CHALLENGE_SHAPES},
+};
 use bevy::prelude::Component;
 use rand::{rngs::StdRng, Rng};
 use serde::{Serialize, Deserialize};
@@ -17,8 +20,18 @@ impl ShapeIndex {
         Self::random_no_circle(&mut shape_rng)
     }
 
+    /// Only classic shapes are chosen so that seeds keep producing the same shapes
     pub fn random_no_circle(rng: &mut impl Rng) -> Self {
-        ShapeIndex(rng.gen_range(1..Self::exclusive_max().0))
+        ShapeIndex(rng.gen_range(1..*CLASSIC_SHAPE_COUNT as u8))
+    }
+
+    /// The shapes for the daily challenge on this date
+    pub fn challenge_shapes(year: u32, month: u32, day: u32) -> Vec<Self> {
+        let seed = ((year * 2000) + (month * 100) + day) as u64;
+        let mut shape_rng: StdRng = rand::SeedableRng::seed_from_u64(seed);
+        (0..CHALLENGE_SHAPES)
+            .map(|_| Self::random_no_circle(&mut shape_rng))
+            .collect()
     }
 
     pub fn game_shape(&self)-> &'static GameShape{
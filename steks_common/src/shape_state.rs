@@ -1,4 +1,4 @@
-use bevy::prelude::Color;
+use bevy::prelude::{Color, Component};
 use bevy_prototype_lyon::prelude::*;
 use bevy_rapier2d::prelude::*;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
@@ -39,6 +39,7 @@ pub enum ShapeState {
     Void = 3,
 }
 
+/// Modifiers are encoded in four bits so there can be at most sixteen of them
 #[derive(
     Debug,
     Clone,
@@ -52,6 +53,8 @@ pub enum ShapeState {
     TryFromPrimitive,
     Ord,
     PartialOrd,
+    EnumIter,
+    Component,
 )]
 #[repr(u8)]
 pub enum ShapeModifiers {
@@ -60,34 +63,84 @@ pub enum ShapeModifiers {
     Normal = 0,
     #[serde(alias = "ice")]
     Ice = 1,
+    #[serde(alias = "bouncy")]
+    Bouncy = 2,
+    #[serde(alias = "heavy")]
+    Heavy = 3,
+    #[serde(alias = "sticky")]
+    Sticky = 4,
 }
 
 impl ShapeModifiers {
     pub fn friction(&self) -> Friction {
-        let coefficient = match self {
-            ShapeModifiers::Normal => DEFAULT_FRICTION,
-            ShapeModifiers::Ice => LOW_FRICTION,
+        use bevy_rapier2d::prelude::CoefficientCombineRule;
+        let (coefficient, combine_rule) = match self {
+            ShapeModifiers::Ice => (LOW_FRICTION, CoefficientCombineRule::Min),
+            // Max so that sticky shapes also grip shapes which are not sticky
+            ShapeModifiers::Sticky => (HIGH_FRICTION, CoefficientCombineRule::Max),
+            ShapeModifiers::Normal | ShapeModifiers::Bouncy | ShapeModifiers::Heavy => {
+                (DEFAULT_FRICTION, CoefficientCombineRule::Min)
+            }
         };
 
         Friction {
             coefficient,
-            combine_rule: bevy_rapier2d::prelude::CoefficientCombineRule::Min,
+            combine_rule,
         }
     }
 
-    pub fn stroke(&self, high_contrast: bool) -> Option<Stroke> {
+    pub fn restitution(&self) -> Restitution {
+        use bevy_rapier2d::prelude::CoefficientCombineRule;
+        match self {
+            ShapeModifiers::Bouncy => Restitution {
+                coefficient: BOUNCY_RESTITUTION,
+                combine_rule: CoefficientCombineRule::Max,
+            },
+            _ => Restitution {
+                coefficient: DEFAULT_RESTITUTION,
+                combine_rule: CoefficientCombineRule::Min,
+            },
+        }
+    }
+
+    pub fn density(&self) -> f32 {
+        match self {
+            ShapeModifiers::Heavy => HEAVY_DENSITY,
+            _ => DEFAULT_DENSITY,
+        }
+    }
+
+    pub fn stroke_color(&self, high_contrast: bool) -> Option<Color> {
+        let (color, high_contrast_color) = match self {
+            ShapeModifiers::Normal => return None,
+            ShapeModifiers::Ice => (ICE_SHAPE_STROKE, ICE_SHAPE_STROKE_HIGH_CONTRAST),
+            ShapeModifiers::Bouncy => (BOUNCY_SHAPE_STROKE, BOUNCY_SHAPE_STROKE_HIGH_CONTRAST),
+            ShapeModifiers::Heavy => (HEAVY_SHAPE_STROKE, HEAVY_SHAPE_STROKE_HIGH_CONTRAST),
+            ShapeModifiers::Sticky => (STICKY_SHAPE_STROKE, STICKY_SHAPE_STROKE_HIGH_CONTRAST),
+        };
+
+        Some(if high_contrast {
+            high_contrast_color
+        } else {
+            color
+        })
+    }
+
+    pub fn stroke_width(&self) -> f32 {
         match self {
-            ShapeModifiers::Normal => None,
-            ShapeModifiers::Ice => Some(Stroke {
-                color: if high_contrast {
-                    ICE_SHAPE_STROKE_HIGH_CONTRAST
-                } else {
-                    ICE_SHAPE_STROKE
-                },
-                options: StrokeOptions::default().with_line_width(ICE_STROKE_WIDTH),
-            }),
+            ShapeModifiers::Normal | ShapeModifiers::Ice => ICE_STROKE_WIDTH,
+            ShapeModifiers::Bouncy | ShapeModifiers::Heavy | ShapeModifiers::Sticky => {
+                MODIFIER_STROKE_WIDTH
+            }
         }
     }
+
+    pub fn stroke(&self, high_contrast: bool) -> Option<Stroke> {
+        self.stroke_color(high_contrast).map(|color| Stroke {
+            color,
+            options: StrokeOptions::default().with_line_width(self.stroke_width()),
+        })
+    }
 }
 
 impl ShapeState {
@@ -3,7 +3,7 @@ use std::ops::Deref;
 use crate::prelude::*;
 use base64::Engine;
 use serde::{Deserialize, Serialize};
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 pub struct ShapesVec(pub Vec<EncodableShape>);
 
 impl Deref for ShapesVec {
@@ -15,8 +15,15 @@ impl Deref for ShapesVec {
 }
 
 impl ShapesVec {
-    pub fn from_bytes(data: &[u8]) -> Self {
-        Self(data.chunks_exact(7).map(EncodableShape::decode).collect())
+    /// Decode shapes from a share code.
+    /// Accepts both versioned share codes and the headerless codes made by older versions.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ShareCodeError> {
+        let payload = unwrap_share_code(data)?;
+        let shapes = payload
+            .chunks_exact(ENCODED_SHAPE_LENGTH)
+            .map(EncodableShape::decode)
+            .collect::<Result<_, _>>()?;
+        Ok(Self(shapes))
     }
 
     pub fn hash(&self) -> u64 {
@@ -62,6 +69,12 @@ impl ShapesVec {
     }
 
     pub fn calculate_tower_height(&self) -> f32 {
+        self.calculate_tower_height_above(None)
+    }
+
+    /// The height of the tower above the water line.
+    /// Without a water line the tower is measured from its lowest shape.
+    pub fn calculate_tower_height_above(&self, water_line: Option<f32>) -> f32 {
         let mut min = MAX_WINDOW_HEIGHT;
         let mut max = -MAX_WINDOW_HEIGHT;
 
@@ -84,18 +97,72 @@ impl ShapesVec {
             max = max.max(bb.max.y);
         }
 
+        let min = water_line.unwrap_or(min);
+
         //info!("Calculated height min {min:.2} max {max:.2} height {height:.2}");
         (max - min).max(0.0) * HEIGHT_MULTIPLIER
     }
 
+    /// Find the first pair of shapes which overlap by more than the tolerance.
+    /// Void shapes and pairs of fixed shapes are ignored.
+    pub fn find_overlapping_shapes(&self, tolerance: f32) -> Option<(usize, usize, f32)> {
+        for (i, a) in self.0.iter().enumerate() {
+            if a.state.is_void() {
+                continue;
+            }
+            for (j, b) in self.0.iter().enumerate().skip(i + 1) {
+                if b.state.is_void() || (a.state.is_fixed() && b.state.is_fixed()) {
+                    continue;
+                }
+                if let Some(depth) = a.overlap_depth(b) {
+                    if depth > tolerance {
+                        return Some((i, j, depth));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Encode the shapes as a versioned share code
     pub fn make_bytes(&self) -> Vec<u8> {
-        let bytes: Vec<u8> = self.0.iter().flat_map(|shape| shape.encode()).collect();
-        bytes
+        let payload: Vec<u8> = self.0.iter().flat_map(|shape| shape.encode()).collect();
+        wrap_share_code(&payload)
     }
 
     pub fn make_base64_data(&self) -> String {
         base64::engine::general_purpose::URL_SAFE.encode(self.make_bytes())
     }
+
+    /// The hash challenge records were kept under before they were keyed by date
+    pub fn challenge_hash(year: u32, month: u32, day: u32) -> u64 {
+        let shapes = ShapeIndex::challenge_shapes(year, month, day)
+            .into_iter()
+            .map(|shape| EncodableShape {
+                shape,
+                location: Location::default(),
+                state: ShapeState::Normal,
+                modifiers: ShapeModifiers::Normal,
+            })
+            .collect();
+
+        Self(shapes).hash()
+    }
+
+    /// Whether these are exactly the shapes of the daily challenge on this date
+    pub fn is_challenge(&self, year: u32, month: u32, day: u32) -> bool {
+        let mut expected = ShapeIndex::challenge_shapes(year, month, day);
+        expected.sort();
+
+        let mut actual: Vec<ShapeIndex> = self.iter().map(|x| x.shape).collect();
+        actual.sort();
+
+        actual == expected
+            && self.iter().all(|x| {
+                matches!(x.state, ShapeState::Normal | ShapeState::Locked)
+                    && x.modifiers == ShapeModifiers::Normal
+            })
+    }
 }
 
 impl From<&DesignedLevel> for ShapesVec {
@@ -147,6 +214,7 @@ impl From<&DesignedLevel> for ShapesVec {
 mod tests {
     use base64::Engine;
     use super::ShapesVec;
+    use crate::prelude::{share_code_checksum, SHARE_CODE_MAGIC};
 
     #[test]
     pub fn test_calculate_height_consistency() {
@@ -154,13 +222,135 @@ mod tests {
         //spellchecker:disable-next-line
         .decode("CQCBGHvzog4AfvB2ZysEAILnjfABCACDxpPAAhMAfnVw1uAFAHw-bLbnAgCCyZ2xPgwAhV6IVCoJAIOygSikCzCOqo__PAMgeVRpVOkCIIv_i1Q8").unwrap();
 
-        let vec = ShapesVec::from_bytes(blob.as_slice());
+        let vec = ShapesVec::from_bytes(blob.as_slice()).unwrap();
         let height_1 = vec.calculate_tower_height();
 
-        let round_tripped = ShapesVec::from_bytes(vec.make_bytes().as_slice());
+        let round_tripped = ShapesVec::from_bytes(vec.make_bytes().as_slice()).unwrap();
 
         let height_2 = round_tripped.calculate_tower_height();
 
         assert_eq!(height_1, height_2);
     }
+
+    #[test]
+    pub fn test_height_above_water_line() {
+        let blob =  base64::engine::general_purpose::URL_SAFE
+        //spellchecker:disable-next-line
+        .decode("CQCBGHvzog4AfvB2ZysEAILnjfABCACDxpPAAhMAfnVw1uAFAHw-bLbnAgCCyZ2xPgwAhV6IVCoJAIOygSikCzCOqo__PAMgeVRpVOkCIIv_i1Q8").unwrap();
+        let vec = ShapesVec::from_bytes(blob.as_slice()).unwrap();
+
+        let height = vec.calculate_tower_height();
+        let above_low_water = vec.calculate_tower_height_above(Some(-10000.0));
+        let above_high_water = vec.calculate_tower_height_above(Some(10000.0));
+
+        assert!(above_low_water > height);
+        assert_eq!(above_high_water, 0.0);
+    }
+
+    #[test]
+    pub fn test_legacy_share_code_round_trip() {
+        let blob =  base64::engine::general_purpose::URL_SAFE
+        //spellchecker:disable-next-line
+        .decode("CQCBGHvzog4AfvB2ZysEAILnjfABCACDxpPAAhMAfnVw1uAFAHw-bLbnAgCCyZ2xPgwAhV6IVCoJAIOygSikCzCOqo__PAMgeVRpVOkCIIv_i1Q8").unwrap();
+
+        let legacy = ShapesVec::from_bytes(blob.as_slice()).unwrap();
+        let versioned_bytes = legacy.make_bytes();
+
+        assert_eq!(versioned_bytes[0], SHARE_CODE_MAGIC);
+        assert_eq!(ShapesVec::from_bytes(&versioned_bytes), Ok(legacy));
+    }
+
+    #[test]
+    pub fn test_version_1_share_code() {
+        let blob =  base64::engine::general_purpose::URL_SAFE
+        //spellchecker:disable-next-line
+        .decode("CQCBGHvzog4AfvB2ZysEAILnjfABCACDxpPAAhMAfnVw1uAFAHw-bLbnAgCCyZ2xPgwAhV6IVCoJAIOygSikCzCOqo__PAMgeVRpVOkCIIv_i1Q8").unwrap();
+        let legacy = ShapesVec::from_bytes(blob.as_slice()).unwrap();
+
+        let mut version_1 = vec![SHARE_CODE_MAGIC, 1];
+        version_1.extend_from_slice(&blob);
+        let checksum = share_code_checksum(&version_1[1..]);
+        version_1.extend_from_slice(&checksum.to_be_bytes());
+
+        assert_eq!(ShapesVec::from_bytes(&version_1), Ok(legacy));
+    }
+
+    #[test]
+    pub fn test_overlapping_shapes() {
+        use crate::prelude::*;
+        let shape = GameShape::by_name("O4").unwrap().index;
+        let make = |x: f32| EncodableShape {
+            shape,
+            location: Location::new(x, 0.0, 0.0),
+            state: ShapeState::Normal,
+            modifiers: ShapeModifiers::Normal,
+        };
+
+        let apart = ShapesVec(vec![make(0.0), make(SHAPE_SIZE * 3.0)]);
+        assert_eq!(apart.find_overlapping_shapes(1.0), None);
+
+        let overlapping = ShapesVec(vec![make(0.0), make(SHAPE_SIZE * 0.5)]);
+        assert!(overlapping.find_overlapping_shapes(1.0).is_some());
+    }
+
+    #[test]
+    pub fn test_hash_depends_on_modifiers() {
+        use crate::prelude::*;
+        use strum::IntoEnumIterator;
+        let shape = GameShape::by_name("O4").unwrap().index;
+
+        let hashes: std::collections::BTreeSet<u64> = ShapeModifiers::iter()
+            .map(|modifiers| {
+                ShapesVec(vec![EncodableShape {
+                    shape,
+                    location: Location::new(0.0, 0.0, 0.0),
+                    state: ShapeState::Normal,
+                    modifiers,
+                }])
+                .hash()
+            })
+            .collect();
+
+        assert_eq!(hashes.len(), ShapeModifiers::iter().count());
+    }
+
+    #[test]
+    pub fn test_is_challenge() {
+        use crate::prelude::*;
+
+        let mut shapes = ShapesVec(
+            ShapeIndex::challenge_shapes(2023, 12, 25)
+                .into_iter()
+                .rev()
+                .enumerate()
+                .map(|(index, shape)| EncodableShape {
+                    shape,
+                    location: Location::new(0.0, index as f32 * SHAPE_SIZE, 0.0),
+                    state: ShapeState::Normal,
+                    modifiers: ShapeModifiers::Normal,
+                })
+                .collect(),
+        );
+
+        assert!(shapes.is_challenge(2023, 12, 25));
+        assert_eq!(shapes.hash(), ShapesVec::challenge_hash(2023, 12, 25));
+
+        shapes.0[0].modifiers = ShapeModifiers::Ice;
+        assert!(!shapes.is_challenge(2023, 12, 25));
+
+        shapes.0.pop();
+        assert!(!shapes.is_challenge(2023, 12, 25));
+    }
+
+    #[test]
+    pub fn test_truncated_share_code() {
+        let blob =  base64::engine::general_purpose::URL_SAFE
+        //spellchecker:disable-next-line
+        .decode("CQCBGHvzog4AfvB2ZysEAILnjfABCACDxpPAAhMAfnVw1uAFAHw-bLbnAgCCyZ2xPgwAhV6IVCoJAIOygSikCzCOqo__PAMgeVRpVOkCIIv_i1Q8").unwrap();
+        let versioned_bytes = ShapesVec::from_bytes(&blob).unwrap().make_bytes();
+
+        let result = ShapesVec::from_bytes(&versioned_bytes[..versioned_bytes.len() - 3]);
+
+        assert!(result.is_err());
+    }
 }
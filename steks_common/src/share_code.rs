@@ -0,0 +1,174 @@
+use std::fmt::Display;
+
+/// First byte of every versioned share code.
+/// Headerless codes always start with a shape index so they can never begin with this byte.
+pub const SHARE_CODE_MAGIC: u8 = 0xF5;
+/// Increment this whenever the shape table or the shape encoding changes.
+/// Version 2 added the bouncy, heavy and sticky modifiers and the shape families after the classic shapes.
+/// Older versions are a subset of the current encoding so they decode unchanged.
+pub const SHARE_CODE_VERSION: u8 = 2;
+
+/// Length of the magic byte and version byte
+pub const SHARE_CODE_HEADER_LENGTH: usize = 2;
+/// Length of the trailing checksum
+pub const SHARE_CODE_CHECKSUM_LENGTH: usize = 2;
+
+pub const ENCODED_SHAPE_LENGTH: usize = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareCodeError {
+    /// The data ended part way through the header, a shape or the checksum
+    Truncated { length: usize },
+    /// The checksum did not match the data
+    Corrupt { expected: u16, actual: u16 },
+    /// The data was made by a newer version of the game
+    FutureVersion { version: u8 },
+    UnknownShape { index: u8 },
+    UnknownState { state: u8 },
+    UnknownModifiers { modifiers: u8 },
+}
+
+impl Display for ShareCodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareCodeError::Truncated { length } => {
+                write!(f, "Share code is truncated ({length} bytes)")
+            }
+            ShareCodeError::Corrupt { expected, actual } => write!(
+                f,
+                "Share code is corrupt (checksum {actual:#06x}, expected {expected:#06x})"
+            ),
+            ShareCodeError::FutureVersion { version } => write!(
+                f,
+                "Share code version {version} is not supported - please update the game"
+            ),
+            ShareCodeError::UnknownShape { index } => write!(f, "Unknown shape {index}"),
+            ShareCodeError::UnknownState { state } => write!(f, "Unknown shape state {state}"),
+            ShareCodeError::UnknownModifiers { modifiers } => {
+                write!(f, "Unknown shape modifiers {modifiers}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShareCodeError {}
+
+/// Fletcher-16 checksum
+pub fn share_code_checksum(data: &[u8]) -> u16 {
+    let mut sum1: u16 = 0;
+    let mut sum2: u16 = 0;
+
+    for byte in data {
+        sum1 = (sum1 + *byte as u16) % 255;
+        sum2 = (sum2 + sum1) % 255;
+    }
+
+    (sum2 << 8) | sum1
+}
+
+/// Wrap an encoded shapes payload in the versioned envelope
+pub fn wrap_share_code(payload: &[u8]) -> Vec<u8> {
+    let mut bytes =
+        Vec::with_capacity(SHARE_CODE_HEADER_LENGTH + payload.len() + SHARE_CODE_CHECKSUM_LENGTH);
+    bytes.push(SHARE_CODE_MAGIC);
+    bytes.push(SHARE_CODE_VERSION);
+    bytes.extend_from_slice(payload);
+
+    let checksum = share_code_checksum(&bytes[1..]);
+    bytes.extend_from_slice(&checksum.to_be_bytes());
+    bytes
+}
+
+/// Get the shapes payload out of the data.
+/// Headerless data from older versions of the game is returned unchanged.
+pub fn unwrap_share_code(data: &[u8]) -> Result<&[u8], ShareCodeError> {
+    let payload = match data.first() {
+        Some(&SHARE_CODE_MAGIC) => {
+            if data.len() < SHARE_CODE_HEADER_LENGTH + SHARE_CODE_CHECKSUM_LENGTH {
+                return Err(ShareCodeError::Truncated { length: data.len() });
+            }
+
+            let version = data[1];
+            if version > SHARE_CODE_VERSION {
+                return Err(ShareCodeError::FutureVersion { version });
+            }
+
+            let (body, checksum) = data.split_at(data.len() - SHARE_CODE_CHECKSUM_LENGTH);
+            let expected = u16::from_be_bytes([checksum[0], checksum[1]]);
+            let actual = share_code_checksum(&body[1..]);
+
+            if expected != actual {
+                return Err(ShareCodeError::Corrupt { expected, actual });
+            }
+
+            &body[SHARE_CODE_HEADER_LENGTH..]
+        }
+        _ => data,
+    };
+
+    if payload.len() % ENCODED_SHAPE_LENGTH != 0 {
+        return Err(ShareCodeError::Truncated { length: data.len() });
+    }
+
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_round_trip() {
+        let payload = [9, 0, 129, 24, 123, 243, 162];
+        let wrapped = wrap_share_code(&payload);
+
+        assert_eq!(wrapped[0], SHARE_CODE_MAGIC);
+        assert_eq!(unwrap_share_code(&wrapped), Ok(payload.as_slice()));
+    }
+
+    #[test]
+    fn test_headerless_data() {
+        let payload = [9, 0, 129, 24, 123, 243, 162];
+        assert_eq!(unwrap_share_code(&payload), Ok(payload.as_slice()));
+        assert_eq!(unwrap_share_code(&[]), Ok(&[] as &[u8]));
+        assert_eq!(
+            unwrap_share_code(&payload[..5]),
+            Err(ShareCodeError::Truncated { length: 5 })
+        );
+    }
+
+    #[test]
+    fn test_corrupt_data() {
+        let mut wrapped = wrap_share_code(&[9, 0, 129, 24, 123, 243, 162]);
+        wrapped[4] ^= 1;
+
+        assert!(matches!(
+            unwrap_share_code(&wrapped),
+            Err(ShareCodeError::Corrupt { .. })
+        ));
+    }
+
+    #[test]
+    fn test_version_1() {
+        let payload = [9, 0, 129, 24, 123, 243, 162];
+        let mut wrapped = vec![SHARE_CODE_MAGIC, 1];
+        wrapped.extend_from_slice(&payload);
+        let checksum = share_code_checksum(&wrapped[1..]);
+        wrapped.extend_from_slice(&checksum.to_be_bytes());
+
+        assert_eq!(unwrap_share_code(&wrapped), Ok(payload.as_slice()));
+    }
+
+    #[test]
+    fn test_future_version() {
+        let mut wrapped = wrap_share_code(&[]);
+        wrapped[1] = SHARE_CODE_VERSION + 1;
+
+        assert_eq!(
+            unwrap_share_code(&wrapped),
+            Err(ShareCodeError::FutureVersion {
+                version: SHARE_CODE_VERSION + 1
+            })
+        );
+    }
+}
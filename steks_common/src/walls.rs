@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use strum::{Display, EnumIs, EnumIter};
+
+use crate::prelude::*;
+
+pub const TOP_BOTTOM_OFFSET: f32 = 10.0;
+pub const TOP_LEFT_SQUARE_SIZE: f32 = 60.0;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, EnumIter, Display, Hash)]
+pub enum MarkerType {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Component, PartialEq, Eq, Clone, Copy, Debug, EnumIter, Display, EnumIs)]
+pub enum WallPosition {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+}
+
+impl WallPosition {
+    /// The centre of the wall.
+    /// The offsets move the top and bottom walls in to leave room for the edges of the screen
+    pub fn get_center(&self, height: f32, width: f32, top_offset: f32, bottom_offset: f32) -> Vec2 {
+        use WallPosition::*;
+        const OFFSET: f32 = WALL_WIDTH / 2.0;
+
+        match self {
+            Top => Vec2::new(0.0, height / 2.0 + OFFSET + top_offset),
+            Bottom => Vec2::new(0.0, -height / 2.0 - OFFSET + bottom_offset),
+            Left => Vec2::new(-width / 2.0 - OFFSET, 0.0),
+            Right => Vec2::new(width / 2.0 + OFFSET, 0.0),
+            TopLeft => Vec2::new(
+                (-width / 2.0) + (TOP_LEFT_SQUARE_SIZE / 2.0),
+                (height / 2.0) - (TOP_LEFT_SQUARE_SIZE / 2.0),
+            ),
+        }
+    }
+
+    pub fn get_extents(&self) -> Vec2 {
+        const EXTRA_WIDTH: f32 = WALL_WIDTH * 2.0;
+        use WallPosition::*;
+
+        match self {
+            Top | Bottom => Vec2 {
+                x: MAX_WINDOW_WIDTH + EXTRA_WIDTH,
+                y: WALL_WIDTH,
+            },
+            Left | Right => Vec2 {
+                x: WALL_WIDTH,
+                y: MAX_WINDOW_HEIGHT,
+            },
+            TopLeft => Vec2 {
+                x: TOP_LEFT_SQUARE_SIZE,
+                y: TOP_LEFT_SQUARE_SIZE,
+            },
+        }
+    }
+
+    pub fn marker_type(&self) -> MarkerType {
+        use WallPosition::*;
+        match self {
+            Top | Bottom => MarkerType::Horizontal,
+            Left | Right => MarkerType::Vertical,
+            TopLeft => MarkerType::Horizontal,
+        }
+    }
+}
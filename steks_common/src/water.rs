@@ -0,0 +1,147 @@
+use bevy::prelude::Vec2;
+
+use crate::prelude::*;
+
+/// A force and torque in pixel units, like bevy_rapier's `ExternalForce`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct WaterForce {
+    pub force: Vec2,
+    pub torque: f32,
+}
+
+/// The part of a shape which is under the water
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Submerged {
+    /// The area in square pixels
+    pub area: f32,
+    /// The center of the submerged part, relative to the center of the shape
+    pub centroid: Vec2,
+}
+
+impl WaterSettings {
+    pub fn density(&self) -> f32 {
+        self.density.unwrap_or(WATER_DENSITY)
+    }
+
+    pub fn drag(&self) -> f32 {
+        self.drag.unwrap_or(WATER_DRAG)
+    }
+
+    /// The buoyancy and drag on a shape with these vertices
+    pub fn force_on(
+        &self,
+        vertices: &[Vec2],
+        location: &Location,
+        linvel: Vec2,
+        angvel: f32,
+        gravity: Vec2,
+    ) -> WaterForce {
+        let Some(submerged) = submerged_part(vertices, location, self.surface) else {
+            return WaterForce::default();
+        };
+
+        // The mass of the water the shape has pushed aside, in physics units
+        let displaced_mass = self.density() * submerged.area / (PHYSICS_SCALE * PHYSICS_SCALE);
+        let drag = self.drag() * displaced_mass;
+
+        let buoyancy = gravity * -displaced_mass;
+        let force = buoyancy - (linvel * drag);
+        // Buoyancy pushes on the submerged part so shapes tip over until they float flat
+        let torque = submerged.centroid.perp_dot(buoyancy)
+            - (angvel * drag * SHAPE_SIZE * SHAPE_SIZE * 0.25);
+
+        WaterForce { force, torque }
+    }
+}
+
+/// The part of a polygon below the surface.
+/// The vertices are relative to the center of the shape, as from `GameShapeBody::get_vertices`.
+pub fn submerged_part(vertices: &[Vec2], location: &Location, surface: f32) -> Option<Submerged> {
+    let rotation = Vec2::from_angle(location.angle);
+    let surface = surface - location.position.y;
+    let rotated: Vec<Vec2> = vertices.iter().map(|v| rotation.rotate(*v)).collect();
+
+    let mut clipped: Vec<Vec2> = Vec::with_capacity(rotated.len() + 2);
+    for (index, current) in rotated.iter().enumerate() {
+        let next = rotated[(index + 1) % rotated.len()];
+        let current_below = current.y <= surface;
+        let next_below = next.y <= surface;
+
+        if current_below {
+            clipped.push(*current);
+        }
+        if current_below != next_below {
+            let t = (surface - current.y) / (next.y - current.y);
+            clipped.push(current.lerp(next, t));
+        }
+    }
+
+    if clipped.len() < 3 {
+        return None;
+    }
+
+    // Shoelace formula, which works for either winding
+    let mut double_area = 0.0;
+    let mut centroid = Vec2::ZERO;
+    for (index, a) in clipped.iter().enumerate() {
+        let b = clipped[(index + 1) % clipped.len()];
+        let cross = a.perp_dot(b);
+        double_area += cross;
+        centroid += (*a + b) * cross;
+    }
+
+    if double_area.abs() < f32::EPSILON {
+        return None;
+    }
+
+    Some(Submerged {
+        area: (double_area * 0.5).abs(),
+        centroid: centroid / (3.0 * double_area),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQUARE: [Vec2; 4] = [
+        Vec2::new(-10.0, -10.0),
+        Vec2::new(10.0, -10.0),
+        Vec2::new(10.0, 10.0),
+        Vec2::new(-10.0, 10.0),
+    ];
+
+    #[test]
+    pub fn test_submerged_part() {
+        let location = Location::new(0.0, 100.0, 0.0);
+
+        let full = submerged_part(&SQUARE, &location, 200.0).unwrap();
+        assert!((full.area - 400.0).abs() < 0.01);
+        assert!(full.centroid.length() < 0.01);
+
+        let half = submerged_part(&SQUARE, &location, 100.0).unwrap();
+        assert!((half.area - 200.0).abs() < 0.01);
+        assert!(half.centroid.distance(Vec2::new(0.0, -5.0)) < 0.01);
+
+        assert_eq!(submerged_part(&SQUARE, &location, 0.0), None);
+    }
+
+    #[test]
+    pub fn test_light_shapes_float() {
+        let water = WaterSettings {
+            surface: 0.0,
+            ..Default::default()
+        };
+        let force = water.force_on(
+            &SQUARE,
+            &Location::new(0.0, -50.0, 0.0),
+            Vec2::ZERO,
+            0.0,
+            GRAVITY,
+        );
+
+        assert!(force.force.y > 0.0);
+        assert_eq!(force.force.x, 0.0);
+        assert!(force.torque.abs() < 0.01);
+    }
+}
@@ -0,0 +1,112 @@
+use resvg::tiny_skia::Pixmap;
+use steks_common::prelude::*;
+
+use crate::prelude::*;
+
+/// How long each shape is shown before the next one appears
+pub const FRAME_DELAY_MILLIS: u16 = 300;
+/// How long the finished tower is shown before the animation loops
+pub const FINAL_FRAME_DELAY_MILLIS: u16 = 2000;
+/// Animations are scaled down so that neither side is longer than this
+pub const MAX_ANIMATION_SIZE: u32 = 1024;
+/// Towers with more shapes than this add several shapes in each frame
+pub const MAX_ANIMATION_FRAMES: usize = 60;
+
+/// Draw the tower being built one shape at a time, in the order of the shapes vec.
+/// Every frame is framed to fit the finished tower so the shapes do not jump around.
+/// The frames are at most `MAX_ANIMATION_SIZE` and there are at most `MAX_ANIMATION_FRAMES` of them.
+pub fn try_draw_frames<Arg: Copy>(
+    bytes: &[u8],
+    overlay_chooser: &OverlayChooser<Arg>,
+    dimensions: Dimensions,
+    arg: Arg,
+) -> Result<Vec<Pixmap>, anyhow::Error> {
+    let dimensions = dimensions.clamp_to(MAX_ANIMATION_SIZE);
+    let shapes = ShapesVec::from_bytes(bytes)?;
+    let bbox = try_calculate_bbox(&create_svg(shapes.0.iter().copied(), dimensions))?;
+
+    frame_shape_counts(shapes.0.len())
+        .map(|count| {
+            let svg_data = create_svg(shapes.0.iter().take(count).copied(), dimensions);
+            try_draw_pixmap(&svg_data, bbox, overlay_chooser, dimensions, arg)
+        })
+        .collect()
+}
+
+/// The number of shapes shown in each frame
+pub fn frame_shape_counts(shape_count: usize) -> impl Iterator<Item = usize> {
+    let shapes_per_frame = ((shape_count + MAX_ANIMATION_FRAMES - 1) / MAX_ANIMATION_FRAMES).max(1);
+    let frame_count = ((shape_count + shapes_per_frame - 1) / shapes_per_frame).max(1);
+
+    (1..=frame_count).map(move |frame| (frame * shapes_per_frame).min(shape_count))
+}
+
+fn frame_delay_millis(index: usize, frame_count: usize) -> u16 {
+    if index + 1 == frame_count {
+        FINAL_FRAME_DELAY_MILLIS
+    } else {
+        FRAME_DELAY_MILLIS
+    }
+}
+
+/// An animated png of the tower being built
+pub fn try_draw_apng<Arg: Copy>(
+    bytes: &[u8],
+    overlay_chooser: &OverlayChooser<Arg>,
+    dimensions: Dimensions,
+    arg: Arg,
+) -> Result<Vec<u8>, anyhow::Error> {
+    let dimensions = dimensions.clamp_to(MAX_ANIMATION_SIZE);
+    let frames = try_draw_frames(bytes, overlay_chooser, dimensions, arg)?;
+    let mut data: Vec<u8> = vec![];
+
+    let mut encoder = png::Encoder::new(&mut data, dimensions.width, dimensions.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(frames.len() as u32, 0)?;
+
+    let mut writer = encoder.write_header()?;
+    for (index, frame) in frames.iter().enumerate() {
+        writer.set_frame_delay(frame_delay_millis(index, frames.len()), 1000)?;
+        // The background is opaque so premultiplied alpha makes no difference
+        writer.write_image_data(frame.data())?;
+    }
+    writer.finish()?;
+
+    Ok(data)
+}
+
+/// An animated gif of the tower being built
+pub fn try_draw_gif<Arg: Copy>(
+    bytes: &[u8],
+    overlay_chooser: &OverlayChooser<Arg>,
+    dimensions: Dimensions,
+    arg: Arg,
+) -> Result<Vec<u8>, anyhow::Error> {
+    /// Lower is slower but has better colors
+    const QUANTIZATION_SPEED: i32 = 10;
+
+    let dimensions = dimensions.clamp_to(MAX_ANIMATION_SIZE);
+    let width: u16 = dimensions.width.try_into()?;
+    let height: u16 = dimensions.height.try_into()?;
+
+    let frames = try_draw_frames(bytes, overlay_chooser, dimensions, arg)?;
+    let mut data: Vec<u8> = vec![];
+
+    {
+        // The encoder writes the end of the gif when it is dropped
+        let mut encoder = gif::Encoder::new(&mut data, width, height, &[])?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+
+        for (index, frame) in frames.iter().enumerate() {
+            let mut rgba = frame.data().to_vec();
+            let mut gif_frame =
+                gif::Frame::from_rgba_speed(width, height, &mut rgba, QUANTIZATION_SPEED);
+            // Gif delays are in hundredths of a second
+            gif_frame.delay = frame_delay_millis(index, frames.len()) / 10;
+            encoder.write_frame(&gif_frame)?;
+        }
+    }
+
+    Ok(data)
+}
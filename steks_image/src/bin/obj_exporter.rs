@@ -77,7 +77,7 @@ pub fn main() {
         println!("{title}",);
         let mut offset: usize = 1;
 
-        let shapes = ShapesVec::from_bytes(&record.image_blob);
+        let shapes = ShapesVec::from_bytes(&record.image_blob).unwrap();
         let groups: Vec<Object> = shapes
             .iter()
             .filter(|shape| !shape.state.is_void())
@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+/// The arguments after the subcommand: positional values and `--name value` options
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    pub positional: Vec<String>,
+    pub options: BTreeMap<String, String>,
+}
+
+impl Args {
+    /// Fails if there is an option which is not in `allowed` or an option without a value
+    pub fn parse(args: impl IntoIterator<Item = String>, allowed: &[&str]) -> anyhow::Result<Self> {
+        let mut result = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                result.positional.push(arg);
+                continue;
+            };
+
+            if !allowed.contains(&name) {
+                return Err(anyhow!("Unknown option --{name}"));
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| anyhow!("Option --{name} needs a value"))?;
+            result.options.insert(name.to_string(), value);
+        }
+
+        Ok(result)
+    }
+
+    pub fn positional(&self, index: usize, name: &str) -> anyhow::Result<&str> {
+        self.positional
+            .get(index)
+            .map(|x| x.as_str())
+            .ok_or_else(|| anyhow!("Missing <{name}>"))
+    }
+
+    pub fn option<T: FromStr>(&self, name: &str, default: T) -> anyhow::Result<T>
+    where
+        T::Err: std::fmt::Display,
+    {
+        match self.options.get(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|err| anyhow!("Could not parse --{name} '{value}': {err}")),
+            None => Ok(default),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    pub fn test_parse_args() {
+        let args = Args::parse(
+            strings(&["code", "--width", "512", "out.png"]),
+            &["width", "height"],
+        )
+        .unwrap();
+
+        assert_eq!(args.positional(0, "code").unwrap(), "code");
+        assert_eq!(args.positional(1, "output").unwrap(), "out.png");
+        assert!(args.positional(2, "other").is_err());
+        assert_eq!(args.option("width", 1024u32).unwrap(), 512);
+        assert_eq!(args.option("height", 1024u32).unwrap(), 1024);
+    }
+
+    #[test]
+    pub fn test_parse_args_errors() {
+        assert!(Args::parse(strings(&["--depth", "3"]), &["width"]).is_err());
+        assert!(Args::parse(strings(&["--width"]), &["width"]).is_err());
+
+        let args = Args::parse(strings(&["--width", "wide"]), &["width"]).unwrap();
+        assert!(args.option("width", 1024u32).is_err());
+    }
+}
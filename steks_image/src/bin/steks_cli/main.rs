@@ -0,0 +1,356 @@
+use std::f32::consts::TAU;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use base64::Engine;
+use bevy::prelude::Vec2;
+use chrono::{Datelike, NaiveDate};
+pub use steks_common::prelude::*;
+use steks_image::prelude::*;
+
+mod args;
+mod records;
+
+use args::Args;
+use records::*;
+
+const USAGE: &str = "Usage: steks_cli <command> [arguments]
+
+Commands:
+  render <share code> [--output PATH] [--format png|svg|gif|apng] [--width N] [--height N]
+      Draw a tower. The share code may be a full game link.
+  render-records <records.tsv> [--output-dir DIR] [--format ...] [--width N] [--height N]
+      Draw every record in a file of `hash height share_code` rows.
+      Records of campaign levels are named after the level.
+  challenges <from> [<to>] [--output-dir DIR] [--format ...] [--width N] [--height N]
+      Draw the shapes of each daily challenge between two dates, like 2023-09-01.
+  verify-records <records.tsv> [--format sql|json] [--tolerance METRES]
+      Print fixes for records whose height does not match their shapes.
+  decode <share code>
+      Print the shapes of a share code as json.
+  encode <path>
+      Print the share code for a json file of shapes, as printed by decode. Use - for stdin.";
+
+pub fn main() {
+    let mut args = std::env::args().skip(1);
+    let Some(command) = args.next() else {
+        eprintln!("{USAGE}");
+        std::process::exit(2);
+    };
+
+    let result = match command.as_str() {
+        "render" => render(args),
+        "render-records" => render_records(args),
+        "challenges" => challenges(args),
+        "verify-records" => verify_records(args),
+        "decode" => decode(args),
+        "encode" => encode(args),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        other => {
+            eprintln!("Unknown command '{other}'\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(err) = result {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImageFormat {
+    Png,
+    Svg,
+    Gif,
+    Apng,
+}
+
+impl FromStr for ImageFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "png" => Ok(Self::Png),
+            "svg" => Ok(Self::Svg),
+            "gif" => Ok(Self::Gif),
+            "apng" => Ok(Self::Apng),
+            _ => Err(anyhow!("Expected png, svg, gif or apng")),
+        }
+    }
+}
+
+impl ImageFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png | ImageFormat::Apng => "png",
+            ImageFormat::Svg => "svg",
+            ImageFormat::Gif => "gif",
+        }
+    }
+
+    fn draw(&self, bytes: &[u8], dimensions: Dimensions) -> anyhow::Result<Vec<u8>> {
+        let overlay_chooser = OverlayChooser::no_overlay();
+        match self {
+            ImageFormat::Png => try_draw_image(bytes, &overlay_chooser, dimensions, ()),
+            ImageFormat::Svg => Ok(make_svg_from_bytes(bytes, dimensions)?.into_bytes()),
+            ImageFormat::Gif => try_draw_gif(bytes, &overlay_chooser, dimensions, ()),
+            ImageFormat::Apng => try_draw_apng(bytes, &overlay_chooser, dimensions, ()),
+        }
+    }
+}
+
+/// The format and dimensions options shared by the drawing commands
+fn image_options(args: &Args) -> anyhow::Result<(ImageFormat, Dimensions)> {
+    let format = args.option("format", ImageFormat::Png)?;
+    let width = args.option("width", 512)?;
+    let height = args.option("height", 512)?;
+
+    Ok((format, Dimensions { width, height }))
+}
+
+fn draw_to_file(
+    bytes: &[u8],
+    format: ImageFormat,
+    dimensions: Dimensions,
+    path: &Path,
+) -> anyhow::Result<()> {
+    let data = format.draw(bytes, dimensions)?;
+    std::fs::write(path, data).map_err(|err| anyhow!("Could not write {}: {err}", path.display()))
+}
+
+/// Decode a share code, or the share code at the end of a game link
+fn decode_share_code(code: &str) -> anyhow::Result<Vec<u8>> {
+    let code = code.rsplit('/').next().unwrap_or(code);
+    Ok(base64::engine::general_purpose::URL_SAFE.decode(code.trim())?)
+}
+
+fn render(args: impl Iterator<Item = String>) -> anyhow::Result<()> {
+    let args = Args::parse(args, &["output", "format", "width", "height"])?;
+    let bytes = decode_share_code(args.positional(0, "share code")?)?;
+    let (format, dimensions) = image_options(&args)?;
+    let output = args.option("output", format!("tower.{}", format.extension()))?;
+
+    draw_to_file(&bytes, format, dimensions, Path::new(&output))?;
+    println!("{output}");
+    Ok(())
+}
+
+fn render_records(args: impl Iterator<Item = String>) -> anyhow::Result<()> {
+    let args = Args::parse(args, &["output-dir", "format", "width", "height"])?;
+    let records = read_records(args.positional(0, "records.tsv")?)?;
+    let (format, dimensions) = image_options(&args)?;
+    let output_dir = args.option("output-dir", "record_images".to_string())?;
+    std::fs::create_dir_all(&output_dir)?;
+
+    let campaign_hashes: Vec<u64> = CAMPAIGN_LEVELS
+        .iter()
+        .map(|level| ShapesVec::from(level).hash())
+        .collect();
+
+    for record in records {
+        let name = match campaign_hashes.iter().position(|x| *x == record.hash) {
+            Some(index) => {
+                let title: String = CAMPAIGN_LEVELS[index]
+                    .title
+                    .clone()
+                    .unwrap_or_default()
+                    .chars()
+                    .filter(|x| x.is_ascii_alphabetic())
+                    .collect();
+                format!("{number}_{title}", number = index + 1)
+            }
+            None => record.hash.to_string(),
+        };
+
+        let path = Path::new(&output_dir).join(format!("{name}.{}", format.extension()));
+        draw_to_file(&record.image_blob, format, dimensions, &path)?;
+        println!("{}\t{}", path.display(), record.height);
+    }
+    Ok(())
+}
+
+fn challenges(args: impl Iterator<Item = String>) -> anyhow::Result<()> {
+    let args = Args::parse(args, &["output-dir", "format", "width", "height"])?;
+    let from: NaiveDate = args.positional(0, "from")?.parse()?;
+    let to: NaiveDate = match args.positional.get(1) {
+        Some(to) => to.parse()?,
+        None => from,
+    };
+    let (format, dimensions) = image_options(&args)?;
+    let output_dir = args.option("output-dir", "challenge_images".to_string())?;
+    std::fs::create_dir_all(&output_dir)?;
+
+    for date in from.iter_days().take_while(|date| *date <= to) {
+        let bytes = challenge_shapes(date).make_bytes();
+        let path = Path::new(&output_dir).join(format!("{date}.{}", format.extension()));
+        draw_to_file(&bytes, format, dimensions, &path)?;
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+/// The shapes of the daily challenge, sorted and stacked in a column
+fn challenge_shapes(date: NaiveDate) -> ShapesVec {
+    let mut shapes =
+        ShapeIndex::challenge_shapes(date.year().unsigned_abs(), date.month(), date.day());
+    shapes.sort();
+
+    ShapesVec(
+        shapes
+            .into_iter()
+            .enumerate()
+            .map(|(index, shape)| EncodableShape {
+                shape,
+                location: Location::new(0.0, index as f32 * SHAPE_SIZE, 0.0),
+                state: ShapeState::Normal,
+                modifiers: ShapeModifiers::Normal,
+            })
+            .collect(),
+    )
+}
+
+fn verify_records(args: impl Iterator<Item = String>) -> anyhow::Result<()> {
+    let args = Args::parse(args, &["format", "tolerance"])?;
+    let records = read_records(args.positional(0, "records.tsv")?)?;
+    let json = match args.option("format", "sql".to_string())?.as_str() {
+        "sql" => false,
+        "json" => true,
+        other => return Err(anyhow!("Expected --format sql or json, not '{other}'")),
+    };
+    let tolerance: f32 = args.option("tolerance", 1.0)?;
+
+    let mut fixes = vec![];
+    for record in records {
+        let actual_height = match ShapesVec::from_bytes(&record.image_blob) {
+            Ok(shapes) => shapes.calculate_tower_height(),
+            Err(err) => {
+                eprintln!("Could not decode record {}: {err}", record.hash);
+                continue;
+            }
+        };
+
+        if (record.height - actual_height).abs() <= tolerance {
+            continue;
+        }
+
+        if json {
+            fixes.push(serde_json::json!({
+                "hash": record.hash,
+                "recorded_height": record.height,
+                "actual_height": actual_height,
+            }));
+        } else {
+            println!(
+                "UPDATE `steks`.`tower_height` SET `max_height` = {actual_height:.2} WHERE `shapes_hash` = {hash};",
+                hash = record.hash
+            );
+        }
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&fixes)?);
+    }
+    Ok(())
+}
+
+fn decode(args: impl Iterator<Item = String>) -> anyhow::Result<()> {
+    let args = Args::parse(args, &[])?;
+    let bytes = decode_share_code(args.positional(0, "share code")?)?;
+    let shapes = ShapesVec::from_bytes(&bytes)?;
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&shapes_to_creations(&shapes))?
+    );
+    Ok(())
+}
+
+fn encode(args: impl Iterator<Item = String>) -> anyhow::Result<()> {
+    let args = Args::parse(args, &[])?;
+    let data = match args.positional(0, "path")? {
+        "-" => std::io::read_to_string(std::io::stdin())?,
+        path => std::fs::read_to_string(path)?,
+    };
+    let creations: Vec<ShapeCreation> = serde_json::from_str(&data)?;
+
+    println!("{}", creations_to_shapes(&creations).make_base64_data());
+    Ok(())
+}
+
+/// The shapes in the same form as the shapes of a level
+fn shapes_to_creations(shapes: &ShapesVec) -> Vec<ShapeCreation> {
+    shapes
+        .iter()
+        .map(|shape| ShapeCreation {
+            vel_x: None,
+            vel_y: None,
+            ..ShapeCreation::from(*shape)
+        })
+        .collect()
+}
+
+fn creations_to_shapes(creations: &[ShapeCreation]) -> ShapesVec {
+    ShapesVec(
+        creations
+            .iter()
+            .map(|creation| EncodableShape {
+                shape: creation.shape.into(),
+                location: Location {
+                    position: Vec2::new(
+                        creation.x.unwrap_or_default(),
+                        creation.y.unwrap_or_default(),
+                    ),
+                    angle: creation.r.unwrap_or_default() * TAU,
+                },
+                state: creation.state,
+                modifiers: creation.modifiers,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // spell-checker: disable-next-line
+    const TEST_DATA: &str = "CQCBGHvzog4AfvB2ZysEAILnjfABCACDxpPAAhMAfnVw1uAFAHw-bLbnAgCCyZ2xPgwAhV6IVCoJAIOygSikCzCOqo__PAMgeVRpVOkCIIv_i1Q8";
+
+    #[test]
+    pub fn test_decode_encode_round_trip() {
+        let bytes = decode_share_code(TEST_DATA).unwrap();
+        let shapes = ShapesVec::from_bytes(&bytes).unwrap();
+
+        let json = serde_json::to_string(&shapes_to_creations(&shapes)).unwrap();
+        let creations: Vec<ShapeCreation> = serde_json::from_str(&json).unwrap();
+        let round_tripped = creations_to_shapes(&creations);
+
+        assert_eq!(round_tripped.hash(), shapes.hash());
+        assert_eq!(round_tripped.len(), shapes.len());
+        for (a, b) in round_tripped.iter().zip(shapes.iter()) {
+            assert!(a.location.position.distance(b.location.position) < 0.01);
+        }
+    }
+
+    #[test]
+    pub fn test_decode_game_link() {
+        assert_eq!(
+            decode_share_code(&format!("https://steks.net/game/{TEST_DATA}")).unwrap(),
+            decode_share_code(TEST_DATA).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_challenge_shapes() {
+        let date = NaiveDate::from_ymd_opt(2023, 9, 1).unwrap();
+        let shapes = challenge_shapes(date);
+
+        assert_eq!(shapes.len(), CHALLENGE_SHAPES);
+        assert_eq!(shapes, challenge_shapes(date));
+    }
+}
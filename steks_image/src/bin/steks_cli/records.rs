@@ -0,0 +1,58 @@
+use anyhow::anyhow;
+use base64::Engine;
+
+/// A row of a records file: the shapes hash, the recorded height and the share code
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub hash: u64,
+    pub height: f32,
+    pub image_blob: Vec<u8>,
+}
+
+impl Record {
+    pub fn from_line(line: &str) -> anyhow::Result<Self> {
+        let mut split = line.split_ascii_whitespace();
+        let mut next = |name: &str| split.next().ok_or_else(|| anyhow!("Missing {name}"));
+
+        let hash: u64 = next("hash")?.parse()?;
+        let height: f32 = next("height")?.parse()?;
+        let image_blob = base64::engine::general_purpose::URL_SAFE.decode(next("share code")?)?;
+
+        Ok(Self {
+            hash,
+            height,
+            image_blob,
+        })
+    }
+}
+
+/// Read a tab separated file of records, like the export of the `tower_height` table
+pub fn read_records(path: &str) -> anyhow::Result<Vec<Record>> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|err| anyhow!("Could not read records from {path}: {err}"))?;
+
+    data.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            Record::from_line(line).map_err(|err| anyhow!("{path}:{}: {err}", index + 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_record_from_line() {
+        let record = Record::from_line("1832848042242027586\t401.61\tARCAiWbYeBEA").unwrap();
+
+        assert_eq!(record.hash, 1832848042242027586);
+        assert_eq!(record.height, 401.61);
+        assert!(!record.image_blob.is_empty());
+
+        assert!(Record::from_line("1832848042242027586\t401.61").is_err());
+        assert!(Record::from_line("hash\t401.61\tARCAiWbYeBEA").is_err());
+    }
+}
@@ -12,3 +12,19 @@ impl Default for Dimensions {
         }
     }
 }
+
+impl Dimensions {
+    /// Scale down, keeping the aspect ratio, so that neither side is longer than `max`
+    pub fn clamp_to(self, max: u32) -> Self {
+        let longest = self.width.max(self.height);
+        if longest <= max {
+            return self;
+        }
+        let scale = |x: u32| ((x as u64 * max as u64) / longest as u64).max(1) as u32;
+
+        Self {
+            width: scale(self.width),
+            height: scale(self.height),
+        }
+    }
+}
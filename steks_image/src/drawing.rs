@@ -1,12 +1,15 @@
 use steks_common::prelude::*;
 pub use crate::prelude::*;
-use resvg::tiny_skia::Transform;
-use resvg::usvg::{AspectRatio, NodeExt, NonZeroRect, Tree, TreeParsing, ViewBox};
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg::{AspectRatio, NodeExt, NonZeroRect, Rect, Tree, TreeParsing, ViewBox};
 
-pub fn make_svg_from_bytes(bytes: &[u8], dimensions: Dimensions) -> String {
-    let shapes = ShapesVec::from_bytes(&bytes);
+pub fn make_svg_from_bytes(
+    bytes: &[u8],
+    dimensions: Dimensions,
+) -> Result<String, ShareCodeError> {
+    let shapes = ShapesVec::from_bytes(&bytes)?;
     let svg = create_svg(shapes.0.into_iter(), dimensions);
-    svg
+    Ok(svg)
 }
 
 pub fn try_draw_image<Arg>(
@@ -15,17 +18,38 @@ pub fn try_draw_image<Arg>(
     dimensions: Dimensions,
     arg: Arg
 ) -> Result<Vec<u8>, anyhow::Error> {
+    let svg_data = make_svg_from_bytes(bytes, dimensions)?;
+    let pixmap = try_draw_pixmap(&svg_data, None, overlay_chooser, dimensions, arg)?;
+
+    Ok(pixmap.encode_png()?)
+}
+
+/// The area of the svg which contains shapes, if there are any
+pub fn try_calculate_bbox(svg_data: &str) -> Result<Option<Rect>, anyhow::Error> {
+    let opt: resvg::usvg::Options = Default::default();
+    let game_tree = Tree::from_data(&svg_data.as_bytes(), &opt)?;
+
+    Ok(game_tree.root.calculate_bbox())
+}
+
+/// Draw the svg scaled to fit `bbox`, or to fit its own shapes if `bbox` is `None`
+pub fn try_draw_pixmap<Arg>(
+    svg_data: &str,
+    bbox: Option<Rect>,
+    overlay_chooser: &OverlayChooser<Arg>,
+    dimensions: Dimensions,
+    arg: Arg
+) -> Result<Pixmap, anyhow::Error> {
     let opt: resvg::usvg::Options = Default::default();
-    let svg_data = make_svg_from_bytes(bytes, dimensions);
 
     let mut game_tree = Tree::from_data(&svg_data.as_bytes(), &opt)?;
 
-    let bbox = game_tree.root.calculate_bbox().unwrap_or(
-        resvg::usvg::Rect::from_xywh(0., 0., dimensions.width as f32, dimensions.height as f32)
+    let bbox = bbox.or_else(|| game_tree.root.calculate_bbox()).unwrap_or(
+        Rect::from_xywh(0., 0., dimensions.width as f32, dimensions.height as f32)
             .ok_or(anyhow::anyhow!("Could not create rectangle"))?,
     );
 
-    let mut pixmap = resvg::tiny_skia::Pixmap::new(dimensions.width, dimensions.height)
+    let mut pixmap = Pixmap::new(dimensions.width, dimensions.height)
         .ok_or(anyhow::anyhow!("Could not create pixmap"))?;
 
     let [r, g, b, a] = BACKGROUND_COLOR.as_rgba_u32().to_le_bytes();
@@ -67,5 +91,5 @@ pub fn try_draw_image<Arg>(
         overlay.try_include(&mut pixmap, &opt, dimensions, arg)?;
     }
 
-    Ok(pixmap.encode_png()?)
+    Ok(pixmap)
 }
@@ -1,3 +1,4 @@
+pub mod animation;
 pub mod dimensions;
 pub mod drawing;
 pub mod overlay;
@@ -5,6 +6,7 @@ pub mod placement;
 pub mod svg;
 
 pub mod prelude {
+    pub use crate::animation::*;
     pub use crate::dimensions::*;
     pub use crate::drawing::*;
     pub use crate::overlay::*;
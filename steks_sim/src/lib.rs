@@ -0,0 +1,9 @@
+pub mod prediction;
+pub mod simulation;
+pub mod world;
+
+pub mod prelude {
+    pub use crate::prediction::*;
+    pub use crate::simulation::*;
+    pub use crate::world::*;
+}
@@ -0,0 +1,63 @@
+use std::sync::atomic::Ordering;
+
+use bevy_rapier2d::rapier::prelude as rapier;
+use steks_common::prelude::*;
+
+use crate::world::PhysicsWorld;
+
+/// The result of looking ahead to see if the tower will stand.
+/// This matches `PredictionResult` in `steks_base`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SimPrediction {
+    MinimalCollision,
+    EarlyWall,
+    Wall,
+    ManyNonWall,
+}
+
+impl SimPrediction {
+    /// Run the world forward on a copy to see whether the tower will stand
+    pub fn predict(world: &PhysicsWorld) -> Self {
+        let mut world = world.clone();
+        let mut pipeline = rapier::PhysicsPipeline::new();
+        let event_handler = CollisionCounter::default();
+
+        for (_, collider) in world.colliders.iter_mut() {
+            collider.set_active_events(rapier::ActiveEvents::COLLISION_EVENTS);
+        }
+
+        for substep in 1..=PREDICTION_MAX_SUBSTEPS {
+            world.step(&mut pipeline, &event_handler);
+
+            let sensor_found = event_handler.sensor_collision_found.load(Ordering::Relaxed);
+
+            if substep < PREDICTION_EARLY_SENSOR_SUBSTEPS {
+                if sensor_found {
+                    return SimPrediction::EarlyWall;
+                }
+            } else {
+                if sensor_found {
+                    return SimPrediction::Wall;
+                }
+
+                if event_handler.total_collisions_found.load(Ordering::Relaxed)
+                    > PREDICTION_MAX_NON_SENSOR_COLLISIONS
+                {
+                    return SimPrediction::ManyNonWall;
+                }
+            }
+        }
+
+        SimPrediction::MinimalCollision
+    }
+
+    pub fn get_countdown_frames(&self, has_acted: bool) -> Option<u32> {
+        match (has_acted, self) {
+            (_, SimPrediction::EarlyWall) => None,
+            (_, SimPrediction::ManyNonWall) => Some(LONG_WIN_FRAMES),
+            (_, SimPrediction::Wall) => Some(LONG_WIN_FRAMES),
+            (true, SimPrediction::MinimalCollision) => Some(SHORT_WIN_FRAMES),
+            (false, SimPrediction::MinimalCollision) => Some(LONG_WIN_FRAMES),
+        }
+    }
+}
@@ -0,0 +1,689 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::Vec2;
+use bevy_rapier2d::rapier::prelude as rapier;
+use rand::{rngs::StdRng, SeedableRng};
+use steks_common::prelude::*;
+use strum::IntoEnumIterator;
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationSettings {
+    /// Width of the play area in pixels
+    pub width: f32,
+    /// Height of the play area in pixels
+    pub height: f32,
+    /// How long to keep going after the last input before giving up on the win countdown
+    pub max_frames_after_inputs: u32,
+    /// Seeds the random placement of shapes without a location, so runs can be repeated
+    pub seed: u64,
+}
+
+impl Default for SimulationSettings {
+    fn default() -> Self {
+        Self {
+            width: MAX_WINDOW_WIDTH,
+            height: MAX_WINDOW_HEIGHT,
+            max_frames_after_inputs: FRAMES_PER_SECOND * 20,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationResult {
+    pub shapes: ShapesVec,
+    pub height: f32,
+    /// Whether the win countdown completed on the final stage
+    pub won: bool,
+    /// The stage reached. This will be greater than zero only for multi-stage levels.
+    pub stage: usize,
+    /// The number of frames simulated
+    pub frames: u32,
+}
+
+/// A headless, deterministic version of the game's physics.
+/// Shapes are created and moved exactly as they are by the bevy systems in `steks_base`.
+pub struct Simulation {
+    world: PhysicsWorld,
+    pipeline: rapier::PhysicsPipeline,
+    settings: SimulationSettings,
+    rng: StdRng,
+    shapes: Vec<SimShape>,
+    walls: Vec<Wall>,
+    level: Option<DesignedLevel>,
+    stage: usize,
+    frame: u32,
+    countdown: Option<u32>,
+    check_for_tower: bool,
+    has_acted: bool,
+    won: bool,
+}
+
+impl Simulation {
+    fn new(gravity: Vec2, settings: SimulationSettings) -> Self {
+        let mut world = PhysicsWorld::new(gravity);
+        let walls = wall_rectangles(&settings, gravity)
+            .into_iter()
+            .map(|(position, extents)| world.add_wall(position, extents))
+            .collect();
+
+        Self {
+            world,
+            pipeline: rapier::PhysicsPipeline::new(),
+            settings,
+            rng: StdRng::seed_from_u64(settings.seed),
+            shapes: vec![],
+            walls,
+            level: None,
+            stage: 0,
+            frame: 0,
+            countdown: None,
+            check_for_tower: false,
+            has_acted: false,
+            won: false,
+        }
+    }
+
+    pub fn from_level(level: &DesignedLevel, settings: SimulationSettings) -> Self {
+        let gravity = level.initial_stage.gravity.unwrap_or(GRAVITY);
+        let mut simulation = Self::new(gravity, settings);
+        simulation.level = Some(level.clone());
+        simulation.create_stage_shapes(0);
+        simulation
+    }
+
+    pub fn from_shapes(shapes: &ShapesVec, settings: SimulationSettings) -> Self {
+        let mut simulation = Self::new(GRAVITY, settings);
+        for shape in shapes.iter() {
+            simulation.add_shape(
+                shape.shape.game_shape(),
+                shape.location,
+                Vec2::ZERO,
+                shape.state,
+                shape.modifiers,
+                None,
+            );
+        }
+        simulation
+    }
+
+    /// Run all of the inputs and then wait for the win countdown to finish
+    pub fn run(mut self, inputs: &[TimedInput]) -> SimulationResult {
+        let mut inputs = inputs.to_vec();
+        inputs.sort_by_key(|x| x.frame);
+        let last_input_frame = inputs.last().map(|x| x.frame).unwrap_or_default();
+        let mut inputs = inputs.into_iter().peekable();
+
+        loop {
+            let frame = self.frame;
+            let frame_inputs = std::iter::from_fn(|| {
+                inputs
+                    .next_if(|input| input.frame <= frame)
+                    .map(|x| x.input)
+            });
+            self.step_frame(frame_inputs);
+
+            if self.won
+                || self.frame
+                    > last_input_frame.saturating_add(self.settings.max_frames_after_inputs)
+            {
+                break;
+            }
+
+            if inputs.peek().is_none() && self.countdown.is_none() && !self.check_for_tower {
+                break;
+            }
+        }
+
+        self.result()
+    }
+
+    pub fn result(&self) -> SimulationResult {
+        let shapes = self.shapes_vec();
+        let height =
+            shapes.calculate_tower_height_above(self.level.as_ref().and_then(|x| x.water_line()));
+
+        SimulationResult {
+            shapes,
+            height,
+            won: self.won,
+            stage: self.stage,
+            frames: self.frame,
+        }
+    }
+
+    pub fn shapes_vec(&self) -> ShapesVec {
+        ShapesVec(
+            self.shapes
+                .iter()
+                .map(|shape| EncodableShape {
+                    shape: shape.shape.index,
+                    location: self.world.location(shape.body),
+                    state: shape.state.shape_state(),
+                    modifiers: shape.modifiers,
+                })
+                .collect(),
+        )
+    }
+
+    /// Advance one physics frame, applying the given inputs first
+    pub fn step_frame(&mut self, inputs: impl Iterator<Item = ReplayInput>) {
+        for input in inputs {
+            self.apply_input(input);
+        }
+
+        self.apply_drag_forces();
+
+        let collisions = CollisionCounter::default();
+        self.world.step(&mut self.pipeline, &collisions);
+        self.frame += 1;
+
+        if self.countdown.is_some()
+            && collisions
+                .sensor_collision_found
+                .load(std::sync::atomic::Ordering::Relaxed)
+        {
+            self.countdown = None;
+        }
+
+        if self.check_for_tower {
+            self.check_for_tower = false;
+            self.check_tower();
+        }
+
+        self.tick_countdown();
+    }
+
+    fn apply_input(&mut self, input: ReplayInput) {
+        match input {
+            ReplayInput::PickUp { position } => {
+                if self.shapes.iter().any(|x| x.state.is_dragged()) {
+                    return;
+                }
+                let world = &self.world;
+                let Some(shape) = self.shapes.iter_mut().find(|shape| {
+                    matches!(
+                        shape.state,
+                        SimShapeState::Placed(ShapeState::Normal | ShapeState::Locked)
+                    ) && shape
+                        .shape
+                        .body
+                        .to_collider_shape(SHAPE_SIZE)
+                        .contains_point(
+                            world.location(shape.body).position,
+                            world.location(shape.body).angle,
+                            position,
+                        )
+                }) else {
+                    return;
+                };
+
+                let origin = world.location(shape.body).position;
+                shape.state = SimShapeState::Dragged {
+                    offset: origin - position,
+                    desired_position: origin,
+                };
+                self.world
+                    .set_state(shape.body, shape.collider, shape.state, shape.modifiers);
+                self.has_acted = true;
+                self.countdown = None;
+            }
+            ReplayInput::DragTo { position } => {
+                let max = Vec2::new(MAX_WINDOW_WIDTH, MAX_WINDOW_HEIGHT) * 0.5;
+                let clamped_position = position.clamp(-max, max);
+                for shape in self.shapes.iter_mut() {
+                    if let SimShapeState::Dragged {
+                        offset,
+                        desired_position,
+                    } = &mut shape.state
+                    {
+                        *desired_position = *offset + clamped_position;
+                    }
+                }
+            }
+            ReplayInput::Rotate {
+                delta,
+                snap_resolution,
+            } => {
+                for shape in self.shapes.iter().filter(|x| x.state.is_dragged()) {
+                    let mut location = self.world.location(shape.body);
+                    location.angle = (location.angle + delta).rem_euclid(TAU);
+                    if let Some(multiple) = snap_resolution {
+                        location.angle = (location.angle / multiple).round() * multiple;
+                    }
+                    self.world.set_location(shape.body, location);
+                }
+            }
+            ReplayInput::Drop => {
+                for shape in self.shapes.iter_mut().filter(|x| x.state.is_dragged()) {
+                    shape.state = SimShapeState::Placed(ShapeState::Normal);
+                    self.world
+                        .set_state(shape.body, shape.collider, shape.state, shape.modifiers);
+                    self.check_for_tower = true;
+                }
+            }
+        }
+    }
+
+    fn apply_drag_forces(&mut self) {
+        for shape in self.shapes.iter() {
+            let SimShapeState::Dragged {
+                desired_position, ..
+            } = shape.state
+            else {
+                continue;
+            };
+
+            let position = self.world.location(shape.body).position;
+            let velocity = self.world.velocity(shape.body);
+            let distance = desired_position - position;
+
+            self.world
+                .set_force(shape.body, drag_force(distance, velocity));
+        }
+    }
+
+    /// This mirrors `check_for_tower` in `steks_base`
+    fn check_tower(&mut self) {
+        if self.countdown.is_some() || self.shapes.iter().any(|x| x.state.is_dragged()) {
+            return;
+        }
+
+        if self
+            .walls
+            .iter()
+            .any(|wall| self.world.has_active_contacts(wall.collider))
+        {
+            return;
+        }
+
+        let sensors = self.walls.iter().map(|wall| wall.sensor).chain(
+            self.shapes
+                .iter()
+                .filter(|x| x.state == SimShapeState::Placed(ShapeState::Void))
+                .map(|x| x.collider),
+        );
+
+        if sensors.into_iter().any(|x| self.world.has_intersections(x)) {
+            return;
+        }
+
+        let prediction = if self.current_stage().and_then(|x| x.rainfall).is_some() {
+            SimPrediction::ManyNonWall
+        } else {
+            SimPrediction::predict(&self.world)
+        };
+
+        self.countdown = prediction.get_countdown_frames(self.has_acted);
+    }
+
+    fn tick_countdown(&mut self) {
+        let Some(frames_remaining) = self.countdown else {
+            return;
+        };
+
+        if frames_remaining > 0 {
+            self.countdown = Some(frames_remaining - 1);
+            return;
+        }
+        self.countdown = None;
+
+        let next_stage = self.stage + 1;
+        if self
+            .level
+            .as_ref()
+            .is_some_and(|level| level.total_stages() > next_stage)
+        {
+            self.stage = next_stage;
+            self.create_stage_shapes(next_stage);
+            self.check_for_tower = true;
+        } else {
+            self.won = true;
+        }
+    }
+
+    fn current_stage(&self) -> Option<&LevelStage> {
+        self.level.as_ref().and_then(|x| x.get_stage(&self.stage))
+    }
+
+    fn create_stage_shapes(&mut self, stage: usize) {
+        let Some(level_stage) = self
+            .level
+            .as_ref()
+            .and_then(|x| x.get_stage(&stage))
+            .cloned()
+        else {
+            return;
+        };
+
+        if let Some(gravity) = level_stage.gravity {
+            self.world.gravity = gravity;
+        }
+        self.world.set_force_zones(level_stage.zones.clone());
+        self.world.water = level_stage.water;
+
+        let mut rectangle_set = RectangleSet::new(
+            self.settings.width,
+            self.settings.height,
+            self.shapes
+                .iter()
+                .map(|x| (x.shape.index, self.world.location(x.body))),
+        );
+
+        for creation in level_stage.shapes.iter() {
+            let shape: &'static GameShape = creation.shape.into();
+            let location = match (creation.x, creation.y, creation.r) {
+                (None, None, None) => rectangle_set.do_place(shape.body, &mut self.rng),
+                (x, y, r) => Location::new(
+                    x.unwrap_or_default(),
+                    y.unwrap_or_default(),
+                    r.unwrap_or_default() * TAU,
+                ),
+            };
+            // This matches `ShapeCreationData::from_shape_creation` in `steks_base`
+            let (velocity, angvel) = match (creation.state, creation.vel_x, creation.vel_y) {
+                (ShapeState::Normal, None, None) => random_velocity(&mut self.rng),
+                (_, vel_x, vel_y) => (
+                    Vec2::new(vel_x.unwrap_or_default(), vel_y.unwrap_or_default()),
+                    0.0,
+                ),
+            };
+
+            let body = self.add_shape(
+                shape,
+                location,
+                velocity,
+                creation.state,
+                creation.modifiers,
+                creation.id,
+            );
+            self.world.set_angular_velocity(body, angvel);
+
+            if let Some(motion) = creation.motion {
+                if creation.state == ShapeState::Fixed {
+                    self.world.start_motion(body, motion, location);
+                }
+            }
+        }
+
+        for update in level_stage.updates.iter() {
+            self.apply_update(update);
+        }
+
+        for joint in level_stage.joints.iter() {
+            self.add_joint(joint);
+        }
+    }
+
+    fn add_joint(&mut self, joint: &ShapeJoint) {
+        let find = |id: u32| {
+            self.shapes
+                .iter()
+                .find(|x| x.id == Some(id))
+                .map(|x| x.body)
+        };
+        let (Some(a), Some(b)) = (find(joint.a), find(joint.b)) else {
+            return;
+        };
+        self.world.add_joint(a, b, joint);
+    }
+
+    fn add_shape(
+        &mut self,
+        shape: &'static GameShape,
+        location: Location,
+        velocity: Vec2,
+        state: ShapeState,
+        modifiers: ShapeModifiers,
+        id: Option<u32>,
+    ) -> rapier::RigidBodyHandle {
+        let (body, collider) = self
+            .world
+            .add_shape(shape, location, velocity, state, modifiers);
+
+        self.shapes.push(SimShape {
+            shape,
+            state: SimShapeState::Placed(state),
+            modifiers,
+            id,
+            stage: self.stage,
+            body,
+            collider,
+        });
+        body
+    }
+
+    fn apply_update(&mut self, update: &ShapeUpdate) {
+        let Some(shape) = self.shapes.iter_mut().find(|x| x.id == Some(update.id)) else {
+            return;
+        };
+
+        if let Some(state) = update.state {
+            shape.state = SimShapeState::Placed(state);
+            if state != ShapeState::Fixed {
+                self.world.stop_motion(shape.body);
+            }
+        }
+        if let Some(form) = update.shape {
+            shape.shape = form.into();
+        }
+        shape.modifiers = update.modifiers;
+        shape.collider = self.world.replace_collider(
+            shape.body,
+            shape.collider,
+            shape.shape,
+            shape.modifiers,
+            shape.state,
+        );
+
+        let mut location = self.world.location(shape.body);
+        if let Some(x) = update.x {
+            location.position.x = x;
+        }
+        if let Some(y) = update.y {
+            location.position.y = y;
+        }
+        if let Some(r) = update.r {
+            location.angle = r * TAU;
+        }
+        self.world.set_location(shape.body, location);
+
+        if update.vel_x.is_some() || update.vel_y.is_some() {
+            self.world.set_velocity(
+                shape.body,
+                Vec2::new(
+                    update.vel_x.unwrap_or_default(),
+                    update.vel_y.unwrap_or_default(),
+                ),
+            );
+        }
+    }
+}
+
+/// Positions and extents of the walls, as the game places them on a desktop screen
+fn wall_rectangles(settings: &SimulationSettings, gravity: Vec2) -> Vec<(Vec2, Vec2)> {
+    let (top_offset, bottom_offset) = if gravity.y > 0.0 {
+        (-TOP_BOTTOM_OFFSET, 0.0)
+    } else {
+        (0.0, TOP_BOTTOM_OFFSET)
+    };
+
+    WallPosition::iter()
+        .map(|wall| {
+            (
+                wall.get_center(settings.height, settings.width, top_offset, bottom_offset),
+                wall.get_extents(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+
+    fn single_shape_level() -> DesignedLevel {
+        let mut level = DesignedLevel::default();
+        level.initial_stage.shapes.push(ShapeCreation {
+            shape: LevelShapeForm::O4,
+            x: Some(0.0),
+            y: Some(0.0),
+            r: Some(0.0),
+            ..Default::default()
+        });
+        level
+    }
+
+    #[test]
+    pub fn test_simulation_is_deterministic() {
+        let blob = base64::engine::general_purpose::URL_SAFE
+        //spellchecker:disable-next-line
+        .decode("CQCBGHvzog4AfvB2ZysEAILnjfABCACDxpPAAhMAfnVw1uAFAHw-bLbnAgCCyZ2xPgwAhV6IVCoJAIOygSikCzCOqo__PAMgeVRpVOkCIIv_i1Q8").unwrap();
+        let shapes = ShapesVec::from_bytes(&blob).unwrap();
+
+        let inputs = [
+            TimedInput::new(
+                10,
+                ReplayInput::PickUp {
+                    position: shapes[0].location.position,
+                },
+            ),
+            TimedInput::new(
+                11,
+                ReplayInput::DragTo {
+                    position: Vec2::new(0.0, 500.0),
+                },
+            ),
+            TimedInput::new(60, ReplayInput::Drop),
+        ];
+
+        let first = Simulation::from_shapes(&shapes, Default::default()).run(&inputs);
+        let second = Simulation::from_shapes(&shapes, Default::default()).run(&inputs);
+
+        assert_eq!(first, second);
+        assert_eq!(first.shapes.len(), shapes.len());
+    }
+
+    #[test]
+    pub fn test_pick_up_and_drop() {
+        let level = single_shape_level();
+        let mut simulation = Simulation::from_level(&level, Default::default());
+
+        simulation.step_frame(std::iter::once(ReplayInput::PickUp {
+            position: Vec2::ZERO,
+        }));
+        assert!(simulation.shapes[0].state.is_dragged());
+
+        simulation.step_frame(std::iter::once(ReplayInput::Drop));
+        assert_eq!(
+            simulation.shapes[0].state,
+            SimShapeState::Placed(ShapeState::Normal)
+        );
+    }
+
+    #[test]
+    pub fn test_shapes_without_location_do_not_overlap() {
+        let mut level = DesignedLevel::default();
+        for _ in 0..5 {
+            level.initial_stage.shapes.push(ShapeCreation {
+                shape: LevelShapeForm::I4,
+                ..Default::default()
+            });
+        }
+
+        let simulation = Simulation::from_level(&level, Default::default());
+        let shapes = simulation.shapes_vec();
+
+        for (i, a) in shapes.iter().enumerate() {
+            for b in shapes.iter().skip(i + 1) {
+                assert_ne!(a.location.position, b.location.position);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_shapes_without_location_are_placed_by_seed() {
+        let mut level = DesignedLevel::default();
+        level.initial_stage.shapes.push(ShapeCreation {
+            shape: LevelShapeForm::T4,
+            ..Default::default()
+        });
+
+        let place = |seed: u64| {
+            let settings = SimulationSettings {
+                seed,
+                ..Default::default()
+            };
+            Simulation::from_level(&level, settings).shapes_vec()[0].location
+        };
+
+        assert_eq!(place(1), place(1));
+        assert_ne!(place(1), place(2));
+    }
+
+    #[test]
+    pub fn test_moving_shape_follows_path() {
+        let mut level = DesignedLevel::default();
+        level.initial_stage.shapes.push(ShapeCreation {
+            shape: LevelShapeForm::O4,
+            x: Some(0.0),
+            y: Some(-100.0),
+            state: ShapeState::Fixed,
+            motion: Some(ShapeMotion {
+                dx: 100.0,
+                period: 2.0,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        let mut simulation = Simulation::from_level(&level, Default::default());
+
+        for _ in 0..FRAMES_PER_SECOND {
+            simulation.step_frame(std::iter::empty());
+        }
+
+        let location = simulation.world.location(simulation.shapes[0].body);
+        assert!((location.position.x - 100.0).abs() < 0.1);
+        assert!((location.position.y + 100.0).abs() < 0.1);
+    }
+
+    #[test]
+    pub fn test_force_zone_pushes_shapes() {
+        let mut level = single_shape_level();
+        level.initial_stage.zones.push(ForceZone {
+            width: 200.0,
+            height: 200.0,
+            strength: 500.0,
+            ..Default::default()
+        });
+        let mut simulation = Simulation::from_level(&level, Default::default());
+
+        for _ in 0..10 {
+            simulation.step_frame(std::iter::empty());
+        }
+
+        let location = simulation.world.location(simulation.shapes[0].body);
+        assert!(location.position.x > 1.0);
+    }
+
+    #[test]
+    pub fn test_shapes_float_in_water() {
+        let mut level = single_shape_level();
+        level.initial_stage.shapes[0].y = Some(-100.0);
+        level.initial_stage.water = Some(WaterSettings {
+            surface: 0.0,
+            ..Default::default()
+        });
+        let mut simulation = Simulation::from_level(&level, Default::default());
+
+        for _ in 0..30 {
+            simulation.step_frame(std::iter::empty());
+        }
+
+        let location = simulation.world.location(simulation.shapes[0].body);
+        assert!(location.position.y > -100.0);
+    }
+}
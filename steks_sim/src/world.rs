@@ -0,0 +1,521 @@
+use std::collections::HashMap;
+
+use bevy::prelude::Vec2;
+use bevy_rapier2d::rapier::prelude as rapier;
+use bevy_rapier2d::rapier::prelude::{
+    ColliderHandle, EventHandler, InteractionGroups, Real, RigidBodyHandle, Rotation, Vector,
+};
+use steks_common::prelude::*;
+
+/// How a shape is currently behaving in the physics world
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimShapeState {
+    Placed(ShapeState),
+    Dragged {
+        offset: Vec2,
+        desired_position: Vec2,
+    },
+}
+
+impl SimShapeState {
+    pub fn shape_state(&self) -> ShapeState {
+        match self {
+            SimShapeState::Placed(state) => *state,
+            SimShapeState::Dragged { .. } => ShapeState::Normal,
+        }
+    }
+
+    pub fn is_dragged(&self) -> bool {
+        matches!(self, SimShapeState::Dragged { .. })
+    }
+
+    fn locks_rotation(&self) -> bool {
+        !matches!(self, SimShapeState::Placed(ShapeState::Normal))
+    }
+
+    fn locks_translation(&self) -> bool {
+        !matches!(
+            self,
+            SimShapeState::Placed(ShapeState::Normal) | SimShapeState::Dragged { .. }
+        )
+    }
+
+    fn gravity_scale(&self) -> Real {
+        match self {
+            SimShapeState::Placed(ShapeState::Normal) => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    fn dominance(&self) -> i8 {
+        match self {
+            SimShapeState::Placed(ShapeState::Normal) | SimShapeState::Dragged { .. } => 0,
+            _ => 10,
+        }
+    }
+
+    fn density(&self, modifiers: ShapeModifiers) -> Real {
+        match self {
+            SimShapeState::Dragged { .. } => DRAGGED_DENSITY,
+            _ => modifiers.density(),
+        }
+    }
+
+    fn restitution(&self, modifiers: ShapeModifiers) -> (Real, rapier::CoefficientCombineRule) {
+        match self {
+            SimShapeState::Dragged { .. } => (0.0, rapier::CoefficientCombineRule::Min),
+            _ => {
+                let restitution = modifiers.restitution();
+                (restitution.coefficient, restitution.combine_rule)
+            }
+        }
+    }
+
+    fn collision_groups(&self) -> InteractionGroups {
+        let (memberships, filters) = match self {
+            SimShapeState::Placed(ShapeState::Void) => {
+                (VOID_COLLISION_GROUP, VOID_COLLISION_FILTERS)
+            }
+            SimShapeState::Dragged { .. } => {
+                (SHAPE_COLLISION_GROUP, DRAGGED_SHAPE_COLLISION_FILTERS)
+            }
+            SimShapeState::Placed(_) => (SHAPE_COLLISION_GROUP, SHAPE_COLLISION_FILTERS),
+        };
+
+        interaction_groups(memberships.bits(), filters.bits())
+    }
+
+    fn is_sensor(&self) -> bool {
+        matches!(self, SimShapeState::Placed(ShapeState::Void))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimShape {
+    pub shape: &'static GameShape,
+    pub state: SimShapeState,
+    pub modifiers: ShapeModifiers,
+    pub id: Option<u32>,
+    pub stage: usize,
+    pub body: RigidBodyHandle,
+    pub collider: ColliderHandle,
+}
+
+/// A fixed shape following its motion path. This mirrors `MovingShape` in `steks_base`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovingBody {
+    pub body: RigidBodyHandle,
+    pub motion: ShapeMotion,
+    pub origin: Location,
+    pub frame: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wall {
+    pub body: RigidBodyHandle,
+    pub collider: ColliderHandle,
+    pub sensor: ColliderHandle,
+}
+
+/// The rapier world without any of the bevy wrapping.
+/// All positions and velocities going in and out are in pixels, like the rest of the game.
+#[derive(Clone)]
+pub struct PhysicsWorld {
+    pub gravity: Vec2,
+    pub integration_parameters: rapier::IntegrationParameters,
+    pub islands: rapier::IslandManager,
+    pub broad_phase: rapier::BroadPhase,
+    pub narrow_phase: rapier::NarrowPhase,
+    pub bodies: rapier::RigidBodySet,
+    pub colliders: rapier::ColliderSet,
+    pub impulse_joints: rapier::ImpulseJointSet,
+    pub multibody_joints: rapier::MultibodyJointSet,
+    pub ccd_solver: rapier::CCDSolver,
+    pub moving_bodies: Vec<MovingBody>,
+    pub force_zones: Vec<ForceZone>,
+    /// Physics frames since the force zones appeared
+    pub zone_frame: u32,
+    pub water: Option<WaterSettings>,
+    /// The bodies pushed by the force zones and the water
+    pub free_bodies: Vec<RigidBodyHandle>,
+    /// The outline of each shape, for working out how much of it is under water
+    pub vertices: HashMap<RigidBodyHandle, Vec<Vec2>>,
+}
+
+impl PhysicsWorld {
+    pub fn new(gravity: Vec2) -> Self {
+        let integration_parameters = rapier::IntegrationParameters {
+            dt: SECONDS_PER_FRAME as Real,
+            ..Default::default()
+        };
+
+        Self {
+            gravity,
+            integration_parameters,
+            islands: rapier::IslandManager::new(),
+            broad_phase: rapier::BroadPhase::new(),
+            narrow_phase: rapier::NarrowPhase::new(),
+            bodies: rapier::RigidBodySet::new(),
+            colliders: rapier::ColliderSet::new(),
+            impulse_joints: rapier::ImpulseJointSet::new(),
+            multibody_joints: rapier::MultibodyJointSet::new(),
+            ccd_solver: rapier::CCDSolver::new(),
+            moving_bodies: vec![],
+            force_zones: vec![],
+            zone_frame: 0,
+            water: None,
+            free_bodies: vec![],
+            vertices: Default::default(),
+        }
+    }
+
+    pub fn step(&mut self, pipeline: &mut rapier::PhysicsPipeline, events: &dyn EventHandler) {
+        self.drive_moving_bodies();
+        self.push_free_bodies();
+        pipeline.step(
+            &to_physics(self.gravity),
+            &self.integration_parameters,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd_solver,
+            None,
+            &(),
+            events,
+        );
+    }
+
+    pub fn add_wall(&mut self, position: Vec2, extents: Vec2) -> Wall {
+        let body = self.bodies.insert(
+            rapier::RigidBodyBuilder::fixed()
+                .translation(to_physics(position))
+                .build(),
+        );
+        let groups = interaction_groups(WALL_COLLISION_GROUP.bits(), WALL_COLLISION_FILTERS.bits());
+        let half_extents = extents * 0.5 / PHYSICS_SCALE;
+
+        let collider = self.colliders.insert_with_parent(
+            rapier::ColliderBuilder::cuboid(half_extents.x, half_extents.y)
+                .restitution(DEFAULT_RESTITUTION)
+                .restitution_combine_rule(rapier::CoefficientCombineRule::Min)
+                .collision_groups(groups)
+                .build(),
+            body,
+            &mut self.bodies,
+        );
+
+        let sensor = self.colliders.insert_with_parent(
+            rapier::ColliderBuilder::cuboid(half_extents.x, half_extents.y)
+                .sensor(true)
+                .active_events(rapier::ActiveEvents::COLLISION_EVENTS)
+                .collision_groups(groups)
+                .build(),
+            body,
+            &mut self.bodies,
+        );
+
+        Wall {
+            body,
+            collider,
+            sensor,
+        }
+    }
+
+    /// Add a shape to the world. This mirrors `create_shape` in `steks_base`
+    pub fn add_shape(
+        &mut self,
+        shape: &'static GameShape,
+        location: Location,
+        velocity: Vec2,
+        state: ShapeState,
+        modifiers: ShapeModifiers,
+    ) -> (RigidBodyHandle, ColliderHandle) {
+        let sim_state = SimShapeState::Placed(state);
+        let velocity = if sim_state.locks_translation() {
+            Vec2::ZERO
+        } else {
+            velocity
+        };
+        let body = self.bodies.insert(
+            rapier::RigidBodyBuilder::dynamic()
+                .translation(to_physics(location.position))
+                .rotation(location.angle)
+                .linvel(to_physics(velocity))
+                .ccd_enabled(true)
+                .can_sleep(false)
+                .build(),
+        );
+
+        let collider = self.insert_collider(shape, modifiers, sim_state, body);
+        self.set_state(body, collider, sim_state, modifiers);
+
+        (body, collider)
+    }
+
+    fn insert_collider(
+        &mut self,
+        shape: &'static GameShape,
+        modifiers: ShapeModifiers,
+        state: SimShapeState,
+        body: RigidBodyHandle,
+    ) -> ColliderHandle {
+        self.vertices
+            .insert(body, shape.body.get_vertices(SHAPE_SIZE));
+        let mut collider_shape = shape.body.to_collider_shape(SHAPE_SIZE);
+        collider_shape.set_scale(Vec2::splat(PHYSICS_SCALE.recip()), 10);
+        let friction = modifiers.friction();
+        let (restitution, restitution_combine_rule) = state.restitution(modifiers);
+
+        self.colliders.insert_with_parent(
+            rapier::ColliderBuilder::new(collider_shape.raw.clone())
+                .friction(friction.coefficient)
+                .friction_combine_rule(friction.combine_rule)
+                .restitution(restitution)
+                .restitution_combine_rule(restitution_combine_rule)
+                .density(state.density(modifiers))
+                .build(),
+            body,
+            &mut self.bodies,
+        )
+    }
+
+    /// Swap the collider of a shape when its form or modifiers change
+    pub fn replace_collider(
+        &mut self,
+        body: RigidBodyHandle,
+        collider: ColliderHandle,
+        shape: &'static GameShape,
+        modifiers: ShapeModifiers,
+        state: SimShapeState,
+    ) -> ColliderHandle {
+        self.colliders
+            .remove(collider, &mut self.islands, &mut self.bodies, true);
+        let collider = self.insert_collider(shape, modifiers, state, body);
+        self.set_state(body, collider, state, modifiers);
+        collider
+    }
+
+    /// Apply the physical properties of a state. This mirrors `handle_drag_changes` in `steks_base`
+    pub fn set_state(
+        &mut self,
+        body: RigidBodyHandle,
+        collider: ColliderHandle,
+        state: SimShapeState,
+        modifiers: ShapeModifiers,
+    ) {
+        if let Some(rb) = self.bodies.get_mut(body) {
+            rb.lock_rotations(state.locks_rotation(), true);
+            rb.lock_translations(state.locks_translation(), true);
+            rb.set_gravity_scale(state.gravity_scale(), true);
+            rb.set_dominance_group(state.dominance());
+            rb.reset_forces(true);
+            if state != SimShapeState::Placed(ShapeState::Normal) {
+                rb.set_linvel(Vector::zeros(), true);
+                rb.set_angvel(0.0, true);
+            }
+        }
+
+        self.free_bodies.retain(|x| *x != body);
+        if state == SimShapeState::Placed(ShapeState::Normal) {
+            self.free_bodies.push(body);
+        }
+
+        if let Some(c) = self.colliders.get_mut(collider) {
+            let (restitution, restitution_combine_rule) = state.restitution(modifiers);
+            c.set_density(state.density(modifiers));
+            c.set_restitution(restitution);
+            c.set_restitution_combine_rule(restitution_combine_rule);
+            c.set_collision_groups(state.collision_groups());
+            c.set_sensor(state.is_sensor());
+            c.set_active_events(if state.is_sensor() {
+                rapier::ActiveEvents::COLLISION_EVENTS
+            } else {
+                rapier::ActiveEvents::empty()
+            });
+        }
+    }
+
+    /// Make a shape follow a motion path. This mirrors `create_shape` in `steks_base`
+    pub fn start_motion(&mut self, body: RigidBodyHandle, motion: ShapeMotion, origin: Location) {
+        if let Some(rb) = self.bodies.get_mut(body) {
+            rb.set_body_type(rapier::RigidBodyType::KinematicPositionBased, true);
+        }
+        self.moving_bodies.push(MovingBody {
+            body,
+            motion,
+            origin,
+            frame: 0,
+        });
+    }
+
+    /// Stop a shape following its motion path. This mirrors `update_shape` in `steks_base`
+    pub fn stop_motion(&mut self, body: RigidBodyHandle) {
+        if !self.moving_bodies.iter().any(|x| x.body == body) {
+            return;
+        }
+        self.moving_bodies.retain(|x| x.body != body);
+        if let Some(rb) = self.bodies.get_mut(body) {
+            rb.set_body_type(rapier::RigidBodyType::Dynamic, true);
+        }
+    }
+
+    /// Move each moving shape one frame along its path. This mirrors `drive_moving_shapes` in `steks_base`
+    fn drive_moving_bodies(&mut self) {
+        for moving in self.moving_bodies.iter_mut() {
+            moving.frame += 1;
+            let Location { position, angle } = moving.motion.location(moving.origin, moving.frame);
+            if let Some(rb) = self.bodies.get_mut(moving.body) {
+                rb.set_next_kinematic_position(rapier::Isometry::new(to_physics(position), angle));
+            }
+        }
+    }
+
+    /// Replace the force zones. This mirrors `manage_force_zones` in `steks_base`
+    pub fn set_force_zones(&mut self, zones: Vec<ForceZone>) {
+        if zones == self.force_zones {
+            return;
+        }
+        self.force_zones = zones;
+        self.zone_frame = 0;
+        for body in self.free_bodies.iter() {
+            if let Some(rb) = self.bodies.get_mut(*body) {
+                rb.reset_forces(true);
+            }
+        }
+    }
+
+    /// Push the free bodies inside the force zones and the water. This mirrors `push_free_shapes` in `steks_base`
+    fn push_free_bodies(&mut self) {
+        if self.force_zones.is_empty() && self.water.is_none() {
+            return;
+        }
+        self.zone_frame += 1;
+
+        for body in self.free_bodies.iter() {
+            let Some(rb) = self.bodies.get_mut(*body) else {
+                continue;
+            };
+            let location = Location {
+                position: from_physics(rb.translation()),
+                angle: rb.rotation().angle(),
+            };
+            let water_force = match (self.water, self.vertices.get(body)) {
+                (Some(water), Some(vertices)) => water.force_on(
+                    vertices,
+                    &location,
+                    from_physics(rb.linvel()),
+                    rb.angvel(),
+                    self.gravity,
+                ),
+                _ => WaterForce::default(),
+            };
+            let force =
+                ForceZone::total_force(&self.force_zones, location.position, self.zone_frame)
+                    + water_force.force;
+
+            rb.reset_forces(false);
+            rb.add_force(to_physics(force), false);
+            rb.add_torque(water_force.torque / (PHYSICS_SCALE * PHYSICS_SCALE), true);
+        }
+    }
+
+    /// Join two shapes. This mirrors `create_pending_joints` in `steks_base`
+    pub fn add_joint(&mut self, a: RigidBodyHandle, b: RigidBodyHandle, joint: &ShapeJoint) {
+        let location_a = self.location(a);
+        let location_b = self.location(b);
+        let anchor_a = rapier::Point::from(to_physics(joint.anchor_a));
+        let anchor_b = rapier::Point::from(to_physics(joint.anchor_b));
+
+        let generic: rapier::GenericJoint = match joint.kind {
+            JointKind::Revolute => rapier::RevoluteJointBuilder::new()
+                .local_anchor1(anchor_a)
+                .local_anchor2(anchor_b)
+                .into(),
+            JointKind::Fixed => rapier::FixedJointBuilder::new()
+                .local_frame1(rapier::Isometry::new(anchor_a.coords, 0.0))
+                .local_frame2(rapier::Isometry::new(
+                    anchor_b.coords,
+                    location_a.angle - location_b.angle,
+                ))
+                .into(),
+            JointKind::Rope => rapier::RopeJointBuilder::new(
+                joint.rope_length(&location_a, &location_b) / PHYSICS_SCALE,
+            )
+            .local_anchor1(anchor_a)
+            .local_anchor2(anchor_b)
+            .into(),
+        };
+
+        self.impulse_joints.insert(a, b, generic, true);
+    }
+
+    pub fn location(&self, body: RigidBodyHandle) -> Location {
+        let rb = &self.bodies[body];
+        Location {
+            position: from_physics(rb.translation()),
+            angle: rb.rotation().angle(),
+        }
+    }
+
+    pub fn set_location(&mut self, body: RigidBodyHandle, location: Location) {
+        if let Some(rb) = self.bodies.get_mut(body) {
+            rb.set_translation(to_physics(location.position), true);
+            rb.set_rotation(Rotation::new(location.angle), true);
+        }
+    }
+
+    pub fn velocity(&self, body: RigidBodyHandle) -> Vec2 {
+        from_physics(self.bodies[body].linvel())
+    }
+
+    pub fn set_velocity(&mut self, body: RigidBodyHandle, velocity: Vec2) {
+        if let Some(rb) = self.bodies.get_mut(body) {
+            rb.set_linvel(to_physics(velocity), true);
+        }
+    }
+
+    pub fn set_angular_velocity(&mut self, body: RigidBodyHandle, angvel: Real) {
+        if let Some(rb) = self.bodies.get_mut(body) {
+            rb.set_angvel(angvel, true);
+        }
+    }
+
+    /// Set the force on a body, in pixel units. This mirrors bevy_rapier's `ExternalForce`
+    pub fn set_force(&mut self, body: RigidBodyHandle, force: Vec2) {
+        if let Some(rb) = self.bodies.get_mut(body) {
+            rb.reset_forces(false);
+            rb.add_force(to_physics(force), true);
+        }
+    }
+
+    pub fn has_active_contacts(&self, collider: ColliderHandle) -> bool {
+        self.narrow_phase
+            .contacts_with(collider)
+            .any(|contact| contact.has_any_active_contact)
+    }
+
+    pub fn has_intersections(&self, collider: ColliderHandle) -> bool {
+        self.narrow_phase
+            .intersections_with(collider)
+            .any(|(_, _, intersecting)| intersecting)
+    }
+}
+
+pub fn to_physics(v: Vec2) -> Vector<Real> {
+    Vector::new(v.x / PHYSICS_SCALE, v.y / PHYSICS_SCALE)
+}
+
+pub fn from_physics(v: &Vector<Real>) -> Vec2 {
+    Vec2::new(v.x * PHYSICS_SCALE, v.y * PHYSICS_SCALE)
+}
+
+fn interaction_groups(memberships: u32, filters: u32) -> InteractionGroups {
+    InteractionGroups::new(
+        rapier::Group::from_bits_truncate(memberships),
+        rapier::Group::from_bits_truncate(filters),
+    )
+}